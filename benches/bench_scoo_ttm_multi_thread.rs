@@ -1,4 +1,4 @@
-#![cfg(test)]
+#![cfg(all(test, feature = "nightly"))]
 #![feature(test)]
 
 extern crate test;
@@ -1,4 +1,4 @@
-#![cfg(test)]
+#![cfg(all(test, feature = "nightly"))]
 #![feature(test)]
 
 extern crate test;
@@ -35,3 +35,14 @@ bench_tensor_io!(bench_load_3d_12031, "data/tensors/3D_12031.tns");
 bench_tensor_io!(bench_load_3d_dense, "data/tensors/3d_dense.tns");
 bench_tensor_io!(bench_load_3d_24, "data/tensors/3d-24.tns");
 bench_tensor_io!(bench_load_4d_3_16, "data/tensors/4d_3_16.tns");
+
+#[bench]
+fn bench_binary_3d_12031(b: &mut Bencher) {
+    let mut input_file = File::open("data/tensors/3D_12031.tns").unwrap();
+    let tensor = tensor::COOTensor::<u32, f32>::read_from_text(&mut input_file).unwrap();
+    b.iter(|| {
+        let mut buffer = Vec::new();
+        tensor.write_to_binary(&mut buffer).unwrap();
+        tensor::COOTensor::<u32, f32>::read_from_binary(&mut buffer.as_slice()).unwrap()
+    });
+}
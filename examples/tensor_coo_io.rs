@@ -1,6 +1,7 @@
 use anyhow::Result;
 use clap::Parser;
 use log::info;
+use pattie::io::Compression;
 use pattie::structs::axis::axes_to_string;
 use pattie::structs::tensor::COOTensor;
 use pattie::traits::Tensor;
@@ -15,7 +16,7 @@ struct Args {
     #[clap(short, long)]
     input: OsString,
 
-    /// Output tensor file
+    /// Output tensor file, compressed if it ends with `.gz`, `.zst` or `.xz`
     #[clap(short, long)]
     output: Option<OsString>,
 }
@@ -38,9 +39,10 @@ fn main() -> Result<()> {
 
     if let Some(output_filename) = args.output {
         info!("Writing tensor to {}", output_filename.to_string_lossy());
-        let mut output_file = File::create(output_filename)?;
+        let compression = Compression::from_path(&output_filename);
+        let mut output_file = compression.writer(File::create(output_filename)?)?;
         tensor.write_to_text(&mut output_file)?;
-        drop(output_file);
+        output_file.finish()?;
     }
 
     Ok(())
@@ -10,7 +10,6 @@ use pattie::traits::Tensor;
 use pattie::utils::hint::black_box;
 use pattie::utils::logger;
 use pattie::utils::tracer::Tracer;
-use rayon;
 use std::ffi::OsString;
 use std::fs::File;
 use std::iter;
@@ -44,6 +43,7 @@ struct Args {
     multi_thread: bool,
 }
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(clap::ValueEnum, Copy, Clone, Debug)]
 enum Algo {
     /// COO tensor times matrix multiplication
@@ -95,7 +95,9 @@ fn main() -> Result<()> {
         .cloned()
         .collect::<Vec<_>>();
     info!("Sorting tensor by    {}", axes_to_string(&sort_order));
-    SortCOOTensor::new(&mut tensor, &sort_order).execute();
+    let mut sort_task = SortCOOTensor::new(&mut tensor, &sort_order).trace(&tracer);
+    sort_task.multi_thread = args.multi_thread;
+    sort_task.execute();
 
     info!(
         "Warming up... Number of threads: {}",
@@ -0,0 +1,290 @@
+use crate::algos::matrix::CreateRandomDenseMatrix;
+use crate::algos::tensor_matrix::COOTensorMTTKRP;
+use crate::structs::axis::AxisBuilder;
+use crate::structs::tensor::{COOTensor, KruskalTensor, KruskalTensorInner};
+use crate::traits::{IdxType, RawParts, ValType};
+use crate::utils::tracer::Tracer;
+use anyhow::{bail, Result};
+use log::info;
+use ndarray::{Array1, Array2, ArrayView2, Ix3};
+use ndarray_rand::rand_distr::{Distribution, StandardNormal};
+use num::{Float, NumCast};
+use scopeguard::defer;
+
+/// CANDECOMP/PARAFAC decomposition of a `COOTensor` by alternating least squares.
+///
+/// The result is a `KruskalTensor` with `rank` components.
+pub struct COOTensorCPALS<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    pub tensor: &'a COOTensor<IT, VT>,
+    pub rank: usize,
+    pub max_iterations: usize,
+    /// Stop when the fit changes less than `tolerance` in one iteration.
+    pub tolerance: VT,
+
+    pub tracer: Tracer,
+    pub multi_thread: bool,
+}
+
+impl<'a, IT, VT> COOTensorCPALS<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType + Float + 'static,
+    StandardNormal: Distribution<VT>,
+{
+    /// Create a new `COOTensorCPALS` task.
+    #[must_use]
+    pub fn new(
+        tensor: &'a COOTensor<IT, VT>,
+        rank: usize,
+        max_iterations: usize,
+        tolerance: VT,
+    ) -> Self {
+        Self {
+            tensor,
+            rank,
+            max_iterations,
+            tolerance,
+            tracer: Tracer::new_dummy(),
+            multi_thread: false,
+        }
+    }
+
+    /// Enable performance tracing.
+    #[must_use]
+    pub fn trace(mut self, tracer: &Tracer) -> Self {
+        self.tracer.clone_from(tracer);
+        self
+    }
+
+    /// Perform the decomposition.
+    ///
+    /// The tensor must be fully sparse.
+    /// Factor matrices are initialized with random values.
+    pub fn execute(self) -> Result<KruskalTensor<IT, VT>> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("COOTensorCPALS");
+        }
+
+        if !self.tensor.dense_axes().is_empty() {
+            bail!("The tensor must be fully sparse.");
+        }
+        if self.rank == 0 {
+            bail!("The rank must be positive.");
+        }
+        let shape = self.tensor.sparse_axes();
+        let rank_axis = AxisBuilder::new()
+            .range(IT::zero()..<IT as NumCast>::from(self.rank).unwrap())
+            .build();
+
+        let mut factors = shape
+            .iter()
+            .map(|axis| {
+                CreateRandomDenseMatrix::new(
+                    (axis.clone(), rank_axis.clone()),
+                    VT::zero(),
+                    VT::one(),
+                )
+                .execute()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut grams = factors
+            .iter()
+            .map(|factor| gram_matrix(&factor_values(factor)))
+            .collect::<Vec<_>>();
+        let mut weights = Array1::<VT>::ones(self.rank);
+
+        let tensor_norm = self
+            .tensor
+            .raw_parts()
+            .values
+            .iter()
+            .fold(VT::zero(), |acc, &value| acc + value * value)
+            .sqrt();
+        let mut fit = VT::zero();
+
+        for iteration in 0..self.max_iterations {
+            let event = self.tracer.start();
+
+            let mut mttkrp_result = Array2::zeros((0, 0));
+            for (axis_idx, axis) in shape.iter().enumerate() {
+                let mut task =
+                    COOTensorMTTKRP::new(self.tensor, &factors, axis).trace(&self.tracer);
+                task.multi_thread = self.multi_thread;
+                let mttkrp = task.execute()?;
+                mttkrp_result = factor_values(&mttkrp).to_owned();
+
+                // Solve factor * gram = mttkrp, where gram is the Hadamard product of all other Gram matrices.
+                let mut gram = Array2::<VT>::ones((self.rank, self.rank));
+                for (other_idx, other_gram) in grams.iter().enumerate() {
+                    if other_idx != axis_idx {
+                        gram.zip_mut_with(other_gram, |a, &b| *a = *a * b);
+                    }
+                }
+                let mut factor = self.solve(gram, mttkrp_result.view())?;
+
+                // Normalize the columns into the weights.
+                for (r, mut column) in factor.columns_mut().into_iter().enumerate() {
+                    let norm = column
+                        .iter()
+                        .fold(VT::zero(), |acc, &value| acc + value * value)
+                        .sqrt();
+                    let norm = if norm > VT::zero() { norm } else { VT::one() };
+                    column.mapv_inplace(|value| value / norm);
+                    weights[r] = norm;
+                }
+                grams[axis_idx] = gram_matrix(&factor.view());
+                // # Safety
+                // The shape of the factor is not changed.
+                unsafe {
+                    factors[axis_idx].raw_parts_mut().values =
+                        factor.insert_axis(ndarray::Axis(0)).into_dyn();
+                }
+            }
+
+            // Compute the fit from the Gram matrices and the last MTTKRP result,
+            // without reconstructing the whole Kruskal tensor.
+            let mut gram = Array2::<VT>::ones((self.rank, self.rank));
+            for other_gram in grams.iter() {
+                gram.zip_mut_with(other_gram, |a, &b| *a = *a * b);
+            }
+            let mut kruskal_norm_sq = VT::zero();
+            for ((r, s), &value) in gram.indexed_iter() {
+                kruskal_norm_sq = kruskal_norm_sq + weights[r] * value * weights[s];
+            }
+            let last_factor = factor_values(factors.last().unwrap());
+            let mut inner_product = VT::zero();
+            for (((_, r), &a), &b) in mttkrp_result.indexed_iter().zip(last_factor.iter()) {
+                inner_product = inner_product + a * b * weights[r];
+            }
+            let residual_norm_sq = tensor_norm * tensor_norm + kruskal_norm_sq
+                - (VT::one() + VT::one()) * inner_product;
+            let residual_norm = residual_norm_sq.max(VT::zero()).sqrt();
+            let fit_old = fit;
+            fit = if tensor_norm > VT::zero() {
+                VT::one() - residual_norm / tensor_norm
+            } else {
+                VT::one()
+            };
+            let fit_delta = (fit - fit_old).abs();
+
+            info!(target: "COOTensorCPALS", "Iteration {}: fit = {}, delta = {}", iteration + 1, fit, fit_delta);
+            event.finish(format!(
+                "COOTensorCPALS::iteration {} (fit {})",
+                iteration + 1,
+                fit
+            ));
+
+            if iteration > 0 && fit_delta < self.tolerance {
+                break;
+            }
+        }
+
+        let result = KruskalTensorInner {
+            name: None,
+            shape: shape.into(),
+            rank_axis,
+            weights,
+            factors,
+        };
+
+        Ok(
+            // # Safety
+            // We make sure the tensor is in valid state.
+            unsafe { KruskalTensor::from_raw_parts(result) },
+        )
+    }
+
+    /// Solve `x * gram = rhs` for `x`, where `gram` is symmetric.
+    ///
+    /// This uses Gaussian elimination with partial pivoting on the transposed system.
+    fn solve(&self, mut gram: Array2<VT>, rhs: ArrayView2<VT>) -> Result<Array2<VT>> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("COOTensorCPALS::solve");
+        }
+
+        let rank = gram.nrows();
+        let mut solution = rhs.t().to_owned();
+        for col in 0..rank {
+            // Without NaNs, the absolute values below are totally ordered.
+            if (col..rank).any(|row| gram[(row, col)].is_nan()) {
+                bail!("The Gram matrix contains NaN.");
+            }
+            let pivot = (col..rank)
+                .max_by(|&a, &b| {
+                    gram[(a, col)]
+                        .abs()
+                        .partial_cmp(&gram[(b, col)].abs())
+                        .unwrap()
+                })
+                .unwrap();
+            if gram[(pivot, col)] == VT::zero() {
+                bail!("The Gram matrix is singular.");
+            }
+            if pivot != col {
+                for k in 0..rank {
+                    gram.swap((pivot, k), (col, k));
+                }
+                for k in 0..solution.ncols() {
+                    solution.swap((pivot, k), (col, k));
+                }
+            }
+            for row in col + 1..rank {
+                let ratio = gram[(row, col)] / gram[(col, col)];
+                for k in col..rank {
+                    gram[(row, k)] = gram[(row, k)] - ratio * gram[(col, k)];
+                }
+                for k in 0..solution.ncols() {
+                    solution[(row, k)] = solution[(row, k)] - ratio * solution[(col, k)];
+                }
+            }
+        }
+        for col in (0..rank).rev() {
+            for k in 0..solution.ncols() {
+                let mut value = solution[(col, k)];
+                for j in col + 1..rank {
+                    value = value - gram[(col, j)] * solution[(j, k)];
+                }
+                solution[(col, k)] = value / gram[(col, col)];
+            }
+        }
+        Ok(solution.reversed_axes())
+    }
+}
+
+/// Compute `matrix^T * matrix`.
+pub(super) fn gram_matrix<VT>(matrix: &ArrayView2<VT>) -> Array2<VT>
+where
+    VT: ValType + Float,
+{
+    let rank = matrix.ncols();
+    let mut result = Array2::<VT>::zeros((rank, rank));
+    for row in matrix.rows() {
+        for (r, &a) in row.iter().enumerate() {
+            for (s, &b) in row.iter().enumerate() {
+                result[(r, s)] = result[(r, s)] + a * b;
+            }
+        }
+    }
+    result
+}
+
+/// View a dense matrix as an `ArrayView2`.
+pub(super) fn factor_values<IT, VT>(matrix: &COOTensor<IT, VT>) -> ArrayView2<'_, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    matrix
+        .raw_parts()
+        .values
+        .view()
+        .into_dimensionality::<Ix3>()
+        .unwrap()
+        .index_axis_move(ndarray::Axis(0), 0)
+}
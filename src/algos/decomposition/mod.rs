@@ -0,0 +1,7 @@
+//! Algorithms for tensor decompositions.
+
+mod cp_als;
+mod tucker_hooi;
+
+pub use cp_als::COOTensorCPALS;
+pub use tucker_hooi::COOTensorTuckerHOOI;
@@ -0,0 +1,474 @@
+use super::cp_als::{factor_values, gram_matrix};
+use crate::algos::matrix::CreateRandomDenseMatrix;
+use crate::algos::tensor::SortCOOTensor;
+use crate::algos::tensor_matrix::{COOTensorMulDenseMatrix, SemiCOOTensorMulDenseMatrix};
+use crate::structs::axis::{Axis, AxisBuilder};
+use crate::structs::tensor::{COOTensor, COOTensorInner, TuckerTensor, TuckerTensorInner};
+use crate::structs::vec::SmallVec;
+use crate::traits::{IdxType, RawParts, ValType};
+use crate::utils::tracer::Tracer;
+use anyhow::{bail, Result};
+use log::info;
+use ndarray::{Array1, Array2, IxDyn};
+use ndarray_rand::rand_distr::{Distribution, StandardNormal};
+use num::{Float, NumCast};
+use scopeguard::defer;
+use std::iter;
+
+/// Tucker decomposition of a `COOTensor` by higher-order orthogonal iteration (HOOI).
+///
+/// The result is a `TuckerTensor`, whose core tensor has `ranks[i]` elements along the `i`-th axis.
+pub struct COOTensorTuckerHOOI<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    pub tensor: &'a COOTensor<IT, VT>,
+    /// The rank of each axis, in the same order as the shape of the tensor.
+    pub ranks: &'a [usize],
+    pub max_iterations: usize,
+    /// Stop when the fit changes less than `tolerance` in one iteration.
+    pub tolerance: VT,
+
+    pub tracer: Tracer,
+    pub multi_thread: bool,
+}
+
+impl<'a, IT, VT> COOTensorTuckerHOOI<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType + Float + 'static,
+    StandardNormal: Distribution<VT>,
+{
+    /// Create a new `COOTensorTuckerHOOI` task.
+    #[must_use]
+    pub fn new(
+        tensor: &'a COOTensor<IT, VT>,
+        ranks: &'a [usize],
+        max_iterations: usize,
+        tolerance: VT,
+    ) -> Self {
+        Self {
+            tensor,
+            ranks,
+            max_iterations,
+            tolerance,
+            tracer: Tracer::new_dummy(),
+            multi_thread: false,
+        }
+    }
+
+    /// Enable performance tracing.
+    #[must_use]
+    pub fn trace(mut self, tracer: &Tracer) -> Self {
+        self.tracer.clone_from(tracer);
+        self
+    }
+
+    /// Perform the decomposition.
+    ///
+    /// The tensor must be fully sparse.
+    /// Factor matrices are initialized with random orthonormal columns.
+    pub fn execute(self) -> Result<TuckerTensor<IT, VT>> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("COOTensorTuckerHOOI");
+        }
+
+        if !self.tensor.dense_axes().is_empty() {
+            bail!("The tensor must be fully sparse.");
+        }
+        let shape = self.tensor.sparse_axes();
+        if shape.len() < 2 {
+            bail!("The tensor must have at least 2 axes.");
+        }
+        if self.ranks.len() != shape.len() {
+            bail!("The number of ranks must match the number of axes.");
+        }
+        if self
+            .ranks
+            .iter()
+            .zip(shape.iter())
+            .any(|(&rank, axis)| rank == 0 || rank > axis.len())
+        {
+            bail!("Each rank must be positive and no larger than its axis.");
+        }
+        if self.max_iterations == 0 {
+            bail!("The maximum number of iterations must be positive.");
+        }
+
+        let rank_axes = self
+            .ranks
+            .iter()
+            .map(|&rank| {
+                AxisBuilder::new()
+                    .range(IT::zero()..<IT as NumCast>::from(rank).unwrap())
+                    .build()
+            })
+            .collect::<SmallVec<_>>();
+        let mut factors = shape
+            .iter()
+            .zip(rank_axes.iter())
+            .map(|(axis, rank_axis)| {
+                let mut factor = CreateRandomDenseMatrix::new(
+                    (axis.clone(), rank_axis.clone()),
+                    VT::zero(),
+                    VT::one(),
+                )
+                .execute()?;
+                let mut factor_values = factor_values(&factor).to_owned();
+                orthonormalize_columns(&mut factor_values);
+                // # Safety
+                // The shape of the factor is not changed.
+                unsafe {
+                    factor.raw_parts_mut().values =
+                        factor_values.insert_axis(ndarray::Axis(0)).into_dyn();
+                }
+                Ok(factor)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let tensor_norm = self
+            .tensor
+            .raw_parts()
+            .values
+            .iter()
+            .fold(VT::zero(), |acc, &value| acc + value * value)
+            .sqrt();
+        let mut fit = VT::zero();
+        let mut core_values = Array2::zeros((0, 0));
+        let mut core_axes = SmallVec::new();
+
+        for iteration in 0..self.max_iterations {
+            let event = self.tracer.start();
+
+            for (axis_idx, rank) in self.ranks.iter().enumerate() {
+                let (unfolding, unfolding_axes) = self.ttm_chain(&factors, axis_idx)?;
+                let factor = self.leading_singular_vectors(&unfolding, *rank);
+
+                if axis_idx == shape.len() - 1 {
+                    // The core tensor is the last TTM chain multiplied by the last factor.
+                    core_values = Array2::zeros((*rank, unfolding.ncols()));
+                    for (row, factor_row) in unfolding.rows().into_iter().zip(factor.rows()) {
+                        for (r, &a) in factor_row.iter().enumerate() {
+                            for (p, &b) in row.iter().enumerate() {
+                                core_values[(r, p)] = core_values[(r, p)] + a * b;
+                            }
+                        }
+                    }
+                    core_axes = iter::once(rank_axes[axis_idx].clone())
+                        .chain(unfolding_axes)
+                        .collect();
+                }
+
+                // # Safety
+                // The shape of the factor is not changed.
+                unsafe {
+                    factors[axis_idx].raw_parts_mut().values =
+                        factor.insert_axis(ndarray::Axis(0)).into_dyn();
+                }
+            }
+
+            // The factors are orthonormal, so the norm of the residual only depends on the norm of the core tensor.
+            let core_norm_sq = core_values
+                .iter()
+                .fold(VT::zero(), |acc, &value| acc + value * value);
+            let residual_norm = (tensor_norm * tensor_norm - core_norm_sq)
+                .max(VT::zero())
+                .sqrt();
+            let fit_old = fit;
+            fit = if tensor_norm > VT::zero() {
+                VT::one() - residual_norm / tensor_norm
+            } else {
+                VT::one()
+            };
+            let fit_delta = (fit - fit_old).abs();
+
+            info!(target: "COOTensorTuckerHOOI", "Iteration {}: fit = {}, delta = {}", iteration + 1, fit, fit_delta);
+            event.finish(format!(
+                "COOTensorTuckerHOOI::iteration {} (fit {})",
+                iteration + 1,
+                fit
+            ));
+
+            if iteration > 0 && fit_delta < self.tolerance {
+                break;
+            }
+        }
+
+        // Reorder the axes of the core tensor to match the shape of the tensor.
+        let core_shape = core_axes.iter().map(Axis::len).collect::<Vec<_>>();
+        let permutation = rank_axes
+            .iter()
+            .map(|rank_axis| core_axes.iter().position(|ax| ax == rank_axis).unwrap())
+            .collect::<Vec<_>>();
+        let core_values = core_values
+            .into_shape(IxDyn(&core_shape))?
+            .permuted_axes(IxDyn(&permutation))
+            .as_standard_layout()
+            .into_owned();
+        let core = COOTensorInner {
+            name: None,
+            shape: rank_axes.clone(),
+            sparse_axes: SmallVec::new(),
+            dense_axes: rank_axes,
+            indices: Array2::zeros((1, 0)),
+            values: core_values.insert_axis(ndarray::Axis(0)),
+            sparse_is_sorted: true,
+            sparse_sort_order: SmallVec::new(),
+            sparse_is_coalesced: true,
+        };
+
+        let result = TuckerTensorInner {
+            name: None,
+            shape: shape.into(),
+            // # Safety
+            // We make sure the tensor is in valid state.
+            core: unsafe { COOTensor::from_raw_parts(core) },
+            factors,
+        };
+
+        Ok(
+            // # Safety
+            // We make sure the tensor is in valid state.
+            unsafe { TuckerTensor::from_raw_parts(result) },
+        )
+    }
+
+    /// Multiply the tensor by the factors of all axes except `axis_idx`.
+    ///
+    /// Returns the result unfolded along `axis_idx`, together with the axes of its columns.
+    fn ttm_chain(
+        &self,
+        factors: &[COOTensor<IT, VT>],
+        axis_idx: usize,
+    ) -> Result<(Array2<VT>, SmallVec<Axis<IT>>)> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("COOTensorTuckerHOOI::ttm_chain");
+        }
+
+        let shape = self.tensor.sparse_axes();
+        let mut other_axes = (0..shape.len()).filter(|&i| i != axis_idx);
+
+        // The first TTM turns the fully sparse tensor into a semi-sparse tensor.
+        let first_axis = other_axes.next().unwrap();
+        let mut tensor = self.tensor.clone();
+        let order = sort_order_ending_with(shape, &shape[first_axis]);
+        let mut task = SortCOOTensor::new(&mut tensor, &order).trace(&self.tracer);
+        task.multi_thread = self.multi_thread;
+        task.execute();
+        let mut task =
+            COOTensorMulDenseMatrix::new(&tensor, &factors[first_axis]).trace(&self.tracer);
+        task.multi_thread = self.multi_thread;
+        let mut tensor = task.execute()?;
+
+        for other_axis in other_axes {
+            let order =
+                sort_order_ending_with(tensor.sparse_sort_order().unwrap(), &shape[other_axis]);
+            let mut task = SortCOOTensor::new(&mut tensor, &order).trace(&self.tracer);
+            task.multi_thread = self.multi_thread;
+            task.execute();
+            let mut task =
+                SemiCOOTensorMulDenseMatrix::new(&tensor, &factors[other_axis]).trace(&self.tracer);
+            task.multi_thread = self.multi_thread;
+            tensor = task.execute()?;
+        }
+
+        // Only `axis_idx` is left sparse, accumulate the blocks into the rows of the unfolding.
+        let axis = &shape[axis_idx];
+        let num_columns = tensor.dense_axes().iter().map(Axis::len).product::<usize>();
+        let tensor_values = tensor
+            .raw_parts()
+            .values
+            .view()
+            .into_shape((tensor.num_blocks(), num_columns))?;
+        let mut unfolding = Array2::<VT>::zeros((axis.len(), num_columns));
+        for (&index, block) in tensor.raw_parts().indices.iter().zip(tensor_values.rows()) {
+            let offset = (index - axis.lower()).to_usize().unwrap();
+            unfolding
+                .row_mut(offset)
+                .zip_mut_with(&block, |a, &b| *a = *a + b);
+        }
+
+        Ok((unfolding, tensor.dense_axes().into()))
+    }
+
+    /// Compute the `rank` leading left singular vectors of `unfolding`.
+    ///
+    /// We take the eigenvectors of the smaller one of `unfolding * unfolding^T` and `unfolding^T * unfolding`.
+    /// The unfolding is tall when the product of the other ranks is small, then the eigenvectors are mapped back through `unfolding`.
+    fn leading_singular_vectors(&self, unfolding: &Array2<VT>, rank: usize) -> Array2<VT> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("COOTensorTuckerHOOI::leading_singular_vectors");
+        }
+
+        let is_wide = unfolding.nrows() < unfolding.ncols();
+        let (eigenvalues, eigenvectors) = if is_wide {
+            symmetric_eigen(gram_matrix(&unfolding.t()))
+        } else {
+            symmetric_eigen(gram_matrix(&unfolding.view()))
+        };
+        let largest = eigenvalues.first().cloned().unwrap_or_else(VT::zero);
+
+        let mut result = Array2::<VT>::zeros((unfolding.nrows(), rank));
+        for (k, (&eigenvalue, eigenvector)) in eigenvalues
+            .iter()
+            .zip(eigenvectors.columns())
+            .take(rank)
+            .enumerate()
+        {
+            // Columns for vanishing singular values are left zero and filled in by orthonormalization.
+            if eigenvalue <= largest * VT::epsilon() || eigenvalue <= VT::zero() {
+                continue;
+            }
+            if is_wide {
+                result.column_mut(k).assign(&eigenvector);
+                continue;
+            }
+            let singular_value = eigenvalue.sqrt();
+            for (value, row) in result.column_mut(k).iter_mut().zip(unfolding.rows()) {
+                *value = row
+                    .iter()
+                    .zip(eigenvector.iter())
+                    .fold(VT::zero(), |acc, (&a, &b)| acc + a * b)
+                    / singular_value;
+            }
+        }
+        orthonormalize_columns(&mut result);
+        result
+    }
+}
+
+/// Move `last` to the end of `order`, keeping the other axes in place.
+fn sort_order_ending_with<IT>(order: &[Axis<IT>], last: &Axis<IT>) -> SmallVec<Axis<IT>>
+where
+    IT: IdxType,
+{
+    order
+        .iter()
+        .filter(|&ax| ax != last)
+        .chain(iter::once(last))
+        .cloned()
+        .collect()
+}
+
+/// Make the columns of `matrix` orthonormal with modified Gram-Schmidt.
+///
+/// Columns that are zero or linearly dependent on previous ones are replaced by unit vectors.
+fn orthonormalize_columns<VT>(matrix: &mut Array2<VT>)
+where
+    VT: ValType + Float,
+{
+    let (num_rows, num_columns) = matrix.dim();
+    let threshold = VT::epsilon().sqrt();
+    for k in 0..num_columns {
+        let mut column = matrix.column(k).to_owned();
+        let mut norm = orthogonalize(matrix, k, &mut column);
+        if norm <= threshold {
+            // The average squared norm of a projected unit vector is (num_rows - k) / num_rows,
+            // so one of them must be at least half of it.
+            let min_norm_sq = <VT as NumCast>::from(num_rows - k).unwrap()
+                / <VT as NumCast>::from(2 * num_rows).unwrap();
+            for i in 0..num_rows {
+                column.fill(VT::zero());
+                column[i] = VT::one();
+                norm = orthogonalize(matrix, k, &mut column);
+                if norm * norm >= min_norm_sq {
+                    break;
+                }
+            }
+        }
+        column.mapv_inplace(|value| value / norm);
+        matrix.column_mut(k).assign(&column);
+    }
+}
+
+/// Remove the components of the first `k` columns of `matrix` from `column`, then return its norm.
+fn orthogonalize<VT>(matrix: &Array2<VT>, k: usize, column: &mut Array1<VT>) -> VT
+where
+    VT: ValType + Float,
+{
+    for prev in matrix.columns().into_iter().take(k) {
+        let projection = prev
+            .iter()
+            .zip(column.iter())
+            .fold(VT::zero(), |acc, (&a, &b)| acc + a * b);
+        column.zip_mut_with(&prev, |value, &p| *value = *value - projection * p);
+    }
+    column
+        .iter()
+        .fold(VT::zero(), |acc, &value| acc + value * value)
+        .sqrt()
+}
+
+/// Eigendecomposition of a symmetric matrix with the cyclic Jacobi method.
+///
+/// Returns the eigenvalues in descending order, and the corresponding eigenvectors as columns.
+fn symmetric_eigen<VT>(mut matrix: Array2<VT>) -> (Vec<VT>, Array2<VT>)
+where
+    VT: ValType + Float,
+{
+    const MAX_SWEEPS: usize = 64;
+
+    let n = matrix.nrows();
+    let mut eigenvectors = Array2::<VT>::eye(n);
+    let two = VT::one() + VT::one();
+    for _ in 0..MAX_SWEEPS {
+        let mut off_diagonal = VT::zero();
+        let mut diagonal = VT::zero();
+        for ((i, j), &value) in matrix.indexed_iter() {
+            if i == j {
+                diagonal = diagonal + value * value;
+            } else {
+                off_diagonal = off_diagonal + value * value;
+            }
+        }
+        if off_diagonal <= diagonal * VT::epsilon() * VT::epsilon() {
+            break;
+        }
+
+        for p in 0..n {
+            for q in p + 1..n {
+                let apq = matrix[(p, q)];
+                if apq == VT::zero() {
+                    continue;
+                }
+                let theta = (matrix[(q, q)] - matrix[(p, p)]) / (two * apq);
+                let t = if theta >= VT::zero() {
+                    VT::one()
+                } else {
+                    -VT::one()
+                } / (theta.abs() + (theta * theta + VT::one()).sqrt());
+                let c = VT::one() / (t * t + VT::one()).sqrt();
+                let s = t * c;
+                for k in 0..n {
+                    let (akp, akq) = (matrix[(k, p)], matrix[(k, q)]);
+                    matrix[(k, p)] = c * akp - s * akq;
+                    matrix[(k, q)] = s * akp + c * akq;
+                }
+                for k in 0..n {
+                    let (apk, aqk) = (matrix[(p, k)], matrix[(q, k)]);
+                    matrix[(p, k)] = c * apk - s * aqk;
+                    matrix[(q, k)] = s * apk + c * aqk;
+                }
+                for k in 0..n {
+                    let (vkp, vkq) = (eigenvectors[(k, p)], eigenvectors[(k, q)]);
+                    eigenvectors[(k, p)] = c * vkp - s * vkq;
+                    eigenvectors[(k, q)] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+
+    let mut order = (0..n).collect::<Vec<_>>();
+    order.sort_unstable_by(|&a, &b| {
+        matrix[(b, b)]
+            .partial_cmp(&matrix[(a, a)])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let eigenvalues = order.iter().map(|&i| matrix[(i, i)]).collect();
+    let eigenvectors = Array2::from_shape_fn((n, n), |(i, j)| eigenvectors[(i, order[j])]);
+    (eigenvalues, eigenvectors)
+}
@@ -54,6 +54,7 @@ where
             values: matrix.into_dyn(),
             sparse_is_sorted: true,
             sparse_sort_order: SmallVec::new(),
+            sparse_is_coalesced: true,
         };
         Ok(
             // # Safety
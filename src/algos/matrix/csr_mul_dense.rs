@@ -0,0 +1,185 @@
+use crate::structs::axis::Axis;
+use crate::structs::matrix::CSRMatrix;
+use crate::structs::tensor::{COOTensor, COOTensorInner};
+use crate::structs::vec::{smallvec, SmallVec};
+use crate::traits::{IdxType, RawParts, Tensor, ValType};
+use crate::utils::tracer::Tracer;
+use anyhow::{anyhow, bail, Result};
+use ndarray::{Array2, ArrayView2, ArrayViewMut1, Ix3};
+use rayon::prelude::*;
+use scopeguard::defer;
+
+/// Multiply a `CSRMatrix` with a `DenseMatrix` (SpMM).
+///
+/// The column axis of the sparse matrix is replaced by the other axis of the dense matrix.
+pub struct CSRMatrixMulDenseMatrix<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    pub matrix: &'a CSRMatrix<IT, VT>,
+    pub dense: &'a COOTensor<IT, VT>,
+
+    pub tracer: Tracer,
+    pub multi_thread: bool,
+}
+
+impl<'a, IT, VT> CSRMatrixMulDenseMatrix<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Create a new `CSRMatrixMulDenseMatrix` task.
+    #[must_use]
+    pub fn new(matrix: &'a CSRMatrix<IT, VT>, dense: &'a COOTensor<IT, VT>) -> Self {
+        Self {
+            matrix,
+            dense,
+            tracer: Tracer::new_dummy(),
+            multi_thread: false,
+        }
+    }
+
+    /// Enable performance tracing.
+    #[must_use]
+    pub fn trace(mut self, tracer: &Tracer) -> Self {
+        self.tracer.clone_from(tracer);
+        self
+    }
+
+    /// Perform the multiplication.
+    ///
+    /// One axis of the dense matrix must be the column axis of the sparse matrix.
+    /// The result is a fully dense matrix, with the row axis of the sparse matrix followed by the other axis of the dense matrix.
+    pub fn execute(self) -> Result<COOTensor<IT, VT>> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("CSRMatrixMulDenseMatrix");
+        }
+
+        // Check if the dense matrix has 2 axes.
+        if self.dense.ndim() != 2 {
+            bail!("The dense matrix must have 2 axes.");
+        }
+        // Check if the dense matrix is fully dense.
+        if !self.dense.sparse_axes().is_empty() {
+            bail!("The dense matrix must be fully dense.");
+        }
+
+        // Map the common axis of the sparse matrix and the dense matrix.
+        let col_axis = self.matrix.col_axis();
+        let dense_axes = self.dense.dense_axes();
+        let common_axis_index = dense_axes
+            .iter()
+            .position(|ax| ax == col_axis)
+            .ok_or_else(|| anyhow!("axis {} not found", col_axis))?;
+        let free_axis = &dense_axes[1 - common_axis_index];
+
+        // Reshape the dense matrix into an ArrayView2, with the common axis as rows.
+        let dense_values = self
+            .dense
+            .raw_parts()
+            .values
+            .view()
+            .into_dimensionality::<Ix3>()?
+            .index_axis_move(ndarray::Axis(0), 0);
+        let dense_values = if common_axis_index == 0 {
+            dense_values
+        } else {
+            dense_values.reversed_axes()
+        };
+
+        let row_axis = self.matrix.row_axis();
+        let mut result_values = Array2::zeros((row_axis.len(), free_axis.len()));
+        if self.multi_thread {
+            self.compute_values_multi_thread(&dense_values, &mut result_values, col_axis);
+        } else {
+            self.compute_values(&dense_values, &mut result_values, col_axis);
+        }
+        let result_values = result_values.insert_axis(ndarray::Axis(0)).into_dyn();
+
+        let result_shape: SmallVec<_> = smallvec![row_axis.clone(), free_axis.clone()];
+        let result = COOTensorInner {
+            name: None,
+            shape: result_shape.clone(),
+            sparse_axes: SmallVec::new(),
+            dense_axes: result_shape,
+            indices: Array2::zeros((1, 0)),
+            values: result_values,
+            sparse_is_sorted: true,
+            sparse_sort_order: SmallVec::new(),
+            sparse_is_coalesced: true,
+        };
+
+        Ok(
+            // # Safety
+            // We make sure the tensor is in valid state.
+            unsafe { COOTensor::from_raw_parts(result) },
+        )
+    }
+
+    fn compute_values(
+        &self,
+        dense_values: &ArrayView2<VT>,
+        result_values: &mut Array2<VT>,
+        col_axis: &Axis<IT>,
+    ) {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("CSRMatrixMulDenseMatrix::compute_values");
+        }
+
+        for (i, result_row) in result_values.outer_iter_mut().enumerate() {
+            accumulate_row(self.matrix, i, dense_values, result_row, col_axis);
+        }
+    }
+
+    fn compute_values_multi_thread(
+        &self,
+        dense_values: &ArrayView2<VT>,
+        result_values: &mut Array2<VT>,
+        col_axis: &Axis<IT>,
+    ) {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("CSRMatrixMulDenseMatrix::compute_values_multi_thread");
+        }
+
+        result_values
+            .outer_iter_mut()
+            .into_par_iter()
+            .with_min_len(256)
+            .enumerate()
+            .for_each(|(i, result_row)| {
+                accumulate_row(self.matrix, i, dense_values, result_row, col_axis);
+            });
+    }
+}
+
+/// Add the `i`-th row of `matrix` times `dense_values` into `result_row`.
+fn accumulate_row<IT, VT>(
+    matrix: &CSRMatrix<IT, VT>,
+    i: usize,
+    dense_values: &ArrayView2<VT>,
+    mut result_row: ArrayViewMut1<VT>,
+    col_axis: &Axis<IT>,
+) where
+    IT: IdxType,
+    VT: ValType,
+{
+    let (col_indices, values) = matrix.row(i);
+    let num_cols = result_row.len();
+    for (col, scale) in col_indices.iter().zip(values.iter()) {
+        // # Safety
+        // 0 <= c < col_axis.len() == dense_values.nrows()
+        let c = unsafe { (*col - col_axis.lower()).to_usize().unwrap_unchecked() };
+        for k in 0..num_cols {
+            // # Safety
+            // k < result_row.len() == dense_values.ncols()
+            unsafe {
+                let value = result_row.uget_mut(k);
+                *value = value.clone() + scale.clone() * dense_values.uget((c, k)).clone();
+            }
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use crate::structs::axis::Axis;
+use crate::structs::matrix::CSRMatrix;
+use crate::structs::tensor::{COOTensor, COOTensorInner};
+use crate::structs::vec::{smallvec, SmallVec};
+use crate::traits::{IdxType, RawParts, Tensor, ValType};
+use crate::utils::tracer::Tracer;
+use anyhow::{bail, Result};
+use ndarray::{Array1, Array2, ArrayView1, Ix2};
+use rayon::prelude::*;
+use scopeguard::defer;
+
+/// Multiply a `CSRMatrix` with a `DenseVector` (SpMV).
+///
+/// The column axis of the matrix is removed from the result.
+pub struct CSRMatrixMulDenseVector<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    pub matrix: &'a CSRMatrix<IT, VT>,
+    pub vector: &'a COOTensor<IT, VT>,
+
+    pub tracer: Tracer,
+    pub multi_thread: bool,
+}
+
+impl<'a, IT, VT> CSRMatrixMulDenseVector<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Create a new `CSRMatrixMulDenseVector` task.
+    #[must_use]
+    pub fn new(matrix: &'a CSRMatrix<IT, VT>, vector: &'a COOTensor<IT, VT>) -> Self {
+        Self {
+            matrix,
+            vector,
+            tracer: Tracer::new_dummy(),
+            multi_thread: false,
+        }
+    }
+
+    /// Enable performance tracing.
+    #[must_use]
+    pub fn trace(mut self, tracer: &Tracer) -> Self {
+        self.tracer.clone_from(tracer);
+        self
+    }
+
+    /// Perform the multiplication.
+    ///
+    /// The axis of the vector must be the column axis of the matrix.
+    /// The result is a fully dense vector along the row axis of the matrix.
+    pub fn execute(self) -> Result<COOTensor<IT, VT>> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("CSRMatrixMulDenseVector");
+        }
+
+        // Check if the vector has 1 axis.
+        if self.vector.ndim() != 1 {
+            bail!("The vector must have 1 axis.");
+        }
+        // Check if the vector is fully dense.
+        if !self.vector.sparse_axes().is_empty() {
+            bail!("The vector must be fully dense.");
+        }
+        let col_axis = self.matrix.col_axis();
+        if &self.vector.dense_axes()[0] != col_axis {
+            bail!("axis {} not found", col_axis);
+        }
+
+        // Reshape the vector into an ArrayView1.
+        let vector_values = self
+            .vector
+            .raw_parts()
+            .values
+            .view()
+            .into_dimensionality::<Ix2>()?
+            .index_axis_move(ndarray::Axis(0), 0);
+
+        let result_values = if self.multi_thread {
+            self.compute_values_multi_thread(&vector_values, col_axis)
+        } else {
+            self.compute_values(&vector_values, col_axis)
+        };
+        let num_rows = result_values.len();
+
+        let row_axis = self.matrix.row_axis();
+        let result = COOTensorInner {
+            name: None,
+            shape: smallvec![row_axis.clone()],
+            sparse_axes: SmallVec::new(),
+            dense_axes: smallvec![row_axis.clone()],
+            indices: Array2::zeros((1, 0)),
+            values: result_values.into_shape((1, num_rows))?.into_dyn(),
+            sparse_is_sorted: true,
+            sparse_sort_order: SmallVec::new(),
+            sparse_is_coalesced: true,
+        };
+
+        Ok(
+            // # Safety
+            // We make sure the tensor is in valid state.
+            unsafe { COOTensor::from_raw_parts(result) },
+        )
+    }
+
+    fn compute_values(&self, vector_values: &ArrayView1<VT>, col_axis: &Axis<IT>) -> Array1<VT> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("CSRMatrixMulDenseVector::compute_values");
+        }
+
+        let num_rows = self.matrix.row_axis().len();
+        (0..num_rows)
+            .map(|i| row_dot(self.matrix, i, vector_values, col_axis))
+            .collect()
+    }
+
+    fn compute_values_multi_thread(
+        &self,
+        vector_values: &ArrayView1<VT>,
+        col_axis: &Axis<IT>,
+    ) -> Array1<VT> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("CSRMatrixMulDenseVector::compute_values_multi_thread");
+        }
+
+        let num_rows = self.matrix.row_axis().len();
+        let result_values = (0..num_rows)
+            .into_par_iter()
+            .with_min_len(256)
+            .map(|i| row_dot(self.matrix, i, vector_values, col_axis))
+            .collect::<Vec<_>>();
+        Array1::from_vec(result_values)
+    }
+}
+
+/// The dot product of the `i`-th row of `matrix` and `vector_values`.
+fn row_dot<IT, VT>(
+    matrix: &CSRMatrix<IT, VT>,
+    i: usize,
+    vector_values: &ArrayView1<VT>,
+    col_axis: &Axis<IT>,
+) -> VT
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    let (col_indices, values) = matrix.row(i);
+    let mut sum = VT::zero();
+    for (col, value) in col_indices.iter().zip(values.iter()) {
+        // # Safety
+        // 0 <= c < col_axis.len() == vector_values.len()
+        let c = unsafe { (*col - col_axis.lower()).to_usize().unwrap_unchecked() };
+        sum = sum + value.clone() * unsafe { vector_values.uget(c) }.clone();
+    }
+    sum
+}
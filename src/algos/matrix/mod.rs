@@ -1,5 +1,9 @@
 //! Algorithms related to matrices.
 
 mod create_random_dense;
+mod csr_mul_dense;
+mod csr_mul_dense_vector;
 
 pub use create_random_dense::CreateRandomDenseMatrix;
+pub use csr_mul_dense::CSRMatrixMulDenseMatrix;
+pub use csr_mul_dense_vector::CSRMatrixMulDenseVector;
@@ -1,6 +1,9 @@
 //! Algorithms for sparse tensor operations.
 
+pub mod decomposition;
 pub mod matrix;
 pub mod tensor;
 pub mod tensor_matrix;
+pub mod tensor_tensor;
+pub mod tensor_vector;
 pub mod vector;
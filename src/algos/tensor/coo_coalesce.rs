@@ -0,0 +1,153 @@
+use super::SortCOOTensor;
+use crate::structs::axis::Axes;
+use crate::structs::tensor::COOTensor;
+use crate::traits::{IdxType, RawParts, ValType};
+use crate::utils::tracer::Tracer;
+use ndarray::{Array2, ArrayD};
+use scopeguard::defer;
+use std::iter;
+use std::mem;
+
+/// Merge blocks with duplicate sparse indices inside a `COOTensor`.
+///
+/// Blocks with the same sparse index are combined element by element with `reducer`, in their storage order.
+/// By default, duplicate blocks are summed.
+pub struct CoalesceCOOTensor<'a, IT, VT>
+where
+    IT: 'a + IdxType,
+    VT: 'a + ValType,
+{
+    pub tensor: &'a mut COOTensor<IT, VT>,
+    /// Combine the accumulated value with the next duplicate value.
+    pub reducer: fn(VT, VT) -> VT,
+    /// Remove blocks whose values are all zero after merging.
+    pub drop_zeros: bool,
+
+    pub tracer: Tracer,
+    pub multi_thread: bool,
+}
+
+impl<'a, IT, VT> CoalesceCOOTensor<'a, IT, VT>
+where
+    IT: 'a + IdxType,
+    VT: 'a + ValType,
+{
+    /// Create a new `CoalesceCOOTensor` task.
+    #[must_use]
+    pub fn new(tensor: &'a mut COOTensor<IT, VT>) -> Self {
+        Self {
+            tensor,
+            reducer: |acc, value| acc + value,
+            drop_zeros: false,
+            tracer: Tracer::new_dummy(),
+            multi_thread: false,
+        }
+    }
+
+    /// Merge duplicate blocks with a custom reducer.
+    #[must_use]
+    pub fn reducer(mut self, reducer: fn(VT, VT) -> VT) -> Self {
+        self.reducer = reducer;
+        self
+    }
+
+    /// Keep the last block among duplicate blocks.
+    #[must_use]
+    pub fn last(self) -> Self {
+        self.reducer(|_, value| value)
+    }
+
+    /// Keep the maximum value among duplicate blocks.
+    #[must_use]
+    pub fn max(self) -> Self
+    where
+        VT: PartialOrd,
+    {
+        self.reducer(|acc, value| if value > acc { value } else { acc })
+    }
+
+    /// Remove blocks whose values are all zero after merging.
+    #[must_use]
+    pub fn drop_zeros(mut self) -> Self {
+        self.drop_zeros = true;
+        self
+    }
+
+    /// Enable performance tracing.
+    #[must_use]
+    pub fn trace(mut self, tracer: &Tracer) -> Self {
+        self.tracer.clone_from(tracer);
+        self
+    }
+
+    /// Perform the merging.
+    ///
+    /// If the tensor is already sorted, its sort order is kept.
+    /// Otherwise, the tensor is sorted along its sparse axes first with [`SortCOOTensor`].
+    /// Afterwards, the tensor is marked as coalesced.
+    ///
+    /// # Allocation
+    /// This function requires `O(n)` auxiliary memory, where `n` is the number of non-zero elements.
+    pub fn execute(self) {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("CoalesceCOOTensor");
+        }
+
+        let tensor = self.tensor;
+        if tensor.sparse_sort_order().is_none() {
+            let order = Axes::from(tensor.sparse_axes());
+            let mut task = SortCOOTensor::new(tensor, &order).trace(&self.tracer);
+            task.multi_thread = self.multi_thread;
+            task.execute();
+        }
+        // # Safety
+        // The indices and values are replaced together, and the sort order is kept.
+        let raw_parts = unsafe { tensor.raw_parts_mut() };
+
+        // Read the values in standard layout, so each block is in logical order.
+        let (num_blocks, num_sparse_axes) = raw_parts.indices.dim();
+        let dense_shape = raw_parts.values.shape()[1..].to_vec();
+        let dense_block_size = dense_shape.iter().product::<usize>();
+        let values = raw_parts.values.as_standard_layout();
+        let block = |i: usize| values.index_axis(ndarray::Axis(0), i);
+        let indices = &raw_parts.indices;
+
+        let event = self.tracer.start();
+        let mut result_indices = Vec::with_capacity(indices.len());
+        let mut result_values = Vec::with_capacity(values.len());
+        let mut block_values = Vec::with_capacity(dense_block_size);
+        let mut num_result_blocks = 0;
+        let mut i = 0;
+        while i < num_blocks {
+            let first = i;
+            block_values.clear();
+            block_values.extend(block(first).iter().cloned());
+            i += 1;
+            while i < num_blocks && indices.row(i) == indices.row(first) {
+                for (acc, value) in block_values.iter_mut().zip(block(i).iter()) {
+                    *acc = (self.reducer)(mem::replace(acc, VT::zero()), value.clone());
+                }
+                i += 1;
+            }
+            if self.drop_zeros && block_values.iter().all(VT::is_zero) {
+                continue;
+            }
+            result_indices.extend(indices.row(first).iter().cloned());
+            result_values.append(&mut block_values);
+            num_result_blocks += 1;
+        }
+        event.finish("CoalesceCOOTensor::merge");
+
+        raw_parts.indices =
+            Array2::from_shape_vec((num_result_blocks, num_sparse_axes), result_indices).unwrap();
+        raw_parts.values = ArrayD::from_shape_vec(
+            iter::once(num_result_blocks)
+                .chain(dense_shape)
+                .collect::<Vec<_>>(),
+            result_values,
+        )
+        .unwrap();
+        raw_parts.sparse_is_coalesced = true;
+    }
+}
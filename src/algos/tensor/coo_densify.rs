@@ -0,0 +1,169 @@
+use crate::structs::axis::{map_axes_unwrap, Axis};
+use crate::structs::tensor::{COOTensor, COOTensorInner, COOTensorView};
+use crate::structs::vec::SmallVec;
+use crate::traits::{IdxType, RawParts, ValType};
+use crate::utils::tracer::Tracer;
+use anyhow::{anyhow, Result};
+use ndarray::{Array2, Array3};
+use scopeguard::defer;
+use std::iter;
+
+/// Turn a sparse axis of a `COOTensor` into a dense axis.
+///
+/// Blocks sharing the same index on the remaining sparse axes are grouped into one block,
+/// which is one dimension larger.
+pub struct DensifyCOOTensorAxis<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    pub tensor: COOTensorView<'a, IT, VT>,
+    pub axis: &'a Axis<IT>,
+
+    pub tracer: Tracer,
+}
+
+impl<'a, IT, VT> DensifyCOOTensorAxis<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Create a new `DensifyCOOTensorAxis` task.
+    #[must_use]
+    pub fn new(tensor: impl Into<COOTensorView<'a, IT, VT>>, axis: &'a Axis<IT>) -> Self {
+        Self {
+            tensor: tensor.into(),
+            axis,
+            tracer: Tracer::new_dummy(),
+        }
+    }
+
+    /// Enable performance tracing.
+    #[must_use]
+    pub fn trace(mut self, tracer: &Tracer) -> Self {
+        self.tracer.clone_from(tracer);
+        self
+    }
+
+    /// Perform the conversion.
+    ///
+    /// `axis` must be a sparse axis of the tensor, and becomes the last dense axis of the result.
+    /// Duplicate elements are summed up, so the result is coalesced.
+    /// The result is sorted in the tensor's sparse_sort_order without `axis`,
+    /// or in the order of the remaining sparse axes if the tensor is not sorted.
+    pub fn execute(self) -> Result<COOTensor<IT, VT>> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("DensifyCOOTensorAxis");
+        }
+
+        let sparse_axes = self.tensor.sparse_axes();
+        let axis_index = sparse_axes
+            .iter()
+            .position(|ax| ax == self.axis)
+            .ok_or_else(|| anyhow!("axis {} is not a sparse axis", self.axis))?;
+
+        // The remaining sparse axes, and the order to group the blocks.
+        let result_sparse_axes = sparse_axes
+            .iter()
+            .filter(|&ax| ax != self.axis)
+            .cloned()
+            .collect::<SmallVec<_>>();
+        let sparse_sort_order = self
+            .tensor
+            .sparse_sort_order()
+            .unwrap_or(sparse_axes)
+            .iter()
+            .filter(|&ax| ax != self.axis)
+            .cloned()
+            .collect::<SmallVec<_>>();
+        let group_order = map_axes_unwrap(&sparse_sort_order, sparse_axes).collect::<SmallVec<_>>();
+
+        let indices = self.tensor.indices();
+        let num_blocks = indices.nrows();
+        let mut block_order = (0..num_blocks).collect::<Vec<_>>();
+        let compare = |&a: &usize, &b: &usize| {
+            group_order
+                .iter()
+                .map(|&col| indices[(a, col)].cmp(&indices[(b, col)]))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        };
+        block_order.sort_by(compare);
+
+        // Each group starts where the remaining sparse index changes.
+        let mut group_offsets = Vec::new();
+        for (i, block) in block_order.iter().enumerate() {
+            if i == 0 || compare(&block_order[i - 1], block).is_ne() {
+                group_offsets.push(i);
+            }
+        }
+        if group_offsets.is_empty() && result_sparse_axes.is_empty() {
+            // Without sparse axes left, the result still needs one block of zeros.
+            group_offsets.push(0);
+        }
+        let num_groups = group_offsets.len();
+        group_offsets.push(num_blocks);
+
+        // Reshape the values into an ArrayView2.
+        // Rows are each dense block, and columns are linearized elements inside the dense block.
+        let dense_shape = self.tensor.values().shape()[1..].to_vec();
+        let dense_block_size = dense_shape.iter().product::<usize>();
+        let values = self.tensor.values();
+        let values = values
+            .as_standard_layout()
+            .into_shape((num_blocks, dense_block_size))?;
+
+        let axis_len = self.axis.len();
+        let mut result_indices = Array2::zeros((num_groups, result_sparse_axes.len()));
+        let mut result_values = Array3::<VT>::zeros((num_groups, dense_block_size, axis_len));
+        for group in 0..num_groups {
+            for (result_col, col) in (0..sparse_axes.len())
+                .filter(|&col| col != axis_index)
+                .enumerate()
+            {
+                let first_block = block_order[group_offsets[group]];
+                result_indices[(group, result_col)] = indices[(first_block, col)];
+            }
+            for &block in &block_order[group_offsets[group]..group_offsets[group + 1]] {
+                let offset = (indices[(block, axis_index)] - self.axis.lower())
+                    .to_usize()
+                    .unwrap();
+                for k in 0..dense_block_size {
+                    let value = &mut result_values[(group, k, offset)];
+                    *value = value.clone() + values[(block, k)].clone();
+                }
+            }
+        }
+        let result_values = result_values.into_shape(
+            iter::once(num_groups)
+                .chain(dense_shape.iter().copied())
+                .chain(iter::once(axis_len))
+                .collect::<Vec<_>>(),
+        )?;
+
+        let result = COOTensorInner {
+            name: self.tensor.name().map(str::to_owned),
+            shape: self.tensor.shape().into(),
+            sparse_axes: result_sparse_axes,
+            dense_axes: self
+                .tensor
+                .dense_axes()
+                .iter()
+                .chain(iter::once(self.axis))
+                .cloned()
+                .collect(),
+            indices: result_indices,
+            values: result_values,
+            sparse_is_sorted: true,
+            sparse_sort_order,
+            sparse_is_coalesced: true,
+        };
+
+        Ok(
+            // # Safety
+            // We make sure the tensor is in valid state.
+            unsafe { COOTensor::from_raw_parts(result) },
+        )
+    }
+}
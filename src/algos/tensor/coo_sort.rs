@@ -2,9 +2,14 @@ use crate::structs::axis::{map_axes_unwrap, Axis};
 use crate::structs::tensor::COOTensor;
 use crate::structs::vec::SmallVec;
 use crate::traits::{IdxType, RawParts, ValType};
-use ndarray::{Array2, ArrayView1, ArrayViewMut2};
+use crate::utils::tracer::Tracer;
+use ndarray::{self, Array2};
+use rayon::prelude::*;
+use scopeguard::defer;
 
 /// Sort the storage order of elements inside a `COOTensor`.
+///
+/// Blocks are sorted lexicographically by their sparse indices along `order`, with the first axis being the outermost.
 pub struct SortCOOTensor<'a, IT, VT>
 where
     IT: 'a + IdxType,
@@ -12,6 +17,9 @@ where
 {
     pub tensor: &'a mut COOTensor<IT, VT>,
     pub order: &'a [Axis<IT>],
+
+    pub tracer: Tracer,
+    pub multi_thread: bool,
 }
 
 impl<'a, IT, VT> SortCOOTensor<'a, IT, VT>
@@ -22,98 +30,103 @@ where
     /// Create a new `SortCOOTensor` task.
     #[must_use]
     pub fn new(tensor: &'a mut COOTensor<IT, VT>, order: &'a [Axis<IT>]) -> Self {
-        Self { tensor, order }
+        Self {
+            tensor,
+            order,
+            tracer: Tracer::new_dummy(),
+            multi_thread: false,
+        }
+    }
+
+    /// Enable performance tracing.
+    #[must_use]
+    pub fn trace(mut self, tracer: &Tracer) -> Self {
+        self.tracer.clone_from(tracer);
+        self
     }
 
     /// Perform the sorting.
     ///
-    /// The sort operation uses quick-sort algorithm, but may change in future versions.
+    /// The sort is stable, so blocks with the same sparse index keep their relative order.
+    /// The sort operation uses merge-sort algorithm, but may change in future versions.
+    ///
+    /// # Allocation
+    /// This function requires `O(n)` auxiliary memory, where `n` is the number of non-zero elements.
     pub fn execute(self) {
-        let tensor = self.tensor;
-        let raw_parts = unsafe { tensor.raw_parts_mut() };
-        let order = self.order;
-        let order_index = map_axes_unwrap(order, &raw_parts.sparse_axes).collect::<SmallVec<_>>();
-
-        // Reshape values into 2D array for more efficient indexing.
-        let num_blocks = raw_parts.indices.nrows();
-        let values_shape = raw_parts.values.shape().split_first().unwrap();
-        assert_eq!(num_blocks, *values_shape.0);
-        let values_2d_shape = (*values_shape.0, values_shape.1.iter().product());
-        let mut values_2d = raw_parts
-            .values
-            .view_mut()
-            .into_shape(values_2d_shape)
-            .unwrap();
-
-        // Call the quick sort algorithm.
-        Self::sort_subtensor(
-            &mut raw_parts.indices,
-            &mut values_2d,
-            &order_index,
-            0,
-            num_blocks,
-        );
+        let event = self.tracer.start();
+        defer! {
+            event.finish("SortCOOTensor");
+        }
+
+        let indices = &self.tensor.raw_parts().indices;
+        let order_index =
+            map_axes_unwrap(self.order, self.tensor.sparse_axes()).collect::<SmallVec<_>>();
+        let permutation = if self.multi_thread {
+            self.compute_permutation_multi_thread(indices, &order_index)
+        } else {
+            self.compute_permutation(indices, &order_index)
+        };
+
+        // # Safety
+        // Indices and values are permuted together.
+        let raw_parts = unsafe { self.tensor.raw_parts_mut() };
+        {
+            let event = self.tracer.start();
+            defer! {
+                event.finish("SortCOOTensor::apply_permutation");
+            }
+
+            raw_parts.indices = raw_parts.indices.select(ndarray::Axis(0), &permutation);
+            raw_parts.values = raw_parts.values.select(ndarray::Axis(0), &permutation);
+        }
 
         // Mark the tensor as sorted.
-        raw_parts.sparse_sort_order.clone_from_slice(order);
+        raw_parts.sparse_sort_order.clone_from_slice(self.order);
         raw_parts.sparse_is_sorted = true;
     }
 
-    fn sort_subtensor(
-        indices: &mut Array2<IT>,
-        values: &mut ArrayViewMut2<VT>,
-        order: &[usize],
-        from: usize,
-        to: usize,
-    ) {
-        if to - from < 2 {
-            return;
-        }
-        let mut pivot = (from + to) / 2;
-        let mut i = from;
-        let mut j = to - 1;
-        loop {
-            while Self::index_less_than(order, &indices.row(i), &indices.row(pivot)) {
-                i += 1;
-            }
-            while Self::index_less_than(order, &indices.row(j), &indices.row(pivot)) {
-                j -= 1;
-            }
-            if i >= j {
-                break;
-            }
-            Self::swap_block(indices, values, i, j);
-            if i == pivot {
-                pivot = j;
-            } else if j == pivot {
-                pivot = i;
-            }
-            i += 1;
-            j -= 1;
-        }
-        Self::sort_subtensor(indices, values, order, from, j);
-        Self::sort_subtensor(indices, values, order, i, to);
+    /// Gather the sort keys of each block, so that each key is contiguous in memory.
+    fn sort_keys(indices: &Array2<IT>, order: &[usize]) -> Array2<IT> {
+        Array2::from_shape_fn((indices.nrows(), order.len()), |(block, i)| {
+            indices[(block, order[i])]
+        })
     }
 
-    fn index_less_than(order: &[usize], a: &ArrayView1<IT>, b: &ArrayView1<IT>) -> bool {
-        for &i in order.iter() {
-            if a[i] >= b[i] {
-                return false;
-            }
+    fn compute_permutation(&self, indices: &Array2<IT>, order: &[usize]) -> Vec<usize> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("SortCOOTensor::compute_permutation");
         }
-        true
+
+        let keys = Self::sort_keys(indices, order);
+        let keys = keys.as_slice().unwrap();
+        let key_len = order.len();
+
+        let mut permutation = (0..indices.nrows()).collect::<Vec<_>>();
+        permutation.sort_by(|&a, &b| {
+            keys[a * key_len..(a + 1) * key_len].cmp(&keys[b * key_len..(b + 1) * key_len])
+        });
+        permutation
     }
 
-    fn swap_block(indices: &mut Array2<IT>, values: &mut ArrayViewMut2<VT>, a: usize, b: usize) {
-        for offset in 0..indices.ncols() {
-            unsafe {
-                indices.uswap((a, offset), (b, offset));
-            }
-        }
-        for offset in 0..values.ncols() {
-            unsafe {
-                values.uswap((a, offset), (b, offset));
-            }
+    fn compute_permutation_multi_thread(
+        &self,
+        indices: &Array2<IT>,
+        order: &[usize],
+    ) -> Vec<usize> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("SortCOOTensor::compute_permutation_multi_thread");
         }
+
+        let keys = Self::sort_keys(indices, order);
+        let keys = keys.as_slice().unwrap();
+        let key_len = order.len();
+
+        let mut permutation = (0..indices.nrows()).collect::<Vec<_>>();
+        permutation.par_sort_by(|&a, &b| {
+            keys[a * key_len..(a + 1) * key_len].cmp(&keys[b * key_len..(b + 1) * key_len])
+        });
+        permutation
     }
 }
@@ -0,0 +1,156 @@
+use crate::structs::axis::Axis;
+use crate::structs::tensor::{COOTensor, COOTensorInner, COOTensorView};
+use crate::structs::vec::SmallVec;
+use crate::traits::{IdxType, RawParts, ValType};
+use crate::utils::tracer::Tracer;
+use anyhow::{anyhow, Result};
+use ndarray::{Array2, ArrayD};
+use num::NumCast;
+use scopeguard::defer;
+use std::iter;
+
+/// Turn a dense axis of a `COOTensor` into a sparse axis.
+///
+/// Each block is exploded into one smaller block for each index along the axis.
+pub struct SparsifyCOOTensorAxis<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    pub tensor: COOTensorView<'a, IT, VT>,
+    pub axis: &'a Axis<IT>,
+    /// Remove blocks whose values are all zero.
+    pub drop_zeros: bool,
+
+    pub tracer: Tracer,
+}
+
+impl<'a, IT, VT> SparsifyCOOTensorAxis<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Create a new `SparsifyCOOTensorAxis` task.
+    #[must_use]
+    pub fn new(tensor: impl Into<COOTensorView<'a, IT, VT>>, axis: &'a Axis<IT>) -> Self {
+        Self {
+            tensor: tensor.into(),
+            axis,
+            drop_zeros: false,
+            tracer: Tracer::new_dummy(),
+        }
+    }
+
+    /// Remove blocks whose values are all zero.
+    #[must_use]
+    pub fn drop_zeros(mut self) -> Self {
+        self.drop_zeros = true;
+        self
+    }
+
+    /// Enable performance tracing.
+    #[must_use]
+    pub fn trace(mut self, tracer: &Tracer) -> Self {
+        self.tracer.clone_from(tracer);
+        self
+    }
+
+    /// Perform the conversion.
+    ///
+    /// `axis` must be a dense axis of the tensor, and becomes the last sparse axis of the result.
+    /// If the tensor is sorted and coalesced, the result is sorted in the tensor's sparse_sort_order followed by `axis`.
+    pub fn execute(self) -> Result<COOTensor<IT, VT>> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("SparsifyCOOTensorAxis");
+        }
+
+        let dense_axes = self.tensor.dense_axes();
+        let axis_index = dense_axes
+            .iter()
+            .position(|ax| ax == self.axis)
+            .ok_or_else(|| anyhow!("axis {} is not a dense axis", self.axis))?;
+
+        // Move the axis right after the block axis, so each exploded block is a contiguous row.
+        let values = self.tensor.values();
+        let num_blocks = values.shape()[0];
+        let axis_len = self.axis.len();
+        let mut permutation = vec![0, axis_index + 1];
+        permutation.extend((1..values.ndim()).filter(|&i| i != axis_index + 1));
+        let result_dense_shape = permutation[2..]
+            .iter()
+            .map(|&i| values.shape()[i])
+            .collect::<Vec<_>>();
+        let dense_block_size = result_dense_shape.iter().product::<usize>();
+        let values = values.permuted_axes(permutation);
+        let values = values
+            .as_standard_layout()
+            .into_shape((num_blocks * axis_len, dense_block_size))?;
+
+        let indices = self.tensor.indices();
+        let num_sparse_axes = indices.ncols();
+        let mut result_indices = Vec::with_capacity((indices.len() + num_blocks) * axis_len);
+        let mut result_values = Vec::with_capacity(values.len());
+        let mut num_result_blocks = 0;
+        for block in 0..num_blocks {
+            for offset in 0..axis_len {
+                let row = values.row(block * axis_len + offset);
+                if self.drop_zeros && row.iter().all(VT::is_zero) {
+                    continue;
+                }
+                result_indices.extend(indices.row(block).iter().cloned());
+                result_indices.push(self.axis.lower() + <IT as NumCast>::from(offset).unwrap());
+                result_values.extend(row.iter().cloned());
+                num_result_blocks += 1;
+            }
+        }
+
+        let result_indices =
+            Array2::from_shape_vec((num_result_blocks, num_sparse_axes + 1), result_indices)?;
+        let result_values = ArrayD::from_shape_vec(
+            iter::once(num_result_blocks)
+                .chain(result_dense_shape)
+                .collect::<Vec<_>>(),
+            result_values,
+        )?;
+
+        // Duplicate blocks would interleave along the new axis.
+        let is_coalesced = self.tensor.is_coalesced();
+        let sparse_sort_order = self
+            .tensor
+            .sparse_sort_order()
+            .filter(|_| is_coalesced)
+            .unwrap_or(self.tensor.sparse_axes())
+            .iter()
+            .chain(iter::once(self.axis))
+            .cloned()
+            .collect::<SmallVec<_>>();
+        let result = COOTensorInner {
+            name: self.tensor.name().map(str::to_owned),
+            shape: self.tensor.shape().into(),
+            sparse_axes: self
+                .tensor
+                .sparse_axes()
+                .iter()
+                .chain(iter::once(self.axis))
+                .cloned()
+                .collect(),
+            dense_axes: dense_axes
+                .iter()
+                .filter(|&ax| ax != self.axis)
+                .cloned()
+                .collect(),
+            indices: result_indices,
+            values: result_values,
+            sparse_is_sorted: is_coalesced && self.tensor.sparse_sort_order().is_some(),
+            sparse_sort_order,
+            sparse_is_coalesced: is_coalesced,
+        };
+
+        Ok(
+            // # Safety
+            // We make sure the tensor is in valid state.
+            unsafe { COOTensor::from_raw_parts(result) },
+        )
+    }
+}
@@ -66,7 +66,7 @@ where
             .unwrap();
         let mut index_hashtable = HashMap::<usize, ()>::with_capacity(num_non_zeros);
 
-        let mut raw_parts = unsafe { tensor.raw_parts_mut() };
+        let raw_parts = unsafe { tensor.raw_parts_mut() };
         let mut rng = rand::thread_rng();
         let index_distr = self
             .shape
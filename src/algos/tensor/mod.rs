@@ -1,7 +1,13 @@
 //! Algorithms related to tensors.
 
+mod coo_coalesce;
+mod coo_densify;
 mod coo_sort;
+mod coo_sparsify;
 mod create_random_coo;
 
+pub use coo_coalesce::CoalesceCOOTensor;
+pub use coo_densify::DensifyCOOTensorAxis;
 pub use coo_sort::SortCOOTensor;
+pub use coo_sparsify::SparsifyCOOTensorAxis;
 pub use create_random_coo::CreateRandomCOOTensor;
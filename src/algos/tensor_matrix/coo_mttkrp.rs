@@ -0,0 +1,321 @@
+use crate::structs::axis::Axis;
+use crate::structs::tensor::{COOTensor, COOTensorInner};
+use crate::structs::vec::{smallvec, SmallVec};
+use crate::traits::{IdxType, RawParts, Tensor, ValType};
+use crate::utils::tracer::Tracer;
+use anyhow::{anyhow, bail, Result};
+use ndarray::{Array2, ArrayView1, ArrayView2, Ix1, Ix3};
+use rayon::prelude::*;
+use scopeguard::defer;
+use std::ops::Range;
+use std::sync::Mutex;
+
+/// Matricized `COOTensor` times Khatri-Rao product of `DenseMatrix`es.
+///
+/// Each matrix has two dense axes, the first one is an axis of the tensor, the second one is the rank axis shared by all matrices.
+/// The result is a dense matrix with the output axis and the rank axis.
+pub struct COOTensorMTTKRP<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    pub tensor: &'a COOTensor<IT, VT>,
+    pub matrices: &'a [COOTensor<IT, VT>],
+    pub axis: &'a Axis<IT>,
+
+    pub tracer: Tracer,
+    pub multi_thread: bool,
+}
+
+impl<'a, IT, VT> COOTensorMTTKRP<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Create a new `COOTensorMTTKRP` task.
+    ///
+    /// `matrices` must contain one matrix for each axis of the tensor except `axis`.
+    /// A matrix for `axis` itself is allowed, but ignored.
+    #[must_use]
+    pub fn new(
+        tensor: &'a COOTensor<IT, VT>,
+        matrices: &'a [COOTensor<IT, VT>],
+        axis: &'a Axis<IT>,
+    ) -> Self {
+        Self {
+            tensor,
+            matrices,
+            axis,
+            tracer: Tracer::new_dummy(),
+            multi_thread: false,
+        }
+    }
+
+    /// Enable performance tracing.
+    #[must_use]
+    pub fn trace(mut self, tracer: &Tracer) -> Self {
+        self.tracer.clone_from(tracer);
+        self
+    }
+
+    /// Perform the MTTKRP.
+    ///
+    /// The tensor does not need to be sorted.
+    pub fn execute(self) -> Result<COOTensor<IT, VT>>
+    where
+        IT: IdxType,
+        VT: ValType,
+    {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("COOTensorMTTKRP");
+        }
+
+        // This algorithm only solves the case where the tensor is fully sparse.
+        if !self.tensor.dense_axes().is_empty() {
+            bail!("The tensor must be fully sparse.");
+        }
+        let sparse_axes = self.tensor.sparse_axes();
+        let output_axis_index = sparse_axes
+            .iter()
+            .position(|ax| ax == self.axis)
+            .ok_or_else(|| anyhow!("axis {} not found", self.axis))?;
+        if sparse_axes.len() < 2 {
+            bail!("The tensor must have at least 2 axes.");
+        }
+
+        let (matrix_values, rank_axis) =
+            mttkrp_matrices(sparse_axes, self.matrices, output_axis_index)?;
+
+        // Reshape the tensor into an ArrayView1.
+        let tensor_values = self
+            .tensor
+            .raw_parts()
+            .values
+            .view()
+            .into_dimensionality::<Ix1>()?;
+
+        let result_values = if self.multi_thread {
+            self.compute_values_multi_thread(
+                &tensor_values,
+                &matrix_values,
+                output_axis_index,
+                rank_axis.len(),
+            )
+        } else {
+            self.compute_values(
+                &tensor_values,
+                &matrix_values,
+                output_axis_index,
+                rank_axis.len(),
+            )
+        };
+
+        let result_shape: SmallVec<_> = smallvec![self.axis.clone(), rank_axis];
+        let result = COOTensorInner {
+            name: None,
+            shape: result_shape.clone(),
+            sparse_axes: SmallVec::new(),
+            dense_axes: result_shape,
+            indices: Array2::zeros((1, 0)),
+            values: result_values.insert_axis(ndarray::Axis(0)).into_dyn(),
+            sparse_is_sorted: true,
+            sparse_sort_order: SmallVec::new(),
+            sparse_is_coalesced: true,
+        };
+
+        Ok(
+            // # Safety
+            // We make sure the tensor is in valid state.
+            unsafe { COOTensor::from_raw_parts(result) },
+        )
+    }
+
+    fn compute_values(
+        &self,
+        tensor_values: &ArrayView1<VT>,
+        matrix_values: &[Option<ArrayView2<VT>>],
+        output_axis_index: usize,
+        rank: usize,
+    ) -> Array2<VT>
+    where
+        IT: IdxType,
+        VT: ValType,
+    {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("COOTensorMTTKRP::compute_values");
+        }
+
+        let mut result_values = Array2::<VT>::zeros((self.axis.len(), rank));
+        self.compute_elements(
+            0..tensor_values.len(),
+            tensor_values,
+            matrix_values,
+            output_axis_index,
+            &mut result_values,
+        );
+        result_values
+    }
+
+    fn compute_values_multi_thread(
+        &self,
+        tensor_values: &ArrayView1<VT>,
+        matrix_values: &[Option<ArrayView2<VT>>],
+        output_axis_index: usize,
+        rank: usize,
+    ) -> Array2<VT>
+    where
+        IT: IdxType,
+        VT: ValType,
+    {
+        const CHUNK_SIZE: usize = 4096;
+
+        let event = self.tracer.start();
+        defer! {
+            event.finish("COOTensorMTTKRP::compute_values_multi_thread");
+        }
+
+        let num_non_zeros = tensor_values.len();
+        let result_shape = (self.axis.len(), rank);
+        accumulate_per_thread(
+            num_non_zeros.div_ceil(CHUNK_SIZE),
+            result_shape,
+            |chunk, result_values| {
+                let chunk_begin = chunk * CHUNK_SIZE;
+                let chunk_end = (chunk_begin + CHUNK_SIZE).min(num_non_zeros);
+                self.compute_elements(
+                    chunk_begin..chunk_end,
+                    tensor_values,
+                    matrix_values,
+                    output_axis_index,
+                    result_values,
+                );
+            },
+        )
+    }
+
+    fn compute_elements(
+        &self,
+        elements: Range<usize>,
+        tensor_values: &ArrayView1<VT>,
+        matrix_values: &[Option<ArrayView2<VT>>],
+        output_axis_index: usize,
+        result_values: &mut Array2<VT>,
+    ) where
+        IT: IdxType,
+        VT: ValType,
+    {
+        let tensor_indices = &self.tensor.raw_parts().indices;
+        let sparse_axes = self.tensor.sparse_axes();
+        let rank = result_values.ncols();
+        let mut row_buffer = vec![VT::zero(); rank];
+
+        for j in elements {
+            row_buffer.fill(tensor_values[j].clone());
+            for (axis_idx, (matrix, axis)) in
+                matrix_values.iter().zip(sparse_axes.iter()).enumerate()
+            {
+                if axis_idx == output_axis_index {
+                    continue;
+                }
+                let matrix = matrix.as_ref().unwrap();
+                // # Safety
+                // j < tensor_indices.nrows()
+                // axis_idx < tensor_indices.ncols()
+                let r = unsafe {
+                    (*tensor_indices.uget((j, axis_idx)) - axis.lower())
+                        .to_usize()
+                        .unwrap_unchecked()
+                };
+                for (k, value) in row_buffer.iter_mut().enumerate() {
+                    // # Safety
+                    // r < axis.len() == matrix.nrows()
+                    // k < rank == matrix.ncols()
+                    *value = value.clone() * unsafe { matrix.uget((r, k)) }.clone();
+                }
+            }
+            let output_offset = (tensor_indices[(j, output_axis_index)] - self.axis.lower())
+                .to_usize()
+                .unwrap();
+            for (k, value) in row_buffer.iter().enumerate() {
+                let result = &mut result_values[(output_offset, k)];
+                *result = result.clone() + value.clone();
+            }
+        }
+    }
+}
+
+/// The values of the matrix for each axis of the tensor, `None` for the output axis.
+pub(super) type MatrixViews<'a, VT> = SmallVec<Option<ArrayView2<'a, VT>>>;
+
+/// Find the matrix for each axis in `shape` except `output_axis_index`, and the rank axis shared by them.
+pub(super) fn mttkrp_matrices<'a, IT, VT>(
+    shape: &[Axis<IT>],
+    matrices: &'a [COOTensor<IT, VT>],
+    output_axis_index: usize,
+) -> Result<(MatrixViews<'a, VT>, Axis<IT>)>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    let mut rank_axis: Option<&Axis<IT>> = None;
+    let mut matrix_values: MatrixViews<VT> = smallvec![None; shape.len()];
+    for (axis_idx, axis) in shape.iter().enumerate() {
+        if axis_idx == output_axis_index {
+            continue;
+        }
+        let matrix = matrices
+            .iter()
+            .find(|matrix| matrix.dense_axes().first() == Some(axis))
+            .ok_or_else(|| anyhow!("The matrix for axis {} is missing.", axis))?;
+        // Check if the matrix is fully dense with 2 axes.
+        if matrix.ndim() != 2 || !matrix.sparse_axes().is_empty() {
+            bail!("The matrix must be fully dense with 2 axes.");
+        }
+        let matrix_rank_axis = &matrix.dense_axes()[1];
+        if *rank_axis.get_or_insert(matrix_rank_axis) != matrix_rank_axis {
+            bail!("All matrices must share the same rank axis.");
+        }
+        matrix_values[axis_idx] = Some(
+            matrix
+                .raw_parts()
+                .values
+                .view()
+                .into_dimensionality::<Ix3>()?
+                .index_axis_move(ndarray::Axis(0), 0),
+        );
+    }
+    Ok((matrix_values, rank_axis.unwrap().clone()))
+}
+
+/// Run `compute` on each task of `0..num_tasks` in parallel, and sum up the matrices it accumulates into.
+///
+/// Each thread of the pool accumulates into its own matrix of `result_shape`, created on its first task,
+/// so there are never more copies of the result than threads.
+pub(super) fn accumulate_per_thread<VT>(
+    num_tasks: usize,
+    result_shape: (usize, usize),
+    compute: impl Fn(usize, &mut Array2<VT>) + Sync,
+) -> Array2<VT>
+where
+    VT: ValType,
+{
+    let partials = (0..rayon::current_num_threads())
+        .map(|_| Mutex::new(None))
+        .collect::<Vec<_>>();
+    (0..num_tasks).into_par_iter().for_each(|task| {
+        // Only this thread locks its own slot, and `compute` never waits for other tasks.
+        let thread = rayon::current_thread_index().unwrap_or(0);
+        let mut partial = partials[thread].lock().unwrap();
+        compute(
+            task,
+            partial.get_or_insert_with(|| Array2::zeros(result_shape)),
+        );
+    });
+    partials
+        .into_iter()
+        .filter_map(|partial| partial.into_inner().unwrap())
+        .reduce(|a, b| a + b)
+        .unwrap_or_else(|| Array2::zeros(result_shape))
+}
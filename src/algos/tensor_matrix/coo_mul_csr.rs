@@ -0,0 +1,346 @@
+use super::index_eq_except_axis;
+use crate::structs::axis::Axis;
+use crate::structs::matrix::CSRMatrix;
+use crate::structs::tensor::{COOTensor, COOTensorInner, COOTensorView};
+use crate::structs::vec::SmallVec;
+use crate::traits::{IdxType, RawParts, ValType};
+use crate::utils::tracer::Tracer;
+use anyhow::{anyhow, bail, Result};
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, Ix1};
+use num::NumCast;
+use rayon::prelude::*;
+use scopeguard::defer;
+
+/// Multiply a `COOTensor` with a `CSRMatrix`.
+///
+/// Unlike [`crate::algos::tensor_matrix::COOTensorMulDenseMatrix`], the result is fully sparse.
+pub struct COOTensorMulCSRMatrix<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    pub tensor: COOTensorView<'a, IT, VT>,
+    pub matrix: &'a CSRMatrix<IT, VT>,
+
+    pub tracer: Tracer,
+    pub multi_thread: bool,
+}
+
+impl<'a, IT, VT> COOTensorMulCSRMatrix<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Create a new `COOTensorMulCSRMatrix` task.
+    #[must_use]
+    pub fn new(
+        tensor: impl Into<COOTensorView<'a, IT, VT>>,
+        matrix: &'a CSRMatrix<IT, VT>,
+    ) -> Self {
+        Self {
+            tensor: tensor.into(),
+            matrix,
+            tracer: Tracer::new_dummy(),
+            multi_thread: false,
+        }
+    }
+
+    /// Enable performance tracing.
+    #[must_use]
+    pub fn trace(mut self, tracer: &Tracer) -> Self {
+        self.tracer.clone_from(tracer);
+        self
+    }
+
+    /// Perform the multiplication.
+    ///
+    /// The last axis of the tensor's sparse_sort_order must be the row axis of the matrix.
+    /// The common axis is replaced by the column axis of the matrix, at the same position.
+    pub fn execute(self) -> Result<COOTensor<IT, VT>> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("COOTensorMulCSRMatrix");
+        }
+
+        // This algorithm only solves the case where the tensor is fully sparse.
+        if !self.tensor.dense_axes().is_empty() {
+            bail!("The tensor must be fully sparse.");
+        }
+
+        // Map the common axis of the tensor and the matrix.
+        let common_axis = self.matrix.row_axis();
+        let new_axis = self.matrix.col_axis();
+        let common_axis_index = self
+            .tensor
+            .sparse_axes()
+            .iter()
+            .position(|ax| ax == common_axis)
+            .ok_or_else(|| anyhow!("axis {} not found", common_axis))?;
+        if self.tensor.shape().contains(new_axis) {
+            bail!("There must be only one common axis.");
+        }
+
+        // Check if the tensor is sorted, and the trailing axis is the common axis.
+        let sparse_sorting_order = self
+            .tensor
+            .sparse_sort_order()
+            .ok_or(anyhow!("The tensor must be sorted"))?;
+        if sparse_sorting_order.last() != Some(common_axis) {
+            bail!("The tensor must be sorted along the common axis.");
+        }
+
+        // Extract the contents from the inputs.
+        let tensor_indices = &self.tensor.indices();
+        // Reshape the tensor into an ArrayView1.
+        let tensor_values = self.tensor.values().into_dimensionality::<Ix1>()?;
+
+        // Create the output tensor.
+        let replace_common_axis = |ax: &Axis<IT>| {
+            if ax == common_axis {
+                new_axis.clone()
+            } else {
+                ax.clone()
+            }
+        };
+        let result_shape = self
+            .tensor
+            .shape()
+            .iter()
+            .map(replace_common_axis)
+            .collect::<SmallVec<_>>();
+        let result_sparse_axes = self
+            .tensor
+            .sparse_axes()
+            .iter()
+            .map(replace_common_axis)
+            .collect::<SmallVec<_>>();
+        let sparse_sort_order = sparse_sorting_order
+            .iter()
+            .map(replace_common_axis)
+            .collect::<SmallVec<_>>();
+
+        // Group the tensor into fibers along the common axis.
+        let fiber_offsets = self.compute_fibers(tensor_indices, common_axis_index);
+
+        let fibers = if self.multi_thread {
+            self.compute_fibers_values_multi_thread(
+                tensor_indices,
+                &tensor_values,
+                &fiber_offsets,
+                common_axis_index,
+            )
+        } else {
+            self.compute_fibers_values(
+                tensor_indices,
+                &tensor_values,
+                &fiber_offsets,
+                common_axis_index,
+            )
+        };
+
+        let (result_indices, result_values) =
+            self.assemble(tensor_indices, &fiber_offsets, fibers, common_axis_index);
+
+        let result = COOTensorInner {
+            name: None,
+            shape: result_shape,
+            sparse_axes: result_sparse_axes,
+            dense_axes: SmallVec::new(),
+            indices: result_indices,
+            values: result_values.into_dyn(),
+            sparse_is_sorted: true,
+            sparse_sort_order,
+            sparse_is_coalesced: true,
+        };
+
+        Ok(
+            // # Safety
+            // We make sure the tensor is in valid state.
+            unsafe { COOTensor::from_raw_parts(result) },
+        )
+    }
+
+    /// Find the offsets of the fibers, each fiber has the same index except for the common axis.
+    fn compute_fibers(
+        &self,
+        tensor_indices: &ArrayView2<IT>,
+        common_axis_index: usize,
+    ) -> Vec<usize> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("COOTensorMulCSRMatrix::compute_fibers");
+        }
+
+        let num_blocks = tensor_indices.nrows();
+        let mut fiber_offsets = Vec::new();
+        for i in 0..num_blocks {
+            if fiber_offsets.last().is_none_or(|&last| unsafe {
+                // # Safety
+                // Both `last` and `i` are lower than `num_blocks`.
+                !index_eq_except_axis(tensor_indices, last, i, common_axis_index)
+            }) {
+                fiber_offsets.push(i);
+            }
+        }
+        fiber_offsets.push(num_blocks);
+        fiber_offsets
+    }
+
+    fn compute_fibers_values(
+        &self,
+        tensor_indices: &ArrayView2<IT>,
+        tensor_values: &ArrayView1<VT>,
+        fiber_offsets: &[usize],
+        common_axis_index: usize,
+    ) -> Vec<Vec<(IT, VT)>> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("COOTensorMulCSRMatrix::compute_fibers_values");
+        }
+
+        let mut accumulator = SparseAccumulator::new(self.matrix.col_axis().len());
+        fiber_offsets
+            .windows(2)
+            .map(|range| {
+                accumulator.fiber(
+                    self.matrix,
+                    tensor_indices,
+                    tensor_values,
+                    range[0]..range[1],
+                    common_axis_index,
+                )
+            })
+            .collect()
+    }
+
+    fn compute_fibers_values_multi_thread(
+        &self,
+        tensor_indices: &ArrayView2<IT>,
+        tensor_values: &ArrayView1<VT>,
+        fiber_offsets: &[usize],
+        common_axis_index: usize,
+    ) -> Vec<Vec<(IT, VT)>> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("COOTensorMulCSRMatrix::compute_fibers_values_multi_thread");
+        }
+
+        let num_cols = self.matrix.col_axis().len();
+        fiber_offsets
+            .par_windows(2)
+            .with_min_len(256)
+            .map_init(
+                || SparseAccumulator::new(num_cols),
+                |accumulator, range| {
+                    accumulator.fiber(
+                        self.matrix,
+                        tensor_indices,
+                        tensor_values,
+                        range[0]..range[1],
+                        common_axis_index,
+                    )
+                },
+            )
+            .collect()
+    }
+
+    /// Expand the result of each fiber into indices and values.
+    fn assemble(
+        &self,
+        tensor_indices: &ArrayView2<IT>,
+        fiber_offsets: &[usize],
+        fibers: Vec<Vec<(IT, VT)>>,
+        common_axis_index: usize,
+    ) -> (Array2<IT>, Array1<VT>) {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("COOTensorMulCSRMatrix::assemble");
+        }
+
+        let num_blocks = fibers.iter().map(Vec::len).sum::<usize>();
+        let mut result_indices = Array2::zeros((num_blocks, tensor_indices.ncols()));
+        let mut result_values = Vec::with_capacity(num_blocks);
+        let mut block = 0;
+        for (&fiber_offset, fiber) in fiber_offsets.iter().zip(fibers) {
+            let fiber_index = tensor_indices.row(fiber_offset);
+            for (col, value) in fiber {
+                let mut result_index = result_indices.row_mut(block);
+                result_index.assign(&fiber_index);
+                result_index[common_axis_index] = col;
+                result_values.push(value);
+                block += 1;
+            }
+        }
+        (result_indices, Array1::from_vec(result_values))
+    }
+}
+
+/// A dense buffer over the columns of the matrix, remembering which columns are touched.
+struct SparseAccumulator<VT> {
+    values: Vec<VT>,
+    touched: Vec<bool>,
+    touched_cols: Vec<usize>,
+}
+
+impl<VT> SparseAccumulator<VT>
+where
+    VT: ValType,
+{
+    fn new(num_cols: usize) -> Self {
+        Self {
+            values: vec![VT::zero(); num_cols],
+            touched: vec![false; num_cols],
+            touched_cols: Vec::new(),
+        }
+    }
+
+    /// Multiply the blocks in `range` with the matrix, returning the sorted columns and their values.
+    fn fiber<IT>(
+        &mut self,
+        matrix: &CSRMatrix<IT, VT>,
+        tensor_indices: &ArrayView2<IT>,
+        tensor_values: &ArrayView1<VT>,
+        range: std::ops::Range<usize>,
+        common_axis_index: usize,
+    ) -> Vec<(IT, VT)>
+    where
+        IT: IdxType,
+    {
+        let row_lower = matrix.row_axis().lower();
+        let col_lower = matrix.col_axis().lower();
+        for j in range {
+            // # Safety
+            // j < tensor_indices.nrows()
+            // 0 <= r < matrix.row_axis().len()
+            let r = unsafe {
+                (*tensor_indices.uget((j, common_axis_index)) - row_lower)
+                    .to_usize()
+                    .unwrap_unchecked()
+            };
+            // # Safety
+            // j < tensor_values.len()
+            let scale = unsafe { tensor_values.uget(j) };
+            let (col_indices, matrix_values) = matrix.row(r);
+            for (col, value) in col_indices.iter().zip(matrix_values.iter()) {
+                // # Safety
+                // 0 <= c < matrix.col_axis().len()
+                let c = unsafe { (*col - col_lower).to_usize().unwrap_unchecked() };
+                if !self.touched[c] {
+                    self.touched[c] = true;
+                    self.touched_cols.push(c);
+                }
+                self.values[c] = self.values[c].clone() + scale.clone() * value.clone();
+            }
+        }
+
+        self.touched_cols.sort_unstable();
+        let mut result = Vec::with_capacity(self.touched_cols.len());
+        for &c in self.touched_cols.iter() {
+            let value = std::mem::replace(&mut self.values[c], VT::zero());
+            result.push((col_lower + <IT as NumCast>::from(c).unwrap(), value));
+            self.touched[c] = false;
+        }
+        self.touched_cols.clear();
+        result
+    }
+}
@@ -1,7 +1,7 @@
 use std::mem;
 
 use crate::structs::axis::{map_axes, Axis};
-use crate::structs::tensor::{COOTensor, COOTensorInner};
+use crate::structs::tensor::{COOTensor, COOTensorInner, COOTensorView};
 use crate::structs::vec::{smallvec, SmallVec};
 use crate::traits::{IdxType, RawParts, Tensor, ValType};
 use crate::utils::tracer::Tracer;
@@ -18,7 +18,7 @@ where
     IT: IdxType,
     VT: ValType,
 {
-    pub tensor: &'a COOTensor<IT, VT>,
+    pub tensor: COOTensorView<'a, IT, VT>,
     pub matrix: &'a COOTensor<IT, VT>,
 
     pub tracer: Tracer,
@@ -32,9 +32,12 @@ where
 {
     /// Create a new `COOTensorMulDenseMatrix` task.
     #[must_use]
-    pub fn new(tensor: &'a COOTensor<IT, VT>, matrix: &'a COOTensor<IT, VT>) -> Self {
+    pub fn new(
+        tensor: impl Into<COOTensorView<'a, IT, VT>>,
+        matrix: &'a COOTensor<IT, VT>,
+    ) -> Self {
         Self {
-            tensor,
+            tensor: tensor.into(),
             matrix,
             tracer: Tracer::new_dummy(),
             multi_thread: false,
@@ -98,14 +101,9 @@ where
         }
 
         // Extract the contents from the inputs.
-        let tensor_indices = &self.tensor.raw_parts().indices;
+        let tensor_indices = &self.tensor.indices();
         // Reshape the tensor into an ArrayView1.
-        let tensor_values = self
-            .tensor
-            .raw_parts()
-            .values
-            .view()
-            .into_dimensionality::<Ix1>()?;
+        let tensor_values = self.tensor.values().into_dimensionality::<Ix1>()?;
         let matrix_shape = self.matrix.shape();
         // Reshape the matrix into an ArrayView2.
         let matrix_values = self
@@ -189,11 +187,12 @@ where
             name: None,
             shape: result_shape,
             sparse_axes: result_sparse_axes,
-            dense_axes: smallvec![common_axis.clone()],
+            dense_axes: smallvec![matrix_shape[1].clone()],
             indices: result_indices,
             values: result_values.into_dyn(),
             sparse_is_sorted: true,
             sparse_sort_order,
+            sparse_is_coalesced: true,
         };
 
         Ok(
@@ -205,7 +204,7 @@ where
 
     fn compute_indices(
         &self,
-        tensor_indices: &Array2<IT>,
+        tensor_indices: &ArrayView2<IT>,
         common_axis_index: usize,
     ) -> (Array2<IT>, Vec<usize>)
     where
@@ -230,12 +229,12 @@ where
                 .map(|last_index| unsafe {
                     // # Safety
                     // Both `last_index` and `i` are lower than `num_blocks`.
-                    !self.index_eq_except_axis(tensor_indices, last_index, i, common_axis_index)
+                    !index_eq_except_axis(tensor_indices, last_index, i, common_axis_index)
                 })
                 .unwrap_or(true)
             {
                 unsafe {
-                    self.copy_index_except_axis(
+                    copy_index_except_axis(
                         tensor_indices,
                         i,
                         common_axis_index,
@@ -258,7 +257,7 @@ where
     #[allow(clippy::too_many_arguments)]
     fn compute_values(
         &self,
-        tensor_indices: &Array2<IT>,
+        tensor_indices: &ArrayView2<IT>,
         tensor_values: &ArrayView1<VT>,
         matrix_values: &ArrayView2<VT>,
         result_indices: &Array2<IT>,
@@ -314,7 +313,7 @@ where
     #[allow(clippy::too_many_arguments)]
     fn compute_values_multi_thread(
         &self,
-        tensor_indices: &Array2<IT>,
+        tensor_indices: &ArrayView2<IT>,
         tensor_values: &ArrayView1<VT>,
         matrix_values: &ArrayView2<VT>,
         result_indices: &Array2<IT>,
@@ -372,56 +371,54 @@ where
 
         result_values
     }
+}
 
-    /// Compare fiber index at row `row_a` and `row_b`, except for one common axis.
-    ///
-    /// # Safety
-    /// Make sure row_{a,b} < indices.nrows()
-    unsafe fn index_eq_except_axis(
-        &self,
-        indices: &Array2<IT>,
-        row_a: usize,
-        row_b: usize,
-        except_axis_index: usize,
-    ) -> bool
-    where
-        IT: IdxType,
-    {
-        for i in (0..indices.ncols()).rev() {
-            // Performance concern:
-            // Why we don't use `indices.row()`?
-            // Because it creates an `ndarray::NdProducer`, which is painfully slow.
-            if i != except_axis_index && indices.uget((row_a, i)) != indices.uget((row_b, i)) {
-                return false;
-            }
+/// Compare the index at row `row_a` and `row_b`, except for one common axis.
+///
+/// # Safety
+/// Make sure row_{a,b} < indices.nrows()
+pub(crate) unsafe fn index_eq_except_axis<IT>(
+    indices: &ArrayView2<IT>,
+    row_a: usize,
+    row_b: usize,
+    except_axis_index: usize,
+) -> bool
+where
+    IT: IdxType,
+{
+    for i in (0..indices.ncols()).rev() {
+        // Performance concern:
+        // Why we don't use `indices.row()`?
+        // Because it creates an `ndarray::NdProducer`, which is painfully slow.
+        if i != except_axis_index && indices.uget((row_a, i)) != indices.uget((row_b, i)) {
+            return false;
         }
-        true
     }
+    true
+}
 
-    /// Copy the index of the fiber at row `row` to `index_buffer`.
-    ///
-    /// # Safety
-    /// Make sure row < indices.nrows()
-    /// Make sure except_axis_index < indices.ncols()
-    /// Make sure index_buffer.len() == indices.ncols() - 1
-    unsafe fn copy_index_except_axis(
-        &self,
-        indices: &Array2<IT>,
-        row: usize,
-        except_axis_index: usize,
-        index_buffer: &mut [IT],
-    ) where
-        IT: IdxType,
-    {
-        for i in 0..except_axis_index {
-            index_buffer
-                .get_unchecked_mut(i)
-                .clone_from(indices.uget((row, i)));
-        }
-        for i in except_axis_index + 1..indices.ncols() {
-            index_buffer
-                .get_unchecked_mut(i - 1)
-                .clone_from(indices.uget((row, i)));
-        }
+/// Copy the index at row `row` to `index_buffer`, except for one common axis.
+///
+/// # Safety
+/// Make sure row < indices.nrows()
+/// Make sure except_axis_index < indices.ncols()
+/// Make sure index_buffer.len() == indices.ncols() - 1
+pub(crate) unsafe fn copy_index_except_axis<IT>(
+    indices: &ArrayView2<IT>,
+    row: usize,
+    except_axis_index: usize,
+    index_buffer: &mut [IT],
+) where
+    IT: IdxType,
+{
+    for i in 0..except_axis_index {
+        index_buffer
+            .get_unchecked_mut(i)
+            .clone_from(indices.uget((row, i)));
+    }
+    for i in except_axis_index + 1..indices.ncols() {
+        index_buffer
+            .get_unchecked_mut(i - 1)
+            .clone_from(indices.uget((row, i)));
     }
 }
@@ -0,0 +1,207 @@
+use super::coo_mttkrp::{accumulate_per_thread, mttkrp_matrices};
+use crate::structs::axis::Axis;
+use crate::structs::tensor::{COOTensor, COOTensorInner, HiCOOTensor};
+use crate::structs::vec::{smallvec, SmallVec};
+use crate::traits::{IdxType, RawParts, Tensor, ValType};
+use crate::utils::tracer::Tracer;
+use anyhow::{anyhow, bail, Result};
+use ndarray::{Array2, ArrayView2};
+use scopeguard::defer;
+use std::ops::Range;
+
+/// Matricized `HiCOOTensor` times Khatri-Rao product of `DenseMatrix`es.
+///
+/// Each matrix has two dense axes, the first one is an axis of the tensor, the second one is the rank axis shared by all matrices.
+/// The result is a dense matrix with the output axis and the rank axis.
+pub struct HiCOOTensorMTTKRP<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    pub tensor: &'a HiCOOTensor<IT, VT>,
+    pub matrices: &'a [COOTensor<IT, VT>],
+    pub axis: &'a Axis<IT>,
+
+    pub tracer: Tracer,
+    pub multi_thread: bool,
+}
+
+impl<'a, IT, VT> HiCOOTensorMTTKRP<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Create a new `HiCOOTensorMTTKRP` task.
+    ///
+    /// `matrices` must contain one matrix for each axis of the tensor except `axis`.
+    /// A matrix for `axis` itself is allowed, but ignored.
+    #[must_use]
+    pub fn new(
+        tensor: &'a HiCOOTensor<IT, VT>,
+        matrices: &'a [COOTensor<IT, VT>],
+        axis: &'a Axis<IT>,
+    ) -> Self {
+        Self {
+            tensor,
+            matrices,
+            axis,
+            tracer: Tracer::new_dummy(),
+            multi_thread: false,
+        }
+    }
+
+    /// Enable performance tracing.
+    #[must_use]
+    pub fn trace(mut self, tracer: &Tracer) -> Self {
+        self.tracer.clone_from(tracer);
+        self
+    }
+
+    /// Perform the MTTKRP.
+    pub fn execute(self) -> Result<COOTensor<IT, VT>>
+    where
+        IT: IdxType,
+        VT: ValType,
+    {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("HiCOOTensorMTTKRP");
+        }
+
+        let shape = self.tensor.shape();
+        let output_axis_index = shape
+            .iter()
+            .position(|ax| ax == self.axis)
+            .ok_or_else(|| anyhow!("axis {} not found", self.axis))?;
+        if shape.len() < 2 {
+            bail!("The tensor must have at least 2 axes.");
+        }
+
+        let (matrix_values, rank_axis) = mttkrp_matrices(shape, self.matrices, output_axis_index)?;
+
+        let result_values = if self.multi_thread {
+            self.compute_values_multi_thread(&matrix_values, output_axis_index, rank_axis.len())
+        } else {
+            self.compute_values(&matrix_values, output_axis_index, rank_axis.len())
+        };
+
+        let result_shape: SmallVec<_> = smallvec![self.axis.clone(), rank_axis];
+        let result = COOTensorInner {
+            name: None,
+            shape: result_shape.clone(),
+            sparse_axes: SmallVec::new(),
+            dense_axes: result_shape,
+            indices: Array2::zeros((1, 0)),
+            values: result_values.insert_axis(ndarray::Axis(0)).into_dyn(),
+            sparse_is_sorted: true,
+            sparse_sort_order: SmallVec::new(),
+            sparse_is_coalesced: true,
+        };
+
+        Ok(
+            // # Safety
+            // We make sure the tensor is in valid state.
+            unsafe { COOTensor::from_raw_parts(result) },
+        )
+    }
+
+    fn compute_values(
+        &self,
+        matrix_values: &[Option<ArrayView2<VT>>],
+        output_axis_index: usize,
+        rank: usize,
+    ) -> Array2<VT>
+    where
+        IT: IdxType,
+        VT: ValType,
+    {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("HiCOOTensorMTTKRP::compute_values");
+        }
+
+        let mut result_values = Array2::<VT>::zeros((self.axis.len(), rank));
+        self.compute_blocks(
+            0..self.tensor.num_blocks(),
+            matrix_values,
+            output_axis_index,
+            &mut result_values,
+        );
+        result_values
+    }
+
+    fn compute_values_multi_thread(
+        &self,
+        matrix_values: &[Option<ArrayView2<VT>>],
+        output_axis_index: usize,
+        rank: usize,
+    ) -> Array2<VT>
+    where
+        IT: IdxType,
+        VT: ValType,
+    {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("HiCOOTensorMTTKRP::compute_values_multi_thread");
+        }
+
+        let result_shape = (self.axis.len(), rank);
+        // Each thread accumulates into its own copy of the result, one superblock at a time.
+        accumulate_per_thread(
+            self.tensor.num_superblocks(),
+            result_shape,
+            |superblock, result_values| {
+                self.compute_blocks(
+                    self.tensor.superblock_range(superblock),
+                    matrix_values,
+                    output_axis_index,
+                    result_values,
+                );
+            },
+        )
+    }
+
+    fn compute_blocks(
+        &self,
+        blocks: Range<usize>,
+        matrix_values: &[Option<ArrayView2<VT>>],
+        output_axis_index: usize,
+        result_values: &mut Array2<VT>,
+    ) where
+        IT: IdxType,
+        VT: ValType,
+    {
+        let raw_parts = self.tensor.raw_parts();
+        let block_bits = raw_parts.block_bits;
+        let rank = result_values.ncols();
+        let mut row_buffer = vec![VT::zero(); rank];
+
+        for block in blocks {
+            let block_index = raw_parts.block_indices.row(block);
+            for element in self.tensor.block_range(block) {
+                let element_index = raw_parts.element_indices.row(element);
+                row_buffer.fill(raw_parts.values[element].clone());
+                let mut output_offset = 0;
+                for (axis_idx, matrix) in matrix_values.iter().enumerate() {
+                    let offset = (block_index[axis_idx].to_usize().unwrap() << block_bits)
+                        + element_index[axis_idx] as usize;
+                    if let Some(matrix) = matrix {
+                        for (k, value) in row_buffer.iter_mut().enumerate() {
+                            // # Safety
+                            // offset < axis.len() == matrix.nrows()
+                            // k < rank == matrix.ncols()
+                            *value = value.clone() * unsafe { matrix.uget((offset, k)) }.clone();
+                        }
+                    } else {
+                        debug_assert_eq!(axis_idx, output_axis_index);
+                        output_offset = offset;
+                    }
+                }
+                for (k, value) in row_buffer.iter().enumerate() {
+                    let result = &mut result_values[(output_offset, k)];
+                    *result = result.clone() + value.clone();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,367 @@
+use crate::structs::tensor::{COOTensor, COOTensorInner, HiCOOTensor};
+use crate::structs::vec::{smallvec, SmallVec};
+use crate::traits::{IdxType, RawParts, Tensor, ValType};
+use crate::utils::tracer::Tracer;
+use anyhow::{anyhow, bail, Result};
+use ndarray::{Array2, ArrayView2, ArrayViewMut1, Ix3};
+use num::NumCast;
+use rayon::prelude::*;
+use scopeguard::defer;
+use std::cmp::Ordering;
+
+/// Multiply a `HiCOOTensor` with a `DenseMatrix`.
+///
+/// The result is a `COOTensor`, where the common axis is replaced by a dense axis.
+pub struct HiCOOTensorMulDenseMatrix<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    pub tensor: &'a HiCOOTensor<IT, VT>,
+    pub matrix: &'a COOTensor<IT, VT>,
+
+    pub tracer: Tracer,
+    pub multi_thread: bool,
+}
+
+impl<'a, IT, VT> HiCOOTensorMulDenseMatrix<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Create a new `HiCOOTensorMulDenseMatrix` task.
+    #[must_use]
+    pub fn new(tensor: &'a HiCOOTensor<IT, VT>, matrix: &'a COOTensor<IT, VT>) -> Self {
+        Self {
+            tensor,
+            matrix,
+            tracer: Tracer::new_dummy(),
+            multi_thread: false,
+        }
+    }
+
+    /// Enable performance tracing.
+    #[must_use]
+    pub fn trace(mut self, tracer: &Tracer) -> Self {
+        self.tracer.clone_from(tracer);
+        self
+    }
+
+    /// Perform the multiplication.
+    ///
+    /// The first axis of the matrix must be one of the axes of the tensor.
+    pub fn execute(self) -> Result<COOTensor<IT, VT>>
+    where
+        IT: IdxType,
+        VT: ValType,
+    {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("HiCOOTensorMulDenseMatrix");
+        }
+
+        // Check if the matrix has 2 axes.
+        if self.matrix.ndim() != 2 {
+            bail!("The matrix must have 2 axes.");
+        }
+        // Check if the matrix is fully dense.
+        if !self.matrix.sparse_axes().is_empty() {
+            bail!("The matrix must be fully dense.");
+        }
+
+        // Map the common axis of the tensor and the matrix.
+        let matrix_shape = self.matrix.dense_axes();
+        let common_axis = &matrix_shape[0];
+        let common_axis_index = self
+            .tensor
+            .shape()
+            .iter()
+            .position(|ax| ax == common_axis)
+            .ok_or_else(|| anyhow!("axis {} not found", common_axis))?;
+        if self.tensor.shape().contains(&matrix_shape[1]) {
+            bail!("There must be only one common axis.");
+        }
+
+        // Reshape the matrix into an ArrayView2.
+        let matrix_values = self
+            .matrix
+            .raw_parts()
+            .values
+            .view()
+            .into_dimensionality::<Ix3>()?
+            .index_axis_move(ndarray::Axis(0), 0);
+
+        // Create the output tensor.
+        let result_shape = self
+            .tensor
+            .shape()
+            .iter()
+            .map(|ax| {
+                if ax == common_axis {
+                    matrix_shape[1].clone()
+                } else {
+                    ax.clone()
+                }
+            })
+            .collect::<SmallVec<_>>();
+        let result_sparse_axes = self
+            .tensor
+            .shape()
+            .iter()
+            .filter(|&ax| ax != common_axis)
+            .cloned()
+            .collect::<SmallVec<_>>();
+
+        // Calculate the result indices.
+        let fibers = self.compute_indices(common_axis_index);
+
+        let result_values = if self.multi_thread {
+            self.compute_values_multi_thread(&matrix_values, &fibers, common_axis_index)
+        } else {
+            self.compute_values(&matrix_values, &fibers, common_axis_index)
+        };
+
+        let result = COOTensorInner {
+            name: None,
+            shape: result_shape,
+            sparse_axes: result_sparse_axes.clone(),
+            dense_axes: smallvec![matrix_shape[1].clone()],
+            indices: fibers.result_indices,
+            values: result_values.into_dyn(),
+            sparse_is_sorted: true,
+            sparse_sort_order: result_sparse_axes,
+            sparse_is_coalesced: true,
+        };
+
+        Ok(
+            // # Safety
+            // We make sure the tensor is in valid state.
+            unsafe { COOTensor::from_raw_parts(result) },
+        )
+    }
+
+    /// Group the non-zero elements of each block by their output fibers, then map them to the output fibers of the whole tensor.
+    fn compute_indices(&self, common_axis_index: usize) -> BlockFibers<IT> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("HiCOOTensorMulDenseMatrix::compute_indices");
+        }
+
+        let raw_parts = self.tensor.raw_parts();
+        let block_bits = raw_parts.block_bits;
+        let ndim = raw_parts.shape.len();
+        let num_non_zeros = raw_parts.values.len();
+        let element_indices = &raw_parts.element_indices;
+
+        // Compare two elements of the same block, ignoring the common axis.
+        let compare_elements = |a: usize, b: usize| {
+            let a = element_indices.row(a);
+            let b = element_indices.row(b);
+            a.iter()
+                .zip(b.iter())
+                .enumerate()
+                .filter(|&(axis_idx, _)| axis_idx != common_axis_index)
+                .map(|(_, (a, b))| a.cmp(b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        };
+
+        // Sort the elements inside each block, so each fiber of the block is a contiguous range.
+        let mut block_fiber_offsets = Vec::with_capacity(self.tensor.num_blocks() + 1);
+        let mut fiber_element_offsets = Vec::new();
+        let mut fiber_elements = Vec::with_capacity(num_non_zeros);
+        let mut fiber_keys = Vec::new();
+        for block in 0..self.tensor.num_blocks() {
+            block_fiber_offsets.push(fiber_element_offsets.len());
+            let block_index = raw_parts.block_indices.row(block);
+            let block_begin = fiber_elements.len();
+            fiber_elements.extend(self.tensor.block_range(block));
+            fiber_elements[block_begin..].sort_unstable_by(|&a, &b| compare_elements(a, b));
+            for i in block_begin..fiber_elements.len() {
+                let element = fiber_elements[i];
+                if i != block_begin && compare_elements(fiber_elements[i - 1], element).is_eq() {
+                    continue;
+                }
+                fiber_element_offsets.push(i);
+                let element_index = element_indices.row(element);
+                fiber_keys.extend(
+                    raw_parts
+                        .shape
+                        .iter()
+                        .enumerate()
+                        .filter(|&(axis_idx, _)| axis_idx != common_axis_index)
+                        .map(|(axis_idx, axis)| {
+                            let offset = (block_index[axis_idx].to_usize().unwrap() << block_bits)
+                                + element_index[axis_idx] as usize;
+                            axis.lower() + <IT as NumCast>::from(offset).unwrap()
+                        }),
+                );
+            }
+        }
+        block_fiber_offsets.push(fiber_element_offsets.len());
+        fiber_element_offsets.push(num_non_zeros);
+
+        // Blocks that only differ along the common axis share the same output fibers.
+        let num_block_fibers = fiber_element_offsets.len() - 1;
+        let fiber_keys = Array2::from_shape_vec((num_block_fibers, ndim - 1), fiber_keys).unwrap();
+        let mut fiber_order = (0..num_block_fibers).collect::<Vec<_>>();
+        fiber_order
+            .sort_unstable_by(|&a, &b| fiber_keys.row(a).iter().cmp(fiber_keys.row(b).iter()));
+
+        let mut result_indices = Vec::new();
+        let mut result_fiber_offsets = Vec::new();
+        let mut result_fibers = vec![0; num_block_fibers];
+        for (i, &fiber) in fiber_order.iter().enumerate() {
+            if i == 0 || fiber_keys.row(fiber) != fiber_keys.row(fiber_order[i - 1]) {
+                result_indices.extend(fiber_keys.row(fiber).iter().cloned());
+                result_fiber_offsets.push(i);
+            }
+            result_fibers[fiber] = result_fiber_offsets.len() - 1;
+        }
+        let num_result_fibers = result_fiber_offsets.len();
+        result_fiber_offsets.push(num_block_fibers);
+
+        let mut fiber_blocks = Vec::with_capacity(num_block_fibers);
+        for block in 0..self.tensor.num_blocks() {
+            let num_fibers = block_fiber_offsets[block + 1] - block_fiber_offsets[block];
+            fiber_blocks.extend(std::iter::repeat_n(block, num_fibers));
+        }
+
+        BlockFibers {
+            result_indices: Array2::from_shape_vec((num_result_fibers, ndim - 1), result_indices)
+                .unwrap(),
+            block_fiber_offsets,
+            fiber_blocks,
+            fiber_element_offsets,
+            fiber_elements,
+            result_fibers,
+            result_fiber_offsets,
+            sorted_fibers: fiber_order,
+        }
+    }
+
+    fn compute_values(
+        &self,
+        matrix_values: &ArrayView2<VT>,
+        fibers: &BlockFibers<IT>,
+        common_axis_index: usize,
+    ) -> Array2<VT>
+    where
+        IT: IdxType,
+        VT: ValType,
+    {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("HiCOOTensorMulDenseMatrix::compute_values");
+        }
+
+        let num_fibers = fibers.result_indices.nrows();
+        let mut result_values = Array2::<VT>::zeros((num_fibers, matrix_values.ncols()));
+        // Walk the blocks in storage order, so the matrix rows being accessed stay inside each block.
+        for block in 0..self.tensor.num_blocks() {
+            for fiber in fibers.block_fiber_offsets[block]..fibers.block_fiber_offsets[block + 1] {
+                self.compute_fiber(
+                    fiber,
+                    matrix_values,
+                    fibers,
+                    common_axis_index,
+                    result_values.row_mut(fibers.result_fibers[fiber]),
+                );
+            }
+        }
+        result_values
+    }
+
+    fn compute_values_multi_thread(
+        &self,
+        matrix_values: &ArrayView2<VT>,
+        fibers: &BlockFibers<IT>,
+        common_axis_index: usize,
+    ) -> Array2<VT>
+    where
+        IT: IdxType,
+        VT: ValType,
+    {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("HiCOOTensorMulDenseMatrix::compute_values_multi_thread");
+        }
+
+        let num_fibers = fibers.result_indices.nrows();
+        let mut result_values = Array2::<VT>::zeros((num_fibers, matrix_values.ncols()));
+        // Each task owns whole rows of the result, and adds up the fibers of every block contributing to them.
+        result_values
+            .outer_iter_mut()
+            .into_par_iter()
+            .with_min_len(256)
+            .enumerate()
+            .for_each(|(i, mut result_row)| {
+                let block_fibers = &fibers.sorted_fibers
+                    [fibers.result_fiber_offsets[i]..fibers.result_fiber_offsets[i + 1]];
+                for &fiber in block_fibers {
+                    self.compute_fiber(
+                        fiber,
+                        matrix_values,
+                        fibers,
+                        common_axis_index,
+                        result_row.view_mut(),
+                    );
+                }
+            });
+        result_values
+    }
+
+    /// Add the product of one fiber of a block and the matrix to `result_row`.
+    fn compute_fiber(
+        &self,
+        fiber: usize,
+        matrix_values: &ArrayView2<VT>,
+        fibers: &BlockFibers<IT>,
+        common_axis_index: usize,
+        mut result_row: ArrayViewMut1<VT>,
+    ) where
+        IT: IdxType,
+        VT: ValType,
+    {
+        let raw_parts = self.tensor.raw_parts();
+        let block = fibers.fiber_blocks[fiber];
+        let common_base = raw_parts.block_indices[(block, common_axis_index)]
+            .to_usize()
+            .unwrap()
+            << raw_parts.block_bits;
+        let elements = &fibers.fiber_elements
+            [fibers.fiber_element_offsets[fiber]..fibers.fiber_element_offsets[fiber + 1]];
+        for &element in elements {
+            let r = common_base + raw_parts.element_indices[(element, common_axis_index)] as usize;
+            let tensor_value = &raw_parts.values[element];
+            for (k, value) in result_row.iter_mut().enumerate() {
+                // # Safety
+                // r < common_axis.len() == matrix_values.nrows()
+                // k < result_row.len() == matrix_values.ncols()
+                *value = value.clone()
+                    + tensor_value.clone() * unsafe { matrix_values.uget((r, k)) }.clone();
+            }
+        }
+    }
+}
+
+/// Non-zero elements grouped by their output fibers inside each block.
+struct BlockFibers<IT>
+where
+    IT: IdxType,
+{
+    /// Index of each output fiber, sorted lexicographically.
+    result_indices: Array2<IT>,
+    /// The fibers of block `i` are `block_fiber_offsets[i]..block_fiber_offsets[i + 1]`.
+    block_fiber_offsets: Vec<usize>,
+    /// The block of each fiber.
+    fiber_blocks: Vec<usize>,
+    /// The elements of fiber `j` are `fiber_elements[fiber_element_offsets[j]..fiber_element_offsets[j + 1]]`.
+    fiber_element_offsets: Vec<usize>,
+    fiber_elements: Vec<usize>,
+    /// The output fiber of each fiber.
+    result_fibers: Vec<usize>,
+    /// The fibers adding up to output fiber `i` are `sorted_fibers[result_fiber_offsets[i]..result_fiber_offsets[i + 1]]`.
+    result_fiber_offsets: Vec<usize>,
+    sorted_fibers: Vec<usize>,
+}
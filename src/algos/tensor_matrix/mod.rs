@@ -1,7 +1,16 @@
 //! Algorithms related to tensors and matrices.
 
+mod coo_mttkrp;
+mod coo_mul_csr;
 mod coo_mul_dense;
+mod hicoo_mttkrp;
+mod hicoo_mul_dense;
 mod scoo_mul_dense;
 
+pub use coo_mttkrp::COOTensorMTTKRP;
+pub use coo_mul_csr::COOTensorMulCSRMatrix;
 pub use coo_mul_dense::COOTensorMulDenseMatrix;
+pub(crate) use coo_mul_dense::{copy_index_except_axis, index_eq_except_axis};
+pub use hicoo_mttkrp::HiCOOTensorMTTKRP;
+pub use hicoo_mul_dense::HiCOOTensorMulDenseMatrix;
 pub use scoo_mul_dense::SemiCOOTensorMulDenseMatrix;
@@ -1,7 +1,7 @@
 use std::iter;
 
 use crate::structs::axis::{map_axes, Axis};
-use crate::structs::tensor::{COOTensor, COOTensorInner};
+use crate::structs::tensor::{COOTensor, COOTensorInner, COOTensorView};
 use crate::structs::vec::{smallvec, SmallVec};
 use crate::traits::{IdxType, RawParts, Tensor, ValType};
 use crate::utils::tracer::Tracer;
@@ -16,7 +16,7 @@ where
     IT: IdxType,
     VT: ValType,
 {
-    pub tensor: &'a COOTensor<IT, VT>,
+    pub tensor: COOTensorView<'a, IT, VT>,
     pub matrix: &'a COOTensor<IT, VT>,
 
     pub tracer: Tracer,
@@ -30,9 +30,12 @@ where
 {
     /// Create a new `SemiCOOTensorMulDenseMatrix` task.
     #[must_use]
-    pub fn new(tensor: &'a COOTensor<IT, VT>, matrix: &'a COOTensor<IT, VT>) -> Self {
+    pub fn new(
+        tensor: impl Into<COOTensorView<'a, IT, VT>>,
+        matrix: &'a COOTensor<IT, VT>,
+    ) -> Self {
         Self {
-            tensor,
+            tensor: tensor.into(),
             matrix,
             tracer: Tracer::new_dummy(),
             multi_thread: false,
@@ -101,14 +104,12 @@ where
             .product::<usize>();
 
         // Extract the contents from the inputs.
-        let tensor_indices = &self.tensor.raw_parts().indices;
+        let tensor_indices = &self.tensor.indices();
         // Reshape the matrix into an ArrayView2.
         // Rows are each dense block, and columns are linearized elements inside the dense block.
         let tensor_values = self
             .tensor
-            .raw_parts()
-            .values
-            .view()
+            .values()
             .into_shape((self.tensor.num_blocks(), dense_block_size))?;
         let matrix_shape = self.matrix.shape();
         // Reshape the matrix into an ArrayView2.
@@ -196,6 +197,7 @@ where
             values: result_values,
             sparse_is_sorted: true,
             sparse_sort_order,
+            sparse_is_coalesced: true,
         };
 
         Ok(
@@ -207,7 +209,7 @@ where
 
     fn compute_indices(
         &self,
-        tensor_indices: &Array2<IT>,
+        tensor_indices: &ArrayView2<IT>,
         common_axis_index: usize,
     ) -> (Array2<IT>, Vec<usize>)
     where
@@ -260,7 +262,7 @@ where
     #[allow(clippy::too_many_arguments)]
     fn compute_values(
         &self,
-        tensor_indices: &Array2<IT>,
+        tensor_indices: &ArrayView2<IT>,
         tensor_values: &ArrayView2<VT>,
         matrix_values: &ArrayView2<VT>,
         result_indices: &Array2<IT>,
@@ -328,7 +330,7 @@ where
     #[allow(clippy::too_many_arguments)]
     fn compute_values_multi_thread(
         &self,
-        tensor_indices: &Array2<IT>,
+        tensor_indices: &ArrayView2<IT>,
         tensor_values: &ArrayView2<VT>,
         matrix_values: &ArrayView2<VT>,
         result_indices: &Array2<IT>,
@@ -404,7 +406,7 @@ where
     /// Make sure row_{a,b} < indices.nrows()
     unsafe fn index_eq_except_axis(
         &self,
-        indices: &Array2<IT>,
+        indices: &ArrayView2<IT>,
         row_a: usize,
         row_b: usize,
         except_axis_index: usize,
@@ -431,7 +433,7 @@ where
     /// Make sure index_buffer.len() == indices.ncols() - 1
     unsafe fn copy_index_except_axis(
         &self,
-        indices: &Array2<IT>,
+        indices: &ArrayView2<IT>,
         row: usize,
         except_axis_index: usize,
         index_buffer: &mut [IT],
@@ -0,0 +1,732 @@
+use crate::structs::axis::{Axes, Axis};
+use crate::structs::tensor::{COOTensor, COOTensorInner};
+use crate::structs::vec::SmallVec;
+use crate::traits::{IdxType, RawParts, Tensor, ValType};
+use crate::utils::tracer::Tracer;
+use anyhow::{anyhow, bail, Result};
+use ndarray::{s, Array2, Array3, Array5, ArrayD, ArrayView1, IxDyn};
+use num::NumCast;
+use rayon::prelude::*;
+use scopeguard::defer;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::iter;
+
+/// Contract two `COOTensor`s over their shared axes.
+///
+/// By default, axes that appear in both tensors are summed over, and the result keeps the remaining axes of `a` followed by the remaining axes of `b`.
+/// An einsum-like spec string can be used instead to bind axes by letters, see [`ContractCOOTensors::spec`].
+///
+/// Both tensors may contain sparse and dense axes.
+/// An axis of the result is dense only if it is dense in every input it comes from.
+/// Blocks are matched by their indices along the axes that are sparse in both inputs, and multiplied block by block.
+pub struct ContractCOOTensors<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    pub a: &'a COOTensor<IT, VT>,
+    pub b: &'a COOTensor<IT, VT>,
+    pub spec: Option<&'a str>,
+
+    pub tracer: Tracer,
+    pub multi_thread: bool,
+}
+
+impl<'a, IT, VT> ContractCOOTensors<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Create a new `ContractCOOTensors` task.
+    #[must_use]
+    pub fn new(a: &'a COOTensor<IT, VT>, b: &'a COOTensor<IT, VT>) -> Self {
+        Self {
+            a,
+            b,
+            spec: None,
+            tracer: Tracer::new_dummy(),
+            multi_thread: false,
+        }
+    }
+
+    /// Bind axes with an einsum-like spec string, such as `"ij,jk->ik"`.
+    ///
+    /// Each letter on the left side binds the axis at the same position in the shape of `a` or `b`.
+    /// Axes bound to the same letter are matched even if they are different [`Axis`] objects, as long as they have the same length.
+    /// Letters missing from the right side are summed over.
+    /// If `->` is omitted, the result keeps the letters that appear only once, in alphabetical order.
+    #[must_use]
+    pub fn spec(mut self, spec: &'a str) -> Self {
+        self.spec = Some(spec);
+        self
+    }
+
+    /// Enable performance tracing.
+    #[must_use]
+    pub fn trace(mut self, tracer: &Tracer) -> Self {
+        self.tracer.clone_from(tracer);
+        self
+    }
+
+    /// Perform the contraction.
+    ///
+    /// The inputs do not need to be sorted.
+    /// The result is sorted by its sparse axes.
+    pub fn execute(self) -> Result<COOTensor<IT, VT>> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("ContractCOOTensors");
+        }
+
+        let bindings = match self.spec {
+            Some(spec) => Bindings::from_spec(self.a, self.b, spec)?,
+            None => Bindings::from_axes(self.a, self.b),
+        };
+
+        let plan = BlockPlan::new(&bindings, self.a, self.b);
+        let a_blocks = plan.prepare_a(&bindings, self.a);
+        let b_blocks = plan.prepare_b(&bindings, self.b);
+        let b_index = self.build_index(&plan, &b_blocks);
+
+        let result_values = if self.multi_thread {
+            self.compute_values_multi_thread(&plan, &a_blocks, &b_blocks, &b_index)
+        } else {
+            self.compute_values(&plan, &a_blocks, &b_blocks, &b_index)
+        };
+
+        self.build_result(&bindings, &plan, result_values)
+    }
+
+    /// Group the blocks of `b` by their indices along the axes that are sparse in both inputs.
+    fn build_index(
+        &self,
+        plan: &BlockPlan,
+        b_blocks: &Blocks<VT>,
+    ) -> HashMap<SmallVec<usize>, Vec<usize>> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("ContractCOOTensors::build_index");
+        }
+
+        let mut b_index = HashMap::<_, Vec<_>>::new();
+        for (j, offset) in b_blocks.offsets.rows().into_iter().enumerate() {
+            let key = plan
+                .key_in_b
+                .iter()
+                .map(|&axis_idx| offset[axis_idx])
+                .collect::<SmallVec<_>>();
+            b_index.entry(key).or_default().push(j);
+        }
+        b_index
+    }
+
+    fn compute_values(
+        &self,
+        plan: &BlockPlan,
+        a_blocks: &Blocks<VT>,
+        b_blocks: &Blocks<VT>,
+        b_index: &HashMap<SmallVec<usize>, Vec<usize>>,
+    ) -> HashMap<SmallVec<usize>, Array3<VT>> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("ContractCOOTensors::compute_values");
+        }
+
+        let mut result_values = HashMap::new();
+        for i in 0..a_blocks.offsets.nrows() {
+            probe_block(plan, i, a_blocks, b_blocks, b_index, &mut result_values);
+        }
+        result_values
+    }
+
+    fn compute_values_multi_thread(
+        &self,
+        plan: &BlockPlan,
+        a_blocks: &Blocks<VT>,
+        b_blocks: &Blocks<VT>,
+        b_index: &HashMap<SmallVec<usize>, Vec<usize>>,
+    ) -> HashMap<SmallVec<usize>, Array3<VT>> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("ContractCOOTensors::compute_values_multi_thread");
+        }
+
+        // Each thread accumulates a private copy of the result, then they are merged together.
+        (0..a_blocks.offsets.nrows())
+            .into_par_iter()
+            .with_min_len(256)
+            .fold(HashMap::new, |mut result_values, i| {
+                probe_block(plan, i, a_blocks, b_blocks, b_index, &mut result_values);
+                result_values
+            })
+            .reduce(HashMap::new, |a, b| {
+                let (mut larger, smaller) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+                for (offset, block) in smaller {
+                    match larger.entry(offset) {
+                        Entry::Occupied(mut entry) => entry
+                            .get_mut()
+                            .zip_mut_with(&block, |x, y| *x = x.clone() + y.clone()),
+                        Entry::Vacant(entry) => {
+                            entry.insert(block);
+                        }
+                    }
+                }
+                larger
+            })
+    }
+
+    /// Pack the accumulated blocks into the result.
+    fn build_result(
+        &self,
+        bindings: &Bindings<IT>,
+        plan: &BlockPlan,
+        result_values: HashMap<SmallVec<usize>, Array3<VT>>,
+    ) -> Result<COOTensor<IT, VT>> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("ContractCOOTensors::build_result");
+        }
+
+        let result_shape = bindings
+            .output
+            .iter()
+            .map(|&var| bindings.axes[var].clone())
+            .collect::<Axes<_>>();
+        let result_sparse_axes = bindings
+            .output
+            .iter()
+            .filter(|&&var| !bindings.is_dense[var])
+            .map(|&var| bindings.axes[var].clone())
+            .collect::<Axes<_>>();
+        let result_dense_axes = bindings
+            .output
+            .iter()
+            .filter(|&&var| bindings.is_dense[var])
+            .map(|&var| bindings.axes[var].clone())
+            .collect::<Axes<_>>();
+
+        // Sort the blocks by their sparse indices.
+        let mut blocks = result_values.into_iter().collect::<Vec<_>>();
+        blocks.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        if blocks.is_empty() && result_sparse_axes.is_empty() {
+            // A tensor without sparse axes always has exactly one block.
+            blocks.push((SmallVec::new(), Array3::zeros(plan.block_shape)));
+        }
+
+        let num_blocks = blocks.len();
+        let mut result_indices = Vec::with_capacity(num_blocks * result_sparse_axes.len());
+        let mut result_values = Vec::with_capacity(
+            num_blocks * plan.block_shape.0 * plan.block_shape.1 * plan.block_shape.2,
+        );
+        for (sparse_offset, block) in blocks {
+            for (&offset, axis) in sparse_offset.iter().zip(result_sparse_axes.iter()) {
+                result_indices.push(axis.lower() + <IT as NumCast>::from(offset).unwrap());
+            }
+            // Reorder the dense axes of the block as they appear in the result.
+            let block = block.into_shape(IxDyn(&plan.block_dims))?;
+            result_values.extend(block.permuted_axes(IxDyn(&plan.block_perm)).iter().cloned());
+        }
+
+        let values_shape = iter::once(num_blocks)
+            .chain(result_dense_axes.iter().map(Axis::len))
+            .collect::<Vec<_>>();
+        let result = COOTensorInner {
+            name: None,
+            shape: result_shape,
+            sparse_axes: result_sparse_axes.clone(),
+            dense_axes: result_dense_axes,
+            indices: Array2::from_shape_vec(
+                (num_blocks, result_sparse_axes.len()),
+                result_indices,
+            )?,
+            values: ArrayD::from_shape_vec(IxDyn(&values_shape), result_values)?,
+            sparse_is_sorted: true,
+            sparse_sort_order: result_sparse_axes,
+            sparse_is_coalesced: true,
+        };
+
+        Ok(
+            // # Safety
+            // We make sure the tensor is in valid state.
+            unsafe { COOTensor::from_raw_parts(result) },
+        )
+    }
+}
+
+/// How the axes of both inputs and the result are bound together.
+///
+/// Each distinct axis is called a variable, which can appear in `a`, `b`, or both.
+struct Bindings<IT>
+where
+    IT: IdxType,
+{
+    /// The axis of each variable.
+    axes: Vec<Axis<IT>>,
+    /// Whether each variable is dense in every input it appears in.
+    is_dense: Vec<bool>,
+    /// The variables of the result, in order.
+    output: Vec<usize>,
+    /// The variable of each axis of `a`, in shape order.
+    a_vars: Vec<usize>,
+    /// The variable of each axis of `b`, in shape order.
+    b_vars: Vec<usize>,
+}
+
+impl<IT> Bindings<IT>
+where
+    IT: IdxType,
+{
+    /// Bind axes by their identity.
+    fn from_axes<VT>(a: &COOTensor<IT, VT>, b: &COOTensor<IT, VT>) -> Self
+    where
+        VT: ValType,
+    {
+        let (a_shape, b_shape) = (a.shape(), b.shape());
+        let mut axes = a_shape.to_vec();
+        let a_vars = (0..a_shape.len()).collect::<Vec<_>>();
+        let b_vars = b_shape
+            .iter()
+            .map(|axis| {
+                axes.iter().position(|ax| ax == axis).unwrap_or_else(|| {
+                    axes.push(axis.clone());
+                    axes.len() - 1
+                })
+            })
+            .collect::<Vec<_>>();
+        let output = a_vars
+            .iter()
+            .filter(|var| !b_vars.contains(var))
+            .chain(b_vars.iter().filter(|var| !a_vars.contains(var)))
+            .copied()
+            .collect();
+        Self::new(a, b, axes, a_vars, b_vars, output)
+    }
+
+    /// Bind axes by an einsum-like spec string.
+    fn from_spec<VT>(a: &COOTensor<IT, VT>, b: &COOTensor<IT, VT>, spec: &str) -> Result<Self>
+    where
+        VT: ValType,
+    {
+        let spec = spec
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>();
+        let (inputs, output) = match spec.split_once("->") {
+            Some((inputs, output)) => (inputs, Some(output)),
+            None => (spec.as_str(), None),
+        };
+        let (a_letters, b_letters) = inputs
+            .split_once(',')
+            .ok_or_else(|| anyhow!("The spec must have two inputs separated by a comma."))?;
+
+        let mut letters = Vec::new();
+        let mut axes = Vec::new();
+        let mut bind = |term: &str, shape: &[Axis<IT>]| -> Result<Vec<usize>> {
+            if term.chars().count() != shape.len() {
+                bail!(
+                    "The spec {:?} does not match a tensor with {} axes.",
+                    term,
+                    shape.len()
+                );
+            }
+            let mut vars = Vec::with_capacity(shape.len());
+            for (letter, axis) in term.chars().zip(shape.iter()) {
+                if !letter.is_alphabetic() {
+                    bail!("Invalid letter {:?} in spec.", letter);
+                }
+                let var = match letters.iter().position(|&l| l == letter) {
+                    Some(var) => {
+                        if vars.contains(&var) {
+                            bail!(
+                                "Repeated letter {:?} in one input is not supported.",
+                                letter
+                            );
+                        }
+                        let bound_axis: &Axis<IT> = &axes[var];
+                        if bound_axis.len() != axis.len() {
+                            bail!(
+                                "Letter {:?} binds axes of different lengths {} and {}.",
+                                letter,
+                                bound_axis,
+                                axis
+                            );
+                        }
+                        var
+                    }
+                    None => {
+                        letters.push(letter);
+                        axes.push(axis.clone());
+                        letters.len() - 1
+                    }
+                };
+                vars.push(var);
+            }
+            Ok(vars)
+        };
+        let a_vars = bind(a_letters, a.shape())?;
+        let b_vars = bind(b_letters, b.shape())?;
+
+        let output = match output {
+            Some(output) => {
+                let mut vars = Vec::new();
+                for letter in output.chars() {
+                    let var = letters
+                        .iter()
+                        .position(|&l| l == letter)
+                        .ok_or_else(|| anyhow!("Letter {:?} is not bound to any axis.", letter))?;
+                    if vars.contains(&var) {
+                        bail!("Repeated letter {:?} in the result.", letter);
+                    }
+                    vars.push(var);
+                }
+                vars
+            }
+            None => {
+                let mut vars = (0..letters.len())
+                    .filter(|var| a_vars.contains(var) != b_vars.contains(var))
+                    .collect::<Vec<_>>();
+                vars.sort_unstable_by_key(|&var| letters[var]);
+                vars
+            }
+        };
+        for (pos, &var) in output.iter().enumerate() {
+            if output[..pos].iter().any(|&other| axes[other] == axes[var]) {
+                bail!("The result would contain axis {} twice.", axes[var]);
+            }
+        }
+        Ok(Self::new(a, b, axes, a_vars, b_vars, output))
+    }
+
+    fn new<VT>(
+        a: &COOTensor<IT, VT>,
+        b: &COOTensor<IT, VT>,
+        axes: Vec<Axis<IT>>,
+        a_vars: Vec<usize>,
+        b_vars: Vec<usize>,
+        output: Vec<usize>,
+    ) -> Self
+    where
+        VT: ValType,
+    {
+        let mut is_dense = vec![true; axes.len()];
+        for (tensor, vars) in [(a, &a_vars), (b, &b_vars)] {
+            for (axis, &var) in tensor.shape().iter().zip(vars.iter()) {
+                is_dense[var] &= tensor.dense_axes().contains(axis);
+            }
+        }
+        Self {
+            axes,
+            is_dense,
+            output,
+            a_vars,
+            b_vars,
+        }
+    }
+
+    /// The variables of the sparse axes and the dense axes of `tensor`, which is bound to `vars`.
+    fn split_vars<VT>(tensor: &COOTensor<IT, VT>, vars: &[usize]) -> (Vec<usize>, Vec<usize>)
+    where
+        VT: ValType,
+    {
+        let var_of =
+            |axis: &Axis<IT>| vars[tensor.shape().iter().position(|ax| ax == axis).unwrap()];
+        (
+            tensor.sparse_axes().iter().map(var_of).collect(),
+            tensor.dense_axes().iter().map(var_of).collect(),
+        )
+    }
+
+    /// The total number of elements along `vars`.
+    fn len_of(&self, vars: &[usize]) -> usize {
+        vars.iter().map(|&var| self.axes[var].len()).product()
+    }
+}
+
+/// How the blocks of both inputs are matched and multiplied.
+///
+/// Only the axes that are sparse in both inputs are used to match blocks.
+/// For each pair of matching blocks, the dense axes that are sparse in the other input are fixed to its indices,
+/// and the rest of the dense axes are multiplied as a batch of matrices.
+struct BlockPlan {
+    /// The sparse axis positions in `a` of the variables that are sparse in both inputs.
+    key_in_a: Vec<usize>,
+    /// The sparse axis positions in `b` of the same variables as `key_in_a`.
+    key_in_b: Vec<usize>,
+    /// The sparse axis positions in `a` or `b` of the sparse variables of the result.
+    result_key: Vec<Operand>,
+    /// The sparse axis positions in `b` and the lengths of the dense variables of `a` that are sparse in `b`.
+    a_fixed: Vec<(usize, usize)>,
+    /// The sparse axis positions in `a` and the lengths of the dense variables of `b` that are sparse in `a`.
+    b_fixed: Vec<(usize, usize)>,
+    /// The variables of each group of dense axes, see [`Blocks::values`].
+    a_fixed_vars: Vec<usize>,
+    b_fixed_vars: Vec<usize>,
+    batch: Vec<usize>,
+    a_free: Vec<usize>,
+    b_free: Vec<usize>,
+    contracted: Vec<usize>,
+    a_summed: Vec<usize>,
+    b_summed: Vec<usize>,
+    /// The shape of each result block as `[batch, a_free, b_free]`.
+    block_shape: (usize, usize, usize),
+    /// The shape of each result block with every dense axis unflattened.
+    block_dims: Vec<usize>,
+    /// The permutation from `block_dims` to the dense axes of the result.
+    block_perm: Vec<usize>,
+}
+
+#[derive(Clone, Copy)]
+enum Operand {
+    A(usize),
+    B(usize),
+}
+
+/// One input of the contraction, with its dense blocks rearranged for [`probe_block`].
+struct Blocks<VT> {
+    /// The offset of each block along each sparse axis.
+    offsets: Array2<usize>,
+    /// The values of each block as `[block, fixed, batch, rows, cols]`.
+    ///
+    /// `fixed` flattens the dense axes that are sparse in the other input.
+    /// `batch` flattens the dense axes shared by both inputs and kept in the result.
+    /// For `a`, `rows` flattens its other dense axes kept in the result, and `cols` flattens the shared dense axes summed over.
+    /// For `b`, they are the other way round.
+    values: Array5<VT>,
+}
+
+impl BlockPlan {
+    fn new<IT, VT>(bindings: &Bindings<IT>, a: &COOTensor<IT, VT>, b: &COOTensor<IT, VT>) -> Self
+    where
+        IT: IdxType,
+        VT: ValType,
+    {
+        let (a_sparse, a_dense) = Bindings::split_vars(a, &bindings.a_vars);
+        let (b_sparse, b_dense) = Bindings::split_vars(b, &bindings.b_vars);
+        let position = |vars: &[usize], var: usize| vars.iter().position(|&v| v == var);
+        let in_output = |var: &usize| bindings.output.contains(var);
+
+        let (key_in_a, key_in_b) = a_sparse
+            .iter()
+            .enumerate()
+            .filter_map(|(a_pos, &var)| position(&b_sparse, var).map(|b_pos| (a_pos, b_pos)))
+            .unzip();
+        let result_key = bindings
+            .output
+            .iter()
+            .filter(|&&var| !bindings.is_dense[var])
+            .map(|&var| match position(&a_sparse, var) {
+                Some(pos) => Operand::A(pos),
+                None => Operand::B(position(&b_sparse, var).unwrap()),
+            })
+            .collect();
+
+        let a_fixed_vars = a_dense
+            .iter()
+            .copied()
+            .filter(|&var| b_sparse.contains(&var))
+            .collect::<Vec<_>>();
+        let b_fixed_vars = b_dense
+            .iter()
+            .copied()
+            .filter(|&var| a_sparse.contains(&var))
+            .collect::<Vec<_>>();
+        let (batch, contracted): (Vec<_>, Vec<_>) = a_dense
+            .iter()
+            .copied()
+            .filter(|var| b_dense.contains(var))
+            .partition(in_output);
+        let (a_free, a_summed): (Vec<_>, Vec<_>) = a_dense
+            .iter()
+            .copied()
+            .filter(|var| !bindings.b_vars.contains(var))
+            .partition(in_output);
+        let (b_free, b_summed): (Vec<_>, Vec<_>) = b_dense
+            .iter()
+            .copied()
+            .filter(|var| !bindings.a_vars.contains(var))
+            .partition(in_output);
+
+        let block_vars = batch
+            .iter()
+            .chain(a_free.iter())
+            .chain(b_free.iter())
+            .copied()
+            .collect::<Vec<_>>();
+        let block_dims = block_vars
+            .iter()
+            .map(|&var| bindings.axes[var].len())
+            .collect();
+        let block_perm = bindings
+            .output
+            .iter()
+            .filter(|&&var| bindings.is_dense[var])
+            .map(|&var| position(&block_vars, var).unwrap())
+            .collect();
+
+        Self {
+            key_in_a,
+            key_in_b,
+            result_key,
+            a_fixed: a_fixed_vars
+                .iter()
+                .map(|&var| (position(&b_sparse, var).unwrap(), bindings.axes[var].len()))
+                .collect(),
+            b_fixed: b_fixed_vars
+                .iter()
+                .map(|&var| (position(&a_sparse, var).unwrap(), bindings.axes[var].len()))
+                .collect(),
+            block_shape: (
+                bindings.len_of(&batch),
+                bindings.len_of(&a_free),
+                bindings.len_of(&b_free),
+            ),
+            block_dims,
+            block_perm,
+            a_fixed_vars,
+            b_fixed_vars,
+            batch,
+            a_free,
+            b_free,
+            contracted,
+            a_summed,
+            b_summed,
+        }
+    }
+
+    fn prepare_a<IT, VT>(&self, bindings: &Bindings<IT>, a: &COOTensor<IT, VT>) -> Blocks<VT>
+    where
+        IT: IdxType,
+        VT: ValType,
+    {
+        let groups = [
+            &self.a_fixed_vars,
+            &self.batch,
+            &self.a_free,
+            &self.contracted,
+            &self.a_summed,
+        ];
+        prepare_blocks(bindings, a, &bindings.a_vars, groups)
+    }
+
+    fn prepare_b<IT, VT>(&self, bindings: &Bindings<IT>, b: &COOTensor<IT, VT>) -> Blocks<VT>
+    where
+        IT: IdxType,
+        VT: ValType,
+    {
+        let groups = [
+            &self.b_fixed_vars,
+            &self.batch,
+            &self.contracted,
+            &self.b_free,
+            &self.b_summed,
+        ];
+        prepare_blocks(bindings, b, &bindings.b_vars, groups)
+    }
+}
+
+/// Rearrange the dense axes of `tensor` into five groups of variables, and sum over the last group.
+fn prepare_blocks<IT, VT>(
+    bindings: &Bindings<IT>,
+    tensor: &COOTensor<IT, VT>,
+    vars: &[usize],
+    groups: [&Vec<usize>; 5],
+) -> Blocks<VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    let raw_parts = tensor.raw_parts();
+    let offsets = Array2::from_shape_fn(raw_parts.indices.dim(), |(i, j)| {
+        (raw_parts.indices[(i, j)] - raw_parts.sparse_axes[j].lower())
+            .to_usize()
+            .unwrap()
+    });
+
+    let (_, dense_vars) = Bindings::split_vars(tensor, vars);
+    let perm = iter::once(0)
+        .chain(groups.iter().flat_map(|group| {
+            group
+                .iter()
+                .map(|var| dense_vars.iter().position(|v| v == var).unwrap() + 1)
+        }))
+        .collect::<Vec<_>>();
+    let [g0, g1, g2, g3, g4] = groups.map(|group| bindings.len_of(group));
+    let values = raw_parts
+        .values
+        .view()
+        .permuted_axes(IxDyn(&perm))
+        .as_standard_layout()
+        .into_owned()
+        .into_shape((offsets.nrows(), g0, g1, g2, g3, g4))
+        .unwrap()
+        .sum_axis(ndarray::Axis(5));
+    Blocks { offsets, values }
+}
+
+/// Multiply block `i` of `a` with all matching blocks of `b`, and add them to the result.
+fn probe_block<VT>(
+    plan: &BlockPlan,
+    i: usize,
+    a_blocks: &Blocks<VT>,
+    b_blocks: &Blocks<VT>,
+    b_index: &HashMap<SmallVec<usize>, Vec<usize>>,
+    result_values: &mut HashMap<SmallVec<usize>, Array3<VT>>,
+) where
+    VT: ValType,
+{
+    let a_offset = a_blocks.offsets.row(i);
+    let key = plan
+        .key_in_a
+        .iter()
+        .map(|&axis_idx| a_offset[axis_idx])
+        .collect::<SmallVec<_>>();
+    let matches = match b_index.get(&key) {
+        Some(matches) => matches,
+        None => return,
+    };
+    let b_fixed = fixed_offset(&plan.b_fixed, a_offset);
+    for &j in matches {
+        let b_offset = b_blocks.offsets.row(j);
+        let result_offset = plan
+            .result_key
+            .iter()
+            .map(|&operand| match operand {
+                Operand::A(axis_idx) => a_offset[axis_idx],
+                Operand::B(axis_idx) => b_offset[axis_idx],
+            })
+            .collect::<SmallVec<_>>();
+        let a_block =
+            a_blocks
+                .values
+                .slice(s![i, fixed_offset(&plan.a_fixed, b_offset), .., .., ..]);
+        let b_block = b_blocks.values.slice(s![j, b_fixed, .., .., ..]);
+        let result_block = result_values
+            .entry(result_offset)
+            .or_insert_with(|| Array3::zeros(plan.block_shape));
+
+        // Batched matrix multiplication, skipping the zeros in the blocks of `a`.
+        for (mut result_matrix, (a_matrix, b_matrix)) in result_block
+            .outer_iter_mut()
+            .zip(a_block.outer_iter().zip(b_block.outer_iter()))
+        {
+            for (mut result_row, a_row) in result_matrix.outer_iter_mut().zip(a_matrix.outer_iter())
+            {
+                for (a_value, b_row) in a_row.iter().zip(b_matrix.outer_iter()) {
+                    if a_value.is_zero() {
+                        continue;
+                    }
+                    result_row
+                        .zip_mut_with(&b_row, |x, y| *x = x.clone() + a_value.clone() * y.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Flatten the offsets along the fixed dense axes of one input, which are taken from the sparse indices of the other input.
+fn fixed_offset(fixed: &[(usize, usize)], offset: ArrayView1<usize>) -> usize {
+    fixed
+        .iter()
+        .fold(0, |acc, &(axis_idx, len)| acc * len + offset[axis_idx])
+}
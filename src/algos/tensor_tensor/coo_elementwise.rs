@@ -0,0 +1,498 @@
+use crate::algos::tensor::{DensifyCOOTensorAxis, SparsifyCOOTensorAxis};
+use crate::structs::axis::{map_axes, map_axes_unwrap, Axis};
+use crate::structs::tensor::{COOTensor, COOTensorInner};
+use crate::structs::vec::SmallVec;
+use crate::traits::{IdxType, RawParts, Tensor, ValType};
+use crate::utils::tracer::Tracer;
+use anyhow::{anyhow, bail, Result};
+use ndarray::{Array2, ArrayView2, ArrayViewMut1, IxDyn};
+use rayon::prelude::*;
+use scopeguard::defer;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::iter;
+
+/// The operation performed by [`ElementwiseCOOTensors`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ElementwiseOp {
+    /// `a + b`, keeping elements present in either input.
+    Add,
+    /// `a - b`, keeping elements present in either input.
+    Sub,
+    /// `a * b`, keeping only elements present in both inputs.
+    Mul,
+    /// `a / b`, keeping only elements present in both inputs.
+    ///
+    /// Where `b` is zero, including the zeros inside dense blocks, the result is zero instead of the quotient.
+    /// This keeps integer types from panicking, and floating-point types from producing infinities or NaNs.
+    Div,
+}
+
+/// Element-wise operation on two `COOTensor`s with the same set of axes.
+///
+/// Both tensors must have the same axes, but they may be in different orders.
+/// If `b` splits them differently between sparse and dense axes, it is first converted to the split of `a`
+/// with [`SparsifyCOOTensorAxis`] and [`DensifyCOOTensorAxis`].
+/// The result follows the axis order of `a`.
+/// Duplicate blocks inside one input are summed before the operation.
+pub struct ElementwiseCOOTensors<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    pub a: &'a COOTensor<IT, VT>,
+    pub b: &'a COOTensor<IT, VT>,
+    pub op: ElementwiseOp,
+
+    pub tracer: Tracer,
+    pub multi_thread: bool,
+}
+
+impl<'a, IT, VT> ElementwiseCOOTensors<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Create a new `ElementwiseCOOTensors` task.
+    #[must_use]
+    pub fn new(a: &'a COOTensor<IT, VT>, b: &'a COOTensor<IT, VT>, op: ElementwiseOp) -> Self {
+        Self {
+            a,
+            b,
+            op,
+            tracer: Tracer::new_dummy(),
+            multi_thread: false,
+        }
+    }
+
+    /// Create a new `ElementwiseCOOTensors` task computing `a + b`.
+    #[must_use]
+    pub fn add(a: &'a COOTensor<IT, VT>, b: &'a COOTensor<IT, VT>) -> Self {
+        Self::new(a, b, ElementwiseOp::Add)
+    }
+
+    /// Create a new `ElementwiseCOOTensors` task computing `a - b`.
+    #[must_use]
+    pub fn sub(a: &'a COOTensor<IT, VT>, b: &'a COOTensor<IT, VT>) -> Self {
+        Self::new(a, b, ElementwiseOp::Sub)
+    }
+
+    /// Create a new `ElementwiseCOOTensors` task computing `a * b`.
+    #[must_use]
+    pub fn mul(a: &'a COOTensor<IT, VT>, b: &'a COOTensor<IT, VT>) -> Self {
+        Self::new(a, b, ElementwiseOp::Mul)
+    }
+
+    /// Create a new `ElementwiseCOOTensors` task computing `a / b`.
+    #[must_use]
+    pub fn div(a: &'a COOTensor<IT, VT>, b: &'a COOTensor<IT, VT>) -> Self {
+        Self::new(a, b, ElementwiseOp::Div)
+    }
+
+    /// Enable performance tracing.
+    #[must_use]
+    pub fn trace(mut self, tracer: &Tracer) -> Self {
+        self.tracer.clone_from(tracer);
+        self
+    }
+
+    /// Perform the operation.
+    ///
+    /// If both inputs are sorted in the same order, they are merged and the result is sorted too.
+    /// Otherwise, blocks are matched with a hash table and the result is unsorted.
+    pub fn execute(self) -> Result<COOTensor<IT, VT>> {
+        if self.a.ndim() != self.b.ndim()
+            || !self
+                .a
+                .shape()
+                .iter()
+                .all(|axis| self.b.shape().contains(axis))
+        {
+            bail!("Both tensors must have the same axes.");
+        }
+        if self.a.sparse_axes().len() != self.b.sparse_axes().len()
+            || !self
+                .a
+                .sparse_axes()
+                .iter()
+                .all(|axis| self.b.sparse_axes().contains(axis))
+        {
+            let b = self.convert_b()?;
+            return ElementwiseCOOTensors {
+                a: self.a,
+                b: &b,
+                op: self.op,
+                tracer: self.tracer,
+                multi_thread: self.multi_thread,
+            }
+            .execute();
+        }
+
+        let event = self.tracer.start();
+        defer! {
+            event.finish("ElementwiseCOOTensors");
+        }
+
+        let a_sparse_axes = self.a.sparse_axes();
+        let a_dense_axes = self.a.dense_axes();
+        // Find the axes of `a` in `b`.
+        let a_sparse_in_b = map_axes(a_sparse_axes, self.b.sparse_axes())
+            .collect::<Result<SmallVec<_>, _>>()
+            .map_err(|err| anyhow!("{}", err))?;
+        let a_dense_in_b = map_axes(a_dense_axes, self.b.dense_axes())
+            .collect::<Result<SmallVec<_>, _>>()
+            .map_err(|err| anyhow!("{}", err))?;
+
+        // Reshape the values into ArrayView2s.
+        // Rows are each dense block, and columns are linearized elements inside the dense block.
+        let dense_block_size = a_dense_axes.iter().map(Axis::len).product::<usize>();
+        let a_values = self.a.raw_parts().values.as_standard_layout();
+        let a_values = a_values
+            .view()
+            .into_shape((self.a.num_blocks(), dense_block_size))?;
+        let b_values = self.b.raw_parts().values.view().permuted_axes(IxDyn(
+            &iter::once(0)
+                .chain(a_dense_in_b.iter().map(|&axis_idx| axis_idx + 1))
+                .collect::<Vec<_>>(),
+        ));
+        let b_values = b_values.as_standard_layout();
+        let b_values = b_values
+            .view()
+            .into_shape((self.b.num_blocks(), dense_block_size))?;
+
+        let merge_order = match (self.a.sparse_sort_order(), self.b.sparse_sort_order()) {
+            (Some(a_order), Some(b_order)) if a_order == b_order => Some(a_order),
+            _ => None,
+        };
+        let blocks = match merge_order {
+            Some(order) => self.compute_indices_merge(order, &a_sparse_in_b),
+            None => self.compute_indices_hash(&a_sparse_in_b),
+        };
+
+        let mut result_values = Array2::<VT>::zeros((blocks.num_blocks(), dense_block_size));
+        if self.multi_thread {
+            self.compute_values_multi_thread(&a_values, &b_values, &blocks, &mut result_values);
+        } else {
+            self.compute_values(&a_values, &b_values, &blocks, &mut result_values);
+        }
+
+        let result_values_shape = iter::once(blocks.num_blocks())
+            .chain(a_dense_axes.iter().map(Axis::len))
+            .collect::<Vec<_>>();
+        let result = COOTensorInner {
+            name: None,
+            shape: self.a.shape().into(),
+            sparse_axes: a_sparse_axes.into(),
+            dense_axes: a_dense_axes.into(),
+            indices: blocks.result_indices,
+            values: result_values.into_shape(IxDyn(&result_values_shape))?,
+            sparse_is_sorted: merge_order.is_some(),
+            sparse_sort_order: merge_order.unwrap_or(a_sparse_axes).into(),
+            sparse_is_coalesced: true,
+        };
+
+        Ok(
+            // # Safety
+            // We make sure the tensor is in valid state.
+            unsafe { COOTensor::from_raw_parts(result) },
+        )
+    }
+
+    /// Convert `b` to the same sparse axes and dense axes as `a`.
+    fn convert_b(&self) -> Result<COOTensor<IT, VT>> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("ElementwiseCOOTensors::convert_b");
+        }
+
+        let mut b = Cow::Borrowed(self.b);
+        for axis in self.b.dense_axes() {
+            if self.a.sparse_axes().contains(axis) {
+                b = Cow::Owned(
+                    SparsifyCOOTensorAxis::new(b.as_ref(), axis)
+                        .trace(&self.tracer)
+                        .execute()?,
+                );
+            }
+        }
+        for axis in self.b.sparse_axes() {
+            if self.a.dense_axes().contains(axis) {
+                b = Cow::Owned(
+                    DensifyCOOTensorAxis::new(b.as_ref(), axis)
+                        .trace(&self.tracer)
+                        .execute()?,
+                );
+            }
+        }
+        Ok(b.into_owned())
+    }
+
+    /// Match blocks of both inputs by merging them along the sort order.
+    fn compute_indices_merge(&self, order: &[Axis<IT>], a_sparse_in_b: &[usize]) -> Blocks<IT> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("ElementwiseCOOTensors::compute_indices_merge");
+        }
+
+        let a_indices = &self.a.raw_parts().indices;
+        let b_indices = &self.b.raw_parts().indices;
+        let a_order = map_axes_unwrap(order, self.a.sparse_axes()).collect::<SmallVec<_>>();
+        let b_order = map_axes_unwrap(order, self.b.sparse_axes()).collect::<SmallVec<_>>();
+        let a_key = |i: usize| {
+            a_order
+                .iter()
+                .map(move |&axis_idx| a_indices[(i, axis_idx)])
+        };
+        let b_key = |j: usize| {
+            b_order
+                .iter()
+                .map(move |&axis_idx| b_indices[(j, axis_idx)])
+        };
+
+        let (num_a, num_b) = (a_indices.nrows(), b_indices.nrows());
+        let mut blocks = Blocks::new(self.a.sparse_axes().len());
+        let (mut i, mut j) = (0, 0);
+        while i < num_a || j < num_b {
+            let ordering = if i >= num_a {
+                Ordering::Greater
+            } else if j >= num_b {
+                Ordering::Less
+            } else {
+                a_key(i).cmp(b_key(j))
+            };
+            let (a_begin, b_begin) = (i, j);
+            if ordering != Ordering::Greater {
+                // Consume all duplicates of this index in `a`.
+                i += 1;
+                while i < num_a && a_key(i).eq(a_key(a_begin)) {
+                    i += 1;
+                }
+            }
+            if ordering != Ordering::Less {
+                j += 1;
+                while j < num_b && b_key(j).eq(b_key(b_begin)) {
+                    j += 1;
+                }
+            }
+            if !self.keeps(i > a_begin, j > b_begin) {
+                continue;
+            }
+            if i > a_begin {
+                blocks.push(
+                    a_indices.row(a_begin).iter().copied(),
+                    a_begin..i,
+                    b_begin..j,
+                );
+            } else {
+                blocks.push(
+                    b_index_in_a_order(b_indices.row(b_begin).iter(), a_sparse_in_b),
+                    a_begin..i,
+                    b_begin..j,
+                );
+            }
+        }
+        blocks
+    }
+
+    /// Match blocks of both inputs with a hash table.
+    fn compute_indices_hash(&self, a_sparse_in_b: &[usize]) -> Blocks<IT> {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("ElementwiseCOOTensors::compute_indices_hash");
+        }
+
+        let a_indices = &self.a.raw_parts().indices;
+        let b_indices = &self.b.raw_parts().indices;
+        let a_sparse_axes = self.a.sparse_axes();
+        let offset_key = |index: &mut dyn Iterator<Item = IT>| {
+            index
+                .zip(a_sparse_axes.iter())
+                .map(|(index, axis)| (index - axis.lower()).to_usize().unwrap())
+                .collect::<SmallVec<_>>()
+        };
+
+        // Collect the blocks of each distinct index, in order of first appearance.
+        let mut block_map = HashMap::new();
+        let mut block_list = Vec::<(SmallVec<usize>, Vec<usize>, Vec<usize>)>::new();
+        for (i, index) in a_indices.rows().into_iter().enumerate() {
+            let key = offset_key(&mut index.iter().copied());
+            let block = *block_map.entry(key.clone()).or_insert_with(|| {
+                block_list.push((key, Vec::new(), Vec::new()));
+                block_list.len() - 1
+            });
+            block_list[block].1.push(i);
+        }
+        for (j, index) in b_indices.rows().into_iter().enumerate() {
+            let key = offset_key(&mut b_index_in_a_order(index.iter(), a_sparse_in_b));
+            let block = *block_map.entry(key.clone()).or_insert_with(|| {
+                block_list.push((key, Vec::new(), Vec::new()));
+                block_list.len() - 1
+            });
+            block_list[block].2.push(j);
+        }
+
+        let mut blocks = Blocks::new(a_sparse_axes.len());
+        for (key, a_blocks, b_blocks) in block_list {
+            if !self.keeps(!a_blocks.is_empty(), !b_blocks.is_empty()) {
+                continue;
+            }
+            let index = key
+                .iter()
+                .zip(a_sparse_axes.iter())
+                .map(|(&offset, axis)| axis.lower() + IT::from(offset).unwrap());
+            blocks.push(index, a_blocks, b_blocks);
+        }
+        blocks
+    }
+
+    fn compute_values(
+        &self,
+        a_values: &ArrayView2<VT>,
+        b_values: &ArrayView2<VT>,
+        blocks: &Blocks<IT>,
+        result_values: &mut Array2<VT>,
+    ) {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("ElementwiseCOOTensors::compute_values");
+        }
+
+        for (i, result_row) in result_values.outer_iter_mut().enumerate() {
+            self.compute_block(a_values, b_values, blocks, i, result_row);
+        }
+    }
+
+    fn compute_values_multi_thread(
+        &self,
+        a_values: &ArrayView2<VT>,
+        b_values: &ArrayView2<VT>,
+        blocks: &Blocks<IT>,
+        result_values: &mut Array2<VT>,
+    ) {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("ElementwiseCOOTensors::compute_values_multi_thread");
+        }
+
+        result_values
+            .outer_iter_mut()
+            .into_par_iter()
+            .with_min_len(256)
+            .enumerate()
+            .for_each(|(i, result_row)| {
+                self.compute_block(a_values, b_values, blocks, i, result_row);
+            });
+    }
+
+    fn compute_block(
+        &self,
+        a_values: &ArrayView2<VT>,
+        b_values: &ArrayView2<VT>,
+        blocks: &Blocks<IT>,
+        i: usize,
+        mut result_row: ArrayViewMut1<VT>,
+    ) {
+        let a_blocks = blocks.a_blocks(i);
+        let b_blocks = blocks.b_blocks(i);
+        for (k, result) in result_row.iter_mut().enumerate() {
+            let a = a_blocks
+                .iter()
+                .fold(VT::zero(), |acc, &block| acc + a_values[(block, k)].clone());
+            let b = b_blocks
+                .iter()
+                .fold(VT::zero(), |acc, &block| acc + b_values[(block, k)].clone());
+            *result = match self.op {
+                ElementwiseOp::Add => a + b,
+                ElementwiseOp::Sub => a - b,
+                ElementwiseOp::Mul => a * b,
+                ElementwiseOp::Div if b.is_zero() => VT::zero(),
+                ElementwiseOp::Div => a / b,
+            };
+        }
+    }
+
+    /// Whether the result has a block at an index, given which inputs have it.
+    #[inline]
+    fn keeps(&self, in_a: bool, in_b: bool) -> bool {
+        match self.op {
+            ElementwiseOp::Add | ElementwiseOp::Sub => in_a || in_b,
+            ElementwiseOp::Mul | ElementwiseOp::Div => in_a && in_b,
+        }
+    }
+}
+
+/// Reorder a sparse index of `b` into the sparse axis order of `a`.
+fn b_index_in_a_order<'a, IT>(
+    index: impl Iterator<Item = &'a IT>,
+    a_sparse_in_b: &[usize],
+) -> impl Iterator<Item = IT>
+where
+    IT: 'a + IdxType,
+{
+    let index = index.copied().collect::<SmallVec<_>>();
+    a_sparse_in_b
+        .iter()
+        .map(|&axis_idx| index[axis_idx])
+        .collect::<SmallVec<_>>()
+        .into_iter()
+}
+
+/// Output blocks and the input blocks contributing to each of them.
+struct Blocks<IT>
+where
+    IT: IdxType,
+{
+    result_indices: Array2<IT>,
+    /// The blocks of `a` contributing to output block `i` are `a_blocks[a_offsets[i]..a_offsets[i + 1]]`.
+    a_offsets: Vec<usize>,
+    a_blocks: Vec<usize>,
+    /// The blocks of `b` contributing to output block `i` are `b_blocks[b_offsets[i]..b_offsets[i + 1]]`.
+    b_offsets: Vec<usize>,
+    b_blocks: Vec<usize>,
+}
+
+impl<IT> Blocks<IT>
+where
+    IT: IdxType,
+{
+    fn new(num_sparse_axes: usize) -> Self {
+        Self {
+            result_indices: Array2::zeros((0, num_sparse_axes)),
+            a_offsets: vec![0],
+            a_blocks: Vec::new(),
+            b_offsets: vec![0],
+            b_blocks: Vec::new(),
+        }
+    }
+
+    fn push(
+        &mut self,
+        index: impl Iterator<Item = IT>,
+        a_blocks: impl IntoIterator<Item = usize>,
+        b_blocks: impl IntoIterator<Item = usize>,
+    ) {
+        self.result_indices
+            .push_row(ndarray::Array1::from_iter(index).view())
+            .unwrap();
+        self.a_blocks.extend(a_blocks);
+        self.a_offsets.push(self.a_blocks.len());
+        self.b_blocks.extend(b_blocks);
+        self.b_offsets.push(self.b_blocks.len());
+    }
+
+    #[inline]
+    fn num_blocks(&self) -> usize {
+        self.result_indices.nrows()
+    }
+
+    #[inline]
+    fn a_blocks(&self, i: usize) -> &[usize] {
+        &self.a_blocks[self.a_offsets[i]..self.a_offsets[i + 1]]
+    }
+
+    #[inline]
+    fn b_blocks(&self, i: usize) -> &[usize] {
+        &self.b_blocks[self.b_offsets[i]..self.b_offsets[i + 1]]
+    }
+}
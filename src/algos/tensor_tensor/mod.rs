@@ -0,0 +1,7 @@
+//! Algorithms related to two tensors.
+
+mod coo_contract;
+mod coo_elementwise;
+
+pub use coo_contract::ContractCOOTensors;
+pub use coo_elementwise::{ElementwiseCOOTensors, ElementwiseOp};
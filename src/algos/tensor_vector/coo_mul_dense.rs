@@ -0,0 +1,372 @@
+use std::iter;
+
+use crate::algos::tensor_matrix::{copy_index_except_axis, index_eq_except_axis};
+use crate::structs::axis::Axis;
+use crate::structs::tensor::{COOTensor, COOTensorInner, COOTensorView};
+use crate::structs::vec::{smallvec, SmallVec};
+use crate::traits::{IdxType, RawParts, Tensor, ValType};
+use crate::utils::tracer::Tracer;
+use anyhow::{anyhow, bail, Result};
+use ndarray::{Array2, ArrayView1, ArrayView2, Ix2};
+use rayon::prelude::*;
+use scopeguard::defer;
+
+/// Multiply a `COOTensor` or `SemiCOOTensor` with a `DenseVector`.
+///
+/// Unlike [`crate::algos::tensor_matrix::COOTensorMulDenseMatrix`], the common axis is removed from the result.
+pub struct COOTensorMulDenseVector<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    pub tensor: COOTensorView<'a, IT, VT>,
+    pub vector: &'a COOTensor<IT, VT>,
+
+    pub tracer: Tracer,
+    pub multi_thread: bool,
+}
+
+impl<'a, IT, VT> COOTensorMulDenseVector<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Create a new `COOTensorMulDenseVector` task.
+    #[must_use]
+    pub fn new(
+        tensor: impl Into<COOTensorView<'a, IT, VT>>,
+        vector: &'a COOTensor<IT, VT>,
+    ) -> Self {
+        Self {
+            tensor: tensor.into(),
+            vector,
+            tracer: Tracer::new_dummy(),
+            multi_thread: false,
+        }
+    }
+
+    /// Enable performance tracing.
+    #[must_use]
+    pub fn trace(mut self, tracer: &Tracer) -> Self {
+        self.tracer.clone_from(tracer);
+        self
+    }
+
+    /// Perform the multiplication.
+    ///
+    /// The last axis of the tensor's sparse_sort_order must be the axis of the vector.
+    /// Dense axes of the tensor are kept in the result.
+    pub fn execute(self) -> Result<COOTensor<IT, VT>>
+    where
+        IT: IdxType,
+        VT: ValType,
+    {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("COOTensorMulDenseVector");
+        }
+
+        // Check if the vector has 1 axis.
+        if self.vector.ndim() != 1 {
+            bail!("The vector must have 1 axis.");
+        }
+        // Check if the vector is fully dense.
+        if !self.vector.sparse_axes().is_empty() {
+            bail!("The vector must be fully dense.");
+        }
+
+        // Map the common axis of the tensor and the vector.
+        let common_axis = &self.vector.dense_axes()[0];
+        let common_axis_index = self
+            .tensor
+            .sparse_axes()
+            .iter()
+            .position(|ax| ax == common_axis)
+            .ok_or_else(|| anyhow!("axis {} not found", common_axis))?;
+
+        // Check if the tensor is sorted, and the trailing axis is the common axis.
+        let sparse_sorting_order = self
+            .tensor
+            .sparse_sort_order()
+            .ok_or(anyhow!("The tensor must be sorted"))?;
+        if sparse_sorting_order.last() != Some(common_axis) {
+            bail!("The tensor must be sorted along the common axis.");
+        }
+
+        // The shape of each dense block.
+        let dense_block_size = self
+            .tensor
+            .dense_axes()
+            .iter()
+            .map(|axis| axis.len())
+            .product::<usize>();
+
+        // Extract the contents from the inputs.
+        let tensor_indices = &self.tensor.indices();
+        // Reshape the tensor into an ArrayView2.
+        // Rows are each dense block, and columns are linearized elements inside the dense block.
+        let tensor_values = self.tensor.values();
+        let tensor_values = tensor_values.as_standard_layout();
+        let tensor_values = tensor_values
+            .view()
+            .into_shape((self.tensor.num_blocks(), dense_block_size))?;
+        // Reshape the vector into an ArrayView1.
+        let vector_values = self
+            .vector
+            .raw_parts()
+            .values
+            .view()
+            .into_dimensionality::<Ix2>()?
+            .index_axis_move(ndarray::Axis(0), 0);
+
+        // Create the output tensor.
+        let result_shape = self
+            .tensor
+            .shape()
+            .iter()
+            .filter(|&ax| ax != common_axis)
+            .cloned()
+            .collect::<SmallVec<_>>();
+        let result_sparse_axes = self
+            .tensor
+            .sparse_axes()
+            .iter()
+            .filter(|&ax| ax != common_axis)
+            .cloned()
+            .collect::<SmallVec<_>>();
+        let sparse_sort_order = sparse_sorting_order
+            .iter()
+            .filter(|&ax| ax != common_axis)
+            .cloned()
+            .collect::<SmallVec<_>>();
+
+        // Calculate the result indices.
+        let (result_indices, chunk_offsets) =
+            self.compute_indices(tensor_indices, common_axis_index);
+
+        let result_values_shape = iter::once(result_indices.nrows())
+            .chain(self.tensor.dense_axes().iter().map(|axis| axis.len()))
+            .collect::<SmallVec<_>>();
+        let result_values = if self.multi_thread {
+            self.compute_values_multi_thread(
+                tensor_indices,
+                &tensor_values,
+                &vector_values,
+                &result_indices,
+                &chunk_offsets,
+                common_axis,
+                common_axis_index,
+            )
+        } else {
+            self.compute_values(
+                tensor_indices,
+                &tensor_values,
+                &vector_values,
+                &result_indices,
+                &chunk_offsets,
+                common_axis,
+                common_axis_index,
+            )
+        }
+        .into_shape(result_values_shape.as_slice())?;
+
+        let result = COOTensorInner {
+            name: None,
+            shape: result_shape,
+            sparse_axes: result_sparse_axes,
+            dense_axes: self.tensor.dense_axes().iter().cloned().collect(),
+            indices: result_indices,
+            values: result_values,
+            sparse_is_sorted: true,
+            sparse_sort_order,
+            sparse_is_coalesced: true,
+        };
+
+        Ok(
+            // # Safety
+            // We make sure the tensor is in valid state.
+            unsafe { COOTensor::from_raw_parts(result) },
+        )
+    }
+
+    fn compute_indices(
+        &self,
+        tensor_indices: &ArrayView2<IT>,
+        common_axis_index: usize,
+    ) -> (Array2<IT>, Vec<usize>)
+    where
+        IT: IdxType,
+    {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("COOTensorMulDenseVector::compute_indices");
+        }
+
+        let num_blocks = tensor_indices.nrows();
+        let num_axes = tensor_indices.ncols();
+        assert_ne!(num_axes, 0);
+
+        let mut last_index = None;
+        let mut semi_sparse_indices = Vec::new();
+        let mut chunk_offsets = Vec::new();
+        let mut index_buffer: SmallVec<_> = smallvec![IT::zero(); num_axes-1];
+
+        for i in 0..num_blocks {
+            if last_index
+                .map(|last_index| unsafe {
+                    // # Safety
+                    // Both `last_index` and `i` are lower than `num_blocks`.
+                    !index_eq_except_axis(tensor_indices, last_index, i, common_axis_index)
+                })
+                .unwrap_or(true)
+            {
+                unsafe {
+                    copy_index_except_axis(
+                        tensor_indices,
+                        i,
+                        common_axis_index,
+                        index_buffer.as_mut_slice(),
+                    )
+                }
+                semi_sparse_indices.extend_from_slice(index_buffer.as_slice());
+                chunk_offsets.push(i);
+                last_index = Some(i);
+            }
+        }
+        if num_axes == 1 && chunk_offsets.is_empty() {
+            // Without sparse axes left, the result still needs one block of zeros.
+            chunk_offsets.push(0);
+        }
+        chunk_offsets.push(num_blocks);
+
+        let result_indices =
+            Array2::from_shape_vec((chunk_offsets.len() - 1, num_axes - 1), semi_sparse_indices)
+                .unwrap();
+        (result_indices, chunk_offsets)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn compute_values(
+        &self,
+        tensor_indices: &ArrayView2<IT>,
+        tensor_values: &ArrayView2<VT>,
+        vector_values: &ArrayView1<VT>,
+        result_indices: &Array2<IT>,
+        chunk_offsets: &[usize],
+        common_axis: &Axis<IT>,
+        common_axis_index: usize,
+    ) -> Array2<VT>
+    where
+        IT: IdxType,
+        VT: ValType,
+    {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("COOTensorMulDenseVector::compute_values");
+        }
+
+        let num_chunks = result_indices.nrows();
+        let tensor_free_axes_len = tensor_values.ncols();
+        let mut result_values = Array2::<VT>::zeros((num_chunks, tensor_free_axes_len));
+
+        // i is chunk-level on the result tensor
+        for i in 0..num_chunks {
+            // # Safety
+            // chunk_offsets.len() == num_chunks + 1
+            let inz_begin = *unsafe { chunk_offsets.get_unchecked(i) };
+            let inz_end = *unsafe { chunk_offsets.get_unchecked(i + 1) };
+            // j is chunk-level on the input tensor,
+            // for each output[i] corresponds to all input[j]
+            for j in inz_begin..inz_end {
+                // # Safety
+                // j < inz_end <= tensor_indices.nrows()
+                // 0 <= r < common_axis.size()
+                let r = unsafe {
+                    (*tensor_indices.uget((j, common_axis_index)) - common_axis.lower())
+                        .to_usize()
+                        .unwrap_unchecked()
+                };
+                // # Safety
+                // r < vector_values.len()
+                let scale = unsafe { vector_values.uget(r) };
+                for k in 0..tensor_free_axes_len {
+                    // # Safety
+                    // i < num_chunks
+                    // j < tensor_indices.nrows()
+                    // k < tensor_values.ncols() == tensor_free_axes_len
+                    unsafe {
+                        let value = result_values.uget_mut((i, k));
+                        *value = value.clone() + tensor_values.uget((j, k)).clone() * scale.clone();
+                    }
+                }
+            }
+        }
+
+        result_values
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn compute_values_multi_thread(
+        &self,
+        tensor_indices: &ArrayView2<IT>,
+        tensor_values: &ArrayView2<VT>,
+        vector_values: &ArrayView1<VT>,
+        result_indices: &Array2<IT>,
+        chunk_offsets: &[usize],
+        common_axis: &Axis<IT>,
+        common_axis_index: usize,
+    ) -> Array2<VT>
+    where
+        IT: IdxType,
+        VT: ValType,
+    {
+        let event = self.tracer.start();
+        defer! {
+            event.finish("COOTensorMulDenseVector::compute_values_multi_thread");
+        }
+
+        let num_chunks = result_indices.nrows();
+        let tensor_free_axes_len = tensor_values.ncols();
+        let mut result_values = Array2::<VT>::zeros((num_chunks, tensor_free_axes_len));
+
+        // i is chunk-level on the result tensor
+        result_values
+            .outer_iter_mut()
+            .into_par_iter()
+            .with_min_len(256)
+            .enumerate()
+            .for_each(|(i, mut result_row)| {
+                // # Safety
+                // chunk_offsets.len() == num_chunks + 1
+                let inz_begin = *unsafe { chunk_offsets.get_unchecked(i) };
+                let inz_end = *unsafe { chunk_offsets.get_unchecked(i + 1) };
+                // j is chunk-level on the input tensor,
+                // for each output[i] corresponds to all input[j]
+                for j in inz_begin..inz_end {
+                    // # Safety
+                    // j < inz_end <= tensor_indices.nrows()
+                    // 0 <= r < common_axis.size()
+                    let r = unsafe {
+                        (*tensor_indices.uget((j, common_axis_index)) - common_axis.lower())
+                            .to_usize()
+                            .unwrap_unchecked()
+                    };
+                    // # Safety
+                    // r < vector_values.len()
+                    let scale = unsafe { vector_values.uget(r) };
+                    for k in 0..tensor_free_axes_len {
+                        // # Safety
+                        // j < tensor_indices.nrows()
+                        // k < tensor_values.ncols() == tensor_free_axes_len
+                        unsafe {
+                            let value = result_row.uget_mut(k);
+                            *value =
+                                value.clone() + tensor_values.uget((j, k)).clone() * scale.clone();
+                        }
+                    }
+                }
+            });
+
+        result_values
+    }
+}
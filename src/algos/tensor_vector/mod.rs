@@ -0,0 +1,5 @@
+//! Algorithms related to tensors and vectors.
+
+mod coo_mul_dense;
+
+pub use coo_mul_dense::COOTensorMulDenseVector;
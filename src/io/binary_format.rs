@@ -0,0 +1,54 @@
+//! Layout of the binary tensor file format.
+//!
+//! All numbers are little-endian.
+//!
+//! | Size               | Content                                                          |
+//! |--------------------|------------------------------------------------------------------|
+//! | 8                  | Magic bytes `PATTIECO`                                           |
+//! | 4                  | Format version, currently 1                                      |
+//! | 1                  | Type tag of indices, see [`BinaryScalar`]                        |
+//! | 1                  | Type tag of values, see [`BinaryScalar`]                         |
+//! | 1                  | Flags, bit 0 is "sorted", bit 1 is "coalesced"                   |
+//! | 1                  | Reserved, must be 0                                              |
+//! | 4                  | Number of axes                                                   |
+//! | 4                  | Number of sparse axes                                            |
+//! | 8                  | Number of blocks                                                 |
+//! | 8                  | Byte offset of the index array from the beginning of the file    |
+//! | 8                  | Byte offset of the value array from the beginning of the file    |
+//! | string             | Tensor name                                                      |
+//! | each axis          | Lower bound and upper bound as indices, then a string as label   |
+//! | 4 × sparse         | Position of each sparse axis in the shape                        |
+//! | 4 × dense          | Position of each dense axis in the shape                         |
+//! | 4 + 4 × n          | Number of axes in the sort order, then the position of each axis |
+//! | padding            | Zeros until the index array                                      |
+//! | indices            | Index of each block on each sparse axis, row-major               |
+//! | padding            | Zeros until the value array                                      |
+//! | values             | Values of each block on each dense axis, row-major               |
+//!
+//! A string is a 4-byte length and UTF-8 bytes, or `0xffffffff` if it is absent.
+//! The index array and the value array start at multiples of [`ARRAY_ALIGNMENT`] bytes.
+//!
+//! [`BinaryScalar`]: crate::traits::BinaryScalar
+
+/// Magic bytes at the beginning of the file.
+pub(super) const MAGIC: [u8; 8] = *b"PATTIECO";
+
+/// The current format version.
+pub(super) const VERSION: u32 = 1;
+
+/// The flag bit for a sorted tensor.
+pub(super) const FLAG_SORTED: u8 = 1 << 0;
+/// The flag bit for a tensor without duplicate indices.
+pub(super) const FLAG_COALESCED: u8 = 1 << 1;
+
+/// The length of an absent string.
+pub(super) const NO_STRING: u32 = u32::MAX;
+
+/// Alignment of the index array and the value array, in bytes.
+pub(super) const ARRAY_ALIGNMENT: u64 = 64;
+
+/// Round `offset` up to the next multiple of [`ARRAY_ALIGNMENT`].
+#[inline]
+pub(super) fn align_offset(offset: u64) -> u64 {
+    offset.div_ceil(ARRAY_ALIGNMENT) * ARRAY_ALIGNMENT
+}
@@ -0,0 +1,258 @@
+//! Transparent compression of tensor files.
+//!
+//! Each compression method is enabled by a cargo feature of the same name: `gzip`, `zstd` and `xz`.
+//! Readers detect the compression by the magic bytes at the beginning of the file,
+//! [`Compression::open`] does the same for a file path,
+//! and writers compress the output through [`CompressWriter`].
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+const GZIP_MAGIC: &[u8] = b"\x1f\x8b";
+const ZSTD_MAGIC: &[u8] = b"\x28\xb5\x2f\xfd";
+const XZ_MAGIC: &[u8] = b"\xfd7zXZ\x00";
+const MAX_MAGIC_LEN: usize = 6;
+
+/// A compression method of tensor files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Compression {
+    /// Not compressed.
+    #[default]
+    None,
+    /// Gzip, requires the `gzip` feature.
+    Gzip,
+    /// Zstandard, requires the `zstd` feature.
+    Zstd,
+    /// XZ, requires the `xz` feature.
+    Xz,
+}
+
+impl Compression {
+    /// Detect the compression method by the magic bytes at the beginning of a file.
+    pub fn detect(magic: &[u8]) -> Self {
+        [Self::Gzip, Self::Zstd, Self::Xz]
+            .into_iter()
+            .find(|compression| magic.starts_with(compression.magic()))
+            .unwrap_or(Self::None)
+    }
+
+    /// Guess the compression method by the extension of a file name,
+    /// which is `.gz`, `.zst` or `.xz`.
+    ///
+    /// ```
+    /// use pattie::io::Compression;
+    ///
+    /// assert_eq!(Compression::from_path("tensor.tns.gz"), Compression::Gzip);
+    /// assert_eq!(Compression::from_path("tensor.tns"), Compression::None);
+    /// ```
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        match path.as_ref().extension().and_then(|ext| ext.to_str()) {
+            Some("gz") => Self::Gzip,
+            Some("zst") => Self::Zstd,
+            Some("xz") => Self::Xz,
+            _ => Self::None,
+        }
+    }
+
+    /// Open a file for reading, and decompress it if its magic bytes show a compression method.
+    ///
+    /// If the compression method is not enabled in this build, an error of [`std::io::ErrorKind::Unsupported`] is returned.
+    ///
+    /// ```
+    /// use pattie::io::Compression;
+    /// use pattie::structs::tensor::COOTensor;
+    /// use pattie::traits::Tensor;
+    ///
+    /// let mut input = Compression::open("data/tensors/3d_7.tns").unwrap();
+    /// let tensor = COOTensor::<u32, f32>::read_from_text(&mut input).unwrap();
+    /// assert_eq!(tensor.ndim(), 3);
+    /// ```
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Box<dyn io::Read>> {
+        decompress(io::BufReader::new(File::open(path)?))
+    }
+
+    /// The name of the compression method, which is also the name of the cargo feature.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+            Self::Xz => "xz",
+        }
+    }
+
+    /// Whether the compression method is enabled in this build.
+    pub fn is_supported(self) -> bool {
+        match self {
+            Self::None => true,
+            Self::Gzip => cfg!(feature = "gzip"),
+            Self::Zstd => cfg!(feature = "zstd"),
+            Self::Xz => cfg!(feature = "xz"),
+        }
+    }
+
+    fn magic(self) -> &'static [u8] {
+        match self {
+            Self::None => b"",
+            Self::Gzip => GZIP_MAGIC,
+            Self::Zstd => ZSTD_MAGIC,
+            Self::Xz => XZ_MAGIC,
+        }
+    }
+
+    fn unsupported(self) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!(
+                "{} compression is not supported, enable the {:?} feature of pattie",
+                self.name(),
+                self.name()
+            ),
+        )
+    }
+
+    /// Start compressing the output to `w`.
+    ///
+    /// [`CompressWriter::finish`] must be called after writing, otherwise the output may be truncated.
+    pub fn writer<W>(self, w: W) -> io::Result<CompressWriter<W>>
+    where
+        W: io::Write,
+    {
+        let encoder = match self {
+            Self::None => Encoder::None(w),
+            #[cfg(feature = "gzip")]
+            Self::Gzip => Encoder::Gzip(flate2::write::GzEncoder::new(
+                w,
+                flate2::Compression::default(),
+            )),
+            #[cfg(feature = "zstd")]
+            Self::Zstd => Encoder::Zstd(zstd::Encoder::new(w, 0)?),
+            #[cfg(feature = "xz")]
+            Self::Xz => Encoder::Xz(xz2::write::XzEncoder::new(w, 6)),
+            #[allow(unreachable_patterns)]
+            _ => return Err(self.unsupported()),
+        };
+        Ok(CompressWriter { encoder })
+    }
+}
+
+impl std::fmt::Display for Compression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A writer that compresses everything written to it, created by [`Compression::writer`].
+///
+/// # Example
+///
+/// ```
+/// use ndarray::array;
+/// use pattie::io::Compression;
+/// use pattie::structs::tensor::COOTensor;
+///
+/// let tensor = COOTensor::<u32, f32>::from_ndarray(array![[1.0, 2.0], [3.0, 4.0]]);
+/// let mut w = Compression::None.writer(Vec::new()).unwrap();
+/// tensor.write_to_text(&mut w).unwrap();
+/// let output = w.finish().unwrap();
+/// assert!(output.starts_with(b"2\n"));
+/// ```
+pub struct CompressWriter<W>
+where
+    W: io::Write,
+{
+    encoder: Encoder<W>,
+}
+
+enum Encoder<W>
+where
+    W: io::Write,
+{
+    None(W),
+    #[cfg(feature = "gzip")]
+    Gzip(flate2::write::GzEncoder<W>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::Encoder<'static, W>),
+    #[cfg(feature = "xz")]
+    Xz(xz2::write::XzEncoder<W>),
+}
+
+impl<W> CompressWriter<W>
+where
+    W: io::Write,
+{
+    /// Finish the compressed stream, flush it, and return the underlying writer.
+    pub fn finish(self) -> io::Result<W> {
+        // Without any compression feature, `Encoder::None` is the only variant.
+        #[allow(clippy::infallible_destructuring_match)]
+        let mut w = match self.encoder {
+            Encoder::None(w) => w,
+            #[cfg(feature = "gzip")]
+            Encoder::Gzip(encoder) => encoder.finish()?,
+            #[cfg(feature = "zstd")]
+            Encoder::Zstd(encoder) => encoder.finish()?,
+            #[cfg(feature = "xz")]
+            Encoder::Xz(encoder) => encoder.finish()?,
+        };
+        w.flush()?;
+        Ok(w)
+    }
+
+    fn inner(&mut self) -> &mut dyn io::Write {
+        match &mut self.encoder {
+            Encoder::None(w) => w,
+            #[cfg(feature = "gzip")]
+            Encoder::Gzip(encoder) => encoder,
+            #[cfg(feature = "zstd")]
+            Encoder::Zstd(encoder) => encoder,
+            #[cfg(feature = "xz")]
+            Encoder::Xz(encoder) => encoder,
+        }
+    }
+}
+
+impl<W> io::Write for CompressWriter<W>
+where
+    W: io::Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner().write(buf)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.inner().write_all(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner().flush()
+    }
+}
+
+/// Detect the compression of `r`, and return a reader of the decompressed content.
+///
+/// If the compression method is not enabled in this build, an error of [`std::io::ErrorKind::Unsupported`] is returned.
+pub(super) fn decompress<'a, R>(r: R) -> io::Result<Box<dyn io::Read + 'a>>
+where
+    R: io::Read + 'a,
+{
+    let mut r = r;
+    let mut magic = Vec::with_capacity(MAX_MAGIC_LEN);
+    (&mut r)
+        .take(MAX_MAGIC_LEN as u64)
+        .read_to_end(&mut magic)?;
+    let compression = Compression::detect(&magic);
+    // Put the magic bytes back.
+    let r = io::Cursor::new(magic).chain(r);
+    Ok(match compression {
+        Compression::None => Box::new(r),
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => Box::new(flate2::read::MultiGzDecoder::new(r)),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => Box::new(zstd::Decoder::new(r)?),
+        #[cfg(feature = "xz")]
+        Compression::Xz => Box::new(xz2::read::XzDecoder::new_multi_decoder(r)),
+        #[allow(unreachable_patterns)]
+        _ => return Err(compression.unsupported()),
+    })
+}
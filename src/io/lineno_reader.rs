@@ -28,6 +28,21 @@ where
         }
     }
 
+    /// Start counting from the beginning of the `line_number`-th line.
+    pub fn with_line_number(inner: R, line_number: u64) -> LineNumberReader<R> {
+        LineNumberReader {
+            inner,
+            line_number,
+            column_number: 1,
+            peek: None,
+        }
+    }
+
+    /// The line of the last byte read, not counting the byte peeked.
+    pub fn line_number(&self) -> u64 {
+        self.line_number
+    }
+
     pub fn line_column(&self) -> (u64, u64) {
         if let Some(Peek::Byte(b)) = self.peek {
             if b == b'\n' {
@@ -86,7 +101,6 @@ where
         }
     }
 
-    #[allow(dead_code)]
     pub fn inner(&mut self) -> &mut R {
         &mut self.inner
     }
@@ -0,0 +1,120 @@
+//! Common definitions of the Matrix Market format.
+//!
+//! The format is described at <https://math.nist.gov/MatrixMarket/formats.html>.
+
+use crate::traits::MatrixMarketScalar;
+use std::fmt;
+
+/// The banner at the beginning of a Matrix Market file.
+pub(super) const BANNER: &str = "%%MatrixMarket";
+
+/// The field of a Matrix Market file, which tells how each value is written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatrixMarketField {
+    /// A floating-point number.
+    Real,
+    /// An integer.
+    Integer,
+    /// A real part and an imaginary part.
+    Complex,
+    /// No value, each entry present in the file is one.
+    Pattern,
+}
+
+/// The symmetry of a Matrix Market file, which tells which entries are stored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatrixMarketSymmetry {
+    /// Every entry is stored.
+    General,
+    /// Only the lower triangle is stored, `a[j][i] == a[i][j]`.
+    Symmetric,
+    /// Only the strictly lower triangle is stored, `a[j][i] == -a[i][j]`.
+    SkewSymmetric,
+    /// Only the lower triangle is stored, `a[j][i]` is the complex conjugate of `a[i][j]`.
+    Hermitian,
+}
+
+impl MatrixMarketField {
+    /// Parse the field in the banner, case-insensitively.
+    pub(super) fn parse(token: &str) -> Option<Self> {
+        [Self::Real, Self::Integer, Self::Complex, Self::Pattern]
+            .into_iter()
+            .find(|field| token.eq_ignore_ascii_case(field.name()))
+    }
+
+    /// The name written in the banner.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Real => "real",
+            Self::Integer => "integer",
+            Self::Complex => "complex",
+            Self::Pattern => "pattern",
+        }
+    }
+
+    /// The number of tokens of each value.
+    pub(super) fn num_tokens(self) -> usize {
+        match self {
+            Self::Real | Self::Integer => 1,
+            Self::Complex => 2,
+            Self::Pattern => 0,
+        }
+    }
+}
+
+impl MatrixMarketSymmetry {
+    /// Parse the symmetry in the banner, case-insensitively.
+    pub(super) fn parse(token: &str) -> Option<Self> {
+        [
+            Self::General,
+            Self::Symmetric,
+            Self::SkewSymmetric,
+            Self::Hermitian,
+        ]
+        .into_iter()
+        .find(|symmetry| token.eq_ignore_ascii_case(symmetry.name()))
+    }
+
+    /// The name written in the banner.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::General => "general",
+            Self::Symmetric => "symmetric",
+            Self::SkewSymmetric => "skew-symmetric",
+            Self::Hermitian => "hermitian",
+        }
+    }
+
+    /// The first row stored in column `col`, rows above it are omitted.
+    pub(super) fn first_row(self, col: usize) -> usize {
+        match self {
+            Self::General => 0,
+            Self::Symmetric | Self::Hermitian => col,
+            Self::SkewSymmetric => col + 1,
+        }
+    }
+
+    /// The value of `a[j][i]`, given the value of `a[i][j]`.
+    pub(super) fn mirror<VT>(self, value: &VT) -> VT
+    where
+        VT: MatrixMarketScalar,
+    {
+        match self {
+            Self::General | Self::Symmetric => value.clone(),
+            Self::SkewSymmetric => VT::zero() - value.clone(),
+            Self::Hermitian => value.conj(),
+        }
+    }
+}
+
+impl fmt::Display for MatrixMarketField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl fmt::Display for MatrixMarketSymmetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
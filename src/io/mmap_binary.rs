@@ -0,0 +1,160 @@
+//! Map a binary tensor file into memory without copying.
+
+use super::read_binary::{read_header, BinaryHeader, BinaryReadError, BinaryReader};
+use crate::structs::tensor::COOTensorView;
+use crate::traits::{IdxType, ValType, ZeroCopyScalar};
+use memmap2::Mmap;
+use ndarray::{ArrayView2, ArrayViewD};
+use std::fs::File;
+use std::marker::PhantomData;
+use std::{io, slice};
+
+/// A COO tensor inside a memory-mapped binary file.
+///
+/// The file is written by [`COOTensor::write_to_binary`](crate::structs::tensor::COOTensor::write_to_binary).
+/// Only the header is parsed when the file is mapped, the indices and the values stay in the file,
+/// and [`MappedCOOTensor::view`] borrows them directly.
+/// Pages are loaded by the operating system when they are first accessed.
+///
+/// The view can be passed to algorithms that only read the tensor, for example
+/// [`COOTensorMulDenseMatrix`](crate::algos::tensor_matrix::COOTensorMulDenseMatrix).
+///
+/// Only little-endian machines are supported.
+pub struct MappedCOOTensor<IT, VT>
+where
+    IT: IdxType + ZeroCopyScalar,
+    VT: ValType + ZeroCopyScalar,
+{
+    mmap: Mmap,
+    header: BinaryHeader<IT>,
+    _phantom: PhantomData<VT>,
+}
+
+impl<IT, VT> MappedCOOTensor<IT, VT>
+where
+    IT: IdxType + ZeroCopyScalar,
+    VT: ValType + ZeroCopyScalar,
+{
+    /// Map a binary tensor file into memory.
+    ///
+    /// `IT` and `VT` must be the same types as the tensor was written with.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified, by this or another process, until the `MappedCOOTensor` is dropped.
+    /// See [`memmap2::Mmap::map`] for details.
+    pub unsafe fn map(file: &File) -> Result<Self, BinaryReadError> {
+        Self::from_mmap(Mmap::map(file)?)
+    }
+
+    /// Use an existing memory map of a binary tensor file.
+    ///
+    /// The header and the indices are checked in the same way as [`COOTensor::read_from_binary`](crate::structs::tensor::COOTensor::read_from_binary).
+    pub fn from_mmap(mmap: Mmap) -> Result<Self, BinaryReadError> {
+        if cfg!(target_endian = "big") {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "memory mapping is only supported on little-endian machines",
+            )
+            .into());
+        }
+
+        let mut r = BinaryReader {
+            inner: &mmap[..],
+            position: 0,
+        };
+        let header = read_header::<IT, VT, _>(&mut r)?;
+        if header.indices_offset < r.position || header.values_offset < r.position {
+            return Err(BinaryReadError::HeaderError(
+                "array offset overlaps the header".into(),
+            ));
+        }
+        check_array::<IT>(&mmap, header.indices_offset, header.num_indices()?)?;
+        check_array::<VT>(&mmap, header.values_offset, header.num_values()?)?;
+
+        let result = Self {
+            mmap,
+            header,
+            _phantom: PhantomData,
+        };
+        result.header.check_indices(result.indices())?;
+        Ok(result)
+    }
+
+    /// Borrow the mapped tensor as a [`COOTensorView`].
+    #[inline]
+    pub fn view(&self) -> COOTensorView<'_, IT, VT> {
+        let header = &self.header;
+        let values = ArrayViewD::from_shape(
+            header.values_shape(),
+            // # Safety
+            // The range, the alignment, and the type are checked in `from_mmap`.
+            unsafe { self.array::<VT>(header.values_offset, header.num_values().unwrap()) },
+        )
+        .unwrap();
+        // # Safety
+        // The header and the indices are checked in `from_mmap`.
+        unsafe {
+            COOTensorView::from_parts(
+                header.name.as_deref(),
+                &header.shape,
+                &header.sparse_axes,
+                &header.dense_axes,
+                self.indices(),
+                values,
+                header
+                    .sparse_is_sorted
+                    .then_some(&header.sparse_sort_order[..]),
+                header.sparse_is_coalesced,
+            )
+        }
+    }
+
+    fn indices(&self) -> ArrayView2<'_, IT> {
+        let header = &self.header;
+        ArrayView2::from_shape(
+            (header.num_blocks, header.sparse_axes.len()),
+            // # Safety
+            // The range, the alignment, and the type are checked in `from_mmap`.
+            unsafe { self.array::<IT>(header.indices_offset, header.num_indices().unwrap()) },
+        )
+        .unwrap()
+    }
+
+    /// # Safety
+    ///
+    /// The array must have been checked by [`check_array`].
+    #[inline]
+    unsafe fn array<T>(&self, offset: u64, len: usize) -> &[T]
+    where
+        T: ZeroCopyScalar,
+    {
+        slice::from_raw_parts(self.mmap.as_ptr().add(offset as usize).cast(), len)
+    }
+}
+
+/// Check that an array of `len` scalars at `offset` is inside the file and properly aligned.
+fn check_array<T>(mmap: &Mmap, offset: u64, len: usize) -> Result<(), BinaryReadError>
+where
+    T: ZeroCopyScalar,
+{
+    let num_bytes = len
+        .checked_mul(T::SIZE)
+        .ok_or_else(|| BinaryReadError::HeaderError("too many blocks".into()))?;
+    let end = usize::try_from(offset)
+        .ok()
+        .and_then(|offset| offset.checked_add(num_bytes));
+    match end {
+        Some(end) if end <= mmap.len() => (),
+        _ => return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+    }
+    if !mmap
+        .as_ptr()
+        .wrapping_add(offset as usize)
+        .cast::<T>()
+        .is_aligned()
+    {
+        return Err(BinaryReadError::HeaderError("array is not aligned".into()));
+    }
+    Ok(())
+}
@@ -4,9 +4,30 @@
 //!
 //! Although this module contains code, the documentation browser shows empty. The actual contents are in the `structs` module.
 
+mod binary_format;
+mod compression;
 mod lineno_reader;
+mod matrix_market;
+mod mmap_binary;
+mod read_binary;
 mod read_coo;
+mod read_coo_chunks;
+mod read_coo_parallel;
+mod read_frostt;
+mod read_matrix_market;
+mod read_numpy;
+mod text_header;
+mod write_binary;
 mod write_coo;
+mod write_frostt;
+mod write_matrix_market;
+mod write_numpy;
 
+pub use compression::{CompressWriter, Compression};
+pub use matrix_market::{MatrixMarketField, MatrixMarketSymmetry};
+pub use mmap_binary::MappedCOOTensor;
+pub use read_binary::BinaryReadError;
 pub use read_coo::*;
-pub use write_coo::*;
+pub use read_coo_chunks::{COOTensorChunk, COOTensorChunks};
+pub use read_coo_parallel::DEFAULT_TEXT_CHUNK_SIZE;
+pub use read_numpy::NumpyReadError;
@@ -0,0 +1,342 @@
+//! Read a tensor from a binary file.
+//!
+//! The real documentation is at [`pattie::structs::tensor::COOTensor`].
+//!
+//! [`pattie::structs::tensor::COOTensor`]: ../../structs/tensor/struct.COOTensor.html#method.read_from_binary
+
+use super::binary_format::{FLAG_COALESCED, FLAG_SORTED, MAGIC, NO_STRING, VERSION};
+use super::compression::decompress;
+use crate::structs::axis::{Axes, Axis, AxisBuilder};
+use crate::structs::tensor::{self, COOTensor, COOTensorInner};
+use crate::structs::vec::SmallVec;
+use crate::traits::{BinaryScalar, IdxType, RawParts, ValType};
+use ndarray::{Array2, ArrayD, ArrayView2};
+use std::borrow::Cow;
+use std::io::{self, Read};
+use std::iter;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum BinaryReadError {
+    #[error("{0}")]
+    IOError(#[from] io::Error),
+    #[error("not a binary tensor file")]
+    MagicError,
+    #[error("unsupported format version {0}")]
+    VersionError(u32),
+    #[error("expect {what} type tag {expect:#04x}, but found {found:#04x}")]
+    TypeError {
+        what: &'static str,
+        expect: u8,
+        found: u8,
+    },
+    #[error("invalid header: {0}")]
+    HeaderError(Cow<'static, str>),
+    #[error("block {block}: index out of bound")]
+    IndexOutOfBoundError { block: usize },
+}
+
+impl<IT, VT> tensor::COOTensor<IT, VT>
+where
+    IT: IdxType + BinaryScalar,
+    VT: ValType + BinaryScalar,
+{
+    /// Read a tensor from a binary file written by [`tensor::COOTensor::write_to_binary`].
+    ///
+    /// # Arguments
+    ///
+    /// * `r` - A `std::io::Read` object. Examples are `std::fs::File`, `std::io::stdin()`, `&[u8]`. Gzip, Zstandard or XZ compressed input is detected, see [`crate::io::Compression`].
+    ///
+    /// `IT` and `VT` must be the same types as the tensor was written with.
+    pub fn read_from_binary<R>(r: &mut R) -> Result<tensor::COOTensor<IT, VT>, BinaryReadError>
+    where
+        R: io::Read,
+    {
+        let mut r = BinaryReader {
+            inner: io::BufReader::new(decompress(r)?),
+            position: 0,
+        };
+        let header = read_header::<IT, VT, _>(&mut r)?;
+
+        // Indices
+        r.skip_to(header.indices_offset)?;
+        let indices = r.read_scalars::<IT>(header.num_indices()?)?;
+        let indices =
+            Array2::from_shape_vec((header.num_blocks, header.sparse_axes.len()), indices).unwrap();
+        header.check_indices(indices.view())?;
+
+        // Values
+        r.skip_to(header.values_offset)?;
+        let values_shape = header.values_shape();
+        let values = r.read_scalars::<VT>(header.num_values()?)?;
+        let values = ArrayD::from_shape_vec(values_shape, values).unwrap();
+
+        let result = COOTensorInner {
+            name: header.name,
+            shape: header.shape,
+            sparse_axes: header.sparse_axes,
+            dense_axes: header.dense_axes,
+            indices,
+            values,
+            sparse_is_sorted: header.sparse_is_sorted,
+            sparse_sort_order: header.sparse_sort_order,
+            sparse_is_coalesced: header.sparse_is_coalesced,
+        };
+
+        Ok(
+            // # Safety
+            // The axes and the indices are checked above.
+            unsafe { COOTensor::from_raw_parts(result) },
+        )
+    }
+}
+
+/// The header of a binary tensor file, which is everything except the two arrays.
+pub(super) struct BinaryHeader<IT>
+where
+    IT: IdxType,
+{
+    pub(super) name: Option<String>,
+    pub(super) shape: Axes<IT>,
+    pub(super) sparse_axes: Axes<IT>,
+    pub(super) dense_axes: Axes<IT>,
+    pub(super) num_blocks: usize,
+    pub(super) indices_offset: u64,
+    pub(super) values_offset: u64,
+    pub(super) sparse_is_sorted: bool,
+    /// Same as `sparse_axes` if the tensor is not sorted.
+    pub(super) sparse_sort_order: Axes<IT>,
+    pub(super) sparse_is_coalesced: bool,
+}
+
+impl<IT> BinaryHeader<IT>
+where
+    IT: IdxType,
+{
+    /// The number of scalars in the index array.
+    pub(super) fn num_indices(&self) -> Result<usize, BinaryReadError> {
+        self.num_blocks
+            .checked_mul(self.sparse_axes.len())
+            .ok_or_else(|| BinaryReadError::HeaderError("too many blocks".into()))
+    }
+
+    /// The shape of the value array.
+    pub(super) fn values_shape(&self) -> Vec<usize> {
+        iter::once(self.num_blocks)
+            .chain(self.dense_axes.iter().map(Axis::len))
+            .collect()
+    }
+
+    /// The number of scalars in the value array.
+    pub(super) fn num_values(&self) -> Result<usize, BinaryReadError> {
+        self.values_shape()
+            .iter()
+            .try_fold(1usize, |acc, &len| acc.checked_mul(len))
+            .ok_or_else(|| BinaryReadError::HeaderError("too many blocks".into()))
+    }
+
+    /// Check that each index is inside the shape.
+    pub(super) fn check_indices(&self, indices: ArrayView2<IT>) -> Result<(), BinaryReadError> {
+        for (block, index) in indices.rows().into_iter().enumerate() {
+            if !index
+                .iter()
+                .zip(self.sparse_axes.iter())
+                .all(|(idx, axis)| axis.range().contains(idx))
+            {
+                return Err(BinaryReadError::IndexOutOfBoundError { block });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Read and check the header, stopping right after it.
+pub(super) fn read_header<IT, VT, R>(
+    r: &mut BinaryReader<R>,
+) -> Result<BinaryHeader<IT>, BinaryReadError>
+where
+    IT: IdxType + BinaryScalar,
+    VT: ValType + BinaryScalar,
+    R: io::Read,
+{
+    if r.read_bytes(MAGIC.len() as u64)? != MAGIC {
+        return Err(BinaryReadError::MagicError);
+    }
+    let version = r.read_scalar::<u32>()?;
+    if version != VERSION {
+        return Err(BinaryReadError::VersionError(version));
+    }
+    let [index_tag, value_tag, flags, _] = <[u8; 4]>::try_from(r.read_bytes(4)?).unwrap();
+    if index_tag != IT::TYPE_TAG {
+        return Err(BinaryReadError::TypeError {
+            what: "index",
+            expect: IT::TYPE_TAG,
+            found: index_tag,
+        });
+    }
+    if value_tag != VT::TYPE_TAG {
+        return Err(BinaryReadError::TypeError {
+            what: "value",
+            expect: VT::TYPE_TAG,
+            found: value_tag,
+        });
+    }
+    let ndim = r.read_scalar::<u32>()? as usize;
+    let num_sparse_axes = r.read_scalar::<u32>()? as usize;
+    if num_sparse_axes > ndim {
+        return Err(BinaryReadError::HeaderError(
+            "more sparse axes than axes".into(),
+        ));
+    }
+    let num_blocks = to_usize(r.read_scalar::<u64>()?)?;
+    let indices_offset = r.read_scalar::<u64>()?;
+    let values_offset = r.read_scalar::<u64>()?;
+    let name = r.read_string()?;
+
+    let shape = (0..ndim)
+        .map(|_| {
+            let lower = r.read_scalar::<IT>()?;
+            let upper = r.read_scalar::<IT>()?;
+            if lower > upper {
+                return Err(BinaryReadError::HeaderError(
+                    "axis lower bound is larger than upper bound".into(),
+                ));
+            }
+            let mut builder = AxisBuilder::new().range(lower..upper);
+            if let Some(label) = r.read_string()? {
+                builder = builder.label(label);
+            }
+            Ok(builder.build())
+        })
+        .collect::<Result<Axes<_>, _>>()?;
+    let sparse_axes = r.read_axes(&shape, num_sparse_axes)?;
+    let dense_axes = r.read_axes(&shape, ndim - num_sparse_axes)?;
+    if sparse_axes.iter().chain(dense_axes.iter()).any(|axis| {
+        sparse_axes
+            .iter()
+            .chain(dense_axes.iter())
+            .filter(|&ax| ax == axis)
+            .count()
+            != 1
+    }) {
+        return Err(BinaryReadError::HeaderError(
+            "each axis must be either sparse or dense".into(),
+        ));
+    }
+    let num_sort_axes = r.read_scalar::<u32>()? as usize;
+    let sparse_sort_order = r.read_axes(&shape, num_sort_axes)?;
+    let sparse_is_sorted = flags & FLAG_SORTED != 0;
+    // With the same length, containing each sparse axis means each one appears exactly once.
+    if sparse_is_sorted
+        && (num_sort_axes != num_sparse_axes
+            || !sparse_axes
+                .iter()
+                .all(|axis| sparse_sort_order.contains(axis)))
+    {
+        return Err(BinaryReadError::HeaderError(
+            "the sort order must contain each sparse axis".into(),
+        ));
+    }
+
+    Ok(BinaryHeader {
+        name,
+        shape,
+        sparse_axes: sparse_axes.clone(),
+        dense_axes,
+        num_blocks,
+        indices_offset,
+        values_offset,
+        sparse_is_sorted,
+        sparse_sort_order: if sparse_is_sorted {
+            sparse_sort_order
+        } else {
+            sparse_axes
+        },
+        sparse_is_coalesced: flags & FLAG_COALESCED != 0,
+    })
+}
+
+/// A reader that keeps track of the current byte offset.
+pub(super) struct BinaryReader<R>
+where
+    R: io::Read,
+{
+    pub(super) inner: R,
+    pub(super) position: u64,
+}
+
+impl<R> BinaryReader<R>
+where
+    R: io::Read,
+{
+    /// Read exactly `len` bytes, without allocating `len` bytes in advance.
+    fn read_bytes(&mut self, len: u64) -> Result<Vec<u8>, BinaryReadError> {
+        let mut buf = Vec::new();
+        (&mut self.inner).take(len).read_to_end(&mut buf)?;
+        if (buf.len() as u64) < len {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        self.position += len;
+        Ok(buf)
+    }
+
+    fn read_scalar<T: BinaryScalar>(&mut self) -> Result<T, BinaryReadError> {
+        Ok(T::read_le(&self.read_bytes(T::SIZE as u64)?))
+    }
+
+    fn read_scalars<T: BinaryScalar>(&mut self, len: usize) -> Result<Vec<T>, BinaryReadError> {
+        let num_bytes = len
+            .checked_mul(T::SIZE)
+            .ok_or_else(|| BinaryReadError::HeaderError("too many blocks".into()))?;
+        Ok(self
+            .read_bytes(num_bytes as u64)?
+            .chunks_exact(T::SIZE)
+            .map(T::read_le)
+            .collect())
+    }
+
+    fn read_string(&mut self) -> Result<Option<String>, BinaryReadError> {
+        let len = self.read_scalar::<u32>()?;
+        if len == NO_STRING {
+            return Ok(None);
+        }
+        String::from_utf8(self.read_bytes(len.into())?)
+            .map(Some)
+            .map_err(|_| BinaryReadError::HeaderError("string is not valid UTF-8".into()))
+    }
+
+    /// Read `len` positions, and look up each axis in `shape`.
+    fn read_axes<IT>(&mut self, shape: &[Axis<IT>], len: usize) -> Result<Axes<IT>, BinaryReadError>
+    where
+        IT: IdxType,
+    {
+        (0..len)
+            .map(|_| {
+                let position = self.read_scalar::<u32>()? as usize;
+                shape
+                    .get(position)
+                    .cloned()
+                    .ok_or_else(|| BinaryReadError::HeaderError("axis does not exist".into()))
+            })
+            .collect::<Result<SmallVec<_>, _>>()
+    }
+
+    fn skip_to(&mut self, offset: u64) -> Result<(), BinaryReadError> {
+        if offset < self.position {
+            return Err(BinaryReadError::HeaderError(
+                "array offset overlaps the header".into(),
+            ));
+        }
+        let len = offset - self.position;
+        let skipped = io::copy(&mut (&mut self.inner).take(len), &mut io::sink())?;
+        if skipped < len {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        self.position = offset;
+        Ok(())
+    }
+}
+
+fn to_usize(value: u64) -> Result<usize, BinaryReadError> {
+    usize::try_from(value).map_err(|_| BinaryReadError::HeaderError("too many blocks".into()))
+}
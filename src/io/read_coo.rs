@@ -4,21 +4,24 @@
 //!
 //! [`pattie::structs::tensor::COOTensor`]: ../../structs/tensor/struct.COOTensor.html#method.read_from_text
 
+use super::compression::decompress;
 use super::lineno_reader::LineNumberReader;
-use crate::structs::axis::{Axes, AxisBuilder};
+use super::text_header::TextHeader;
+use crate::structs::axis::{map_axes_unwrap, Axes, Axis, AxisBuilder};
 use crate::structs::tensor::{self, COOTensor};
-use crate::structs::vec::{smallvec, SmallVec};
-use crate::traits;
-use ndarray::{aview0, aview1};
+use crate::structs::vec::SmallVec;
+use crate::traits::{self, RawParts, Tensor};
+use ndarray::{Array2, ArrayD, IxDyn};
 use std::ascii;
 use std::borrow::Cow;
 use std::fmt::Display;
 use std::io;
+use std::ops::Range;
 use std::str::FromStr;
 use std::string::FromUtf8Error;
 use thiserror::Error;
 
-enum Token {
+pub(super) enum Token {
     Eof,
     NewLine,
     Comment,
@@ -26,11 +29,11 @@ enum Token {
 }
 
 #[derive(Copy, Clone, Debug, Default)]
-struct TokenMask {
-    eof: bool,
-    new_line: bool,
-    comment: bool,
-    value: bool,
+pub(super) struct TokenMask {
+    pub eof: bool,
+    pub new_line: bool,
+    pub comment: bool,
+    pub value: bool,
 }
 
 #[derive(Error, Debug)]
@@ -41,6 +44,12 @@ pub enum TensorReadError {
         column: u64,
         source: FromUtf8Error,
     },
+    #[error("line {line}, column {column}: invalid header: {message}")]
+    HeaderError {
+        line: u64,
+        column: u64,
+        message: String,
+    },
     #[error("line {line}, column {column}: index out of bound")]
     IndexOutOfBoundError { line: u64, column: u64 },
     #[error("line {line}, column {column}: {source}")]
@@ -74,7 +83,7 @@ where
     ///
     /// # Arguments
     ///
-    /// * `r` - A `std::io::Read` object. Examples are `std::fs::File`, `std::io::stdin()`, `Vec<u8>`.
+    /// * `r` - A `std::io::Read` object. Examples are `std::fs::File`, `std::io::stdin()`, `Vec<u8>`. Gzip, Zstandard or XZ compressed input is detected, see [`crate::io::Compression`].
     ///
     /// # Example input
     ///
@@ -96,6 +105,15 @@ where
     /// Second line is the lower bound of each axis (inclusive).
     /// Third line is the upper bound of each axis (exclusive).
     /// The following lines are the elements of the tensor.
+    ///
+    /// Text after `#` is a comment.
+    /// Comments in the form of `#@ name: ...` and `#@ axis i: ...` set the tensor name and the label of the `i`-th axis.
+    /// Backslashes and line breaks in the name or labels are escaped as `\\`, `\n` and `\r`.
+    /// Labels written by older versions as `# Axis i: ...` are also read, but their `# name` comment is not.
+    ///
+    /// A `#@ dense: i j ...` comment before the elements marks the listed axes as dense,
+    /// then each element is a dense block, see [`tensor::COOTensor::write_to_text`].
+    /// The sparse axes are in the order of the shape, and the dense axes are in the listed order.
     #[inline]
     pub fn read_from_text<R>(r: &mut R) -> Result<tensor::COOTensor<IT, VT>, TensorReadError>
     where
@@ -117,13 +135,102 @@ where
         IT: FromStr,
     {
         let mut parser = parser;
+        let r = decompress(r).map_err(|source| TensorReadError::IOError {
+            line: 1,
+            column: 1,
+            source,
+        })?;
         let mut r = LineNumberReader::new(io::BufReader::new(r));
+        let mut header = TextHeader::default();
 
-        // First line: number of axes
-        let ndim = {
+        let (shape, eof) = read_shape(&mut r, &mut header)?;
+        let dense = take_dense_axes(&mut header, shape.len())?;
+        let mut indices = Vec::new();
+        let mut values = Vec::new();
+        if !eof {
+            read_elements(
+                &mut r,
+                &mut header,
+                &shape,
+                &dense,
+                &mut parser,
+                usize::MAX,
+                &mut indices,
+                &mut values,
+            )?;
+        }
+        apply_header(from_elements(&shape, &dense, indices, values), header)
+    }
+}
+
+/// Read the first three lines, which are the shape of the tensor.
+///
+/// Returns the shape, and whether the end of file is reached.
+pub(super) fn read_shape<R, IT>(
+    r: &mut LineNumberReader<R>,
+    header: &mut TextHeader,
+) -> Result<(Axes<IT>, bool), TensorReadError>
+where
+    R: io::Read,
+    IT: traits::IdxType + FromStr,
+{
+    // First line: number of axes
+    let ndim = {
+        let (line, column) = r.line_column();
+        let token = read_until_token(
+            r,
+            header,
+            TokenMask {
+                eof: false,
+                new_line: false,
+                comment: false,
+                value: true, // !
+            },
+            TokenMask {
+                eof: false,
+                new_line: true, // !
+                comment: true,  // !
+                value: false,
+            },
+        )?;
+        if let Token::Value(value) = token {
+            value
+                .parse::<usize>()
+                .map_err(|_| TensorReadError::ValueError {
+                    line,
+                    column,
+                    value: value.into(),
+                })?
+        } else {
+            unreachable!();
+        }
+    };
+
+    // Read until EOL (or EOF if ndim == 0)
+    let mut eof = is_token_eof(&read_until_token(
+        r,
+        header,
+        TokenMask {
+            eof: ndim == 0,
+            new_line: true, // !
+            comment: false,
+            value: false,
+        },
+        TokenMask {
+            eof: false,
+            new_line: true, // !
+            comment: true,  // !
+            value: false,
+        },
+    )?);
+
+    // Second line: lower bound of each axis (inclusive)
+    let lower_bound = (0..ndim)
+        .map(|dim| {
             let (line, column) = r.line_column();
             let token = read_until_token(
-                &mut r,
+                r,
+                header,
                 TokenMask {
                     eof: false,
                     new_line: false,
@@ -132,27 +239,30 @@ where
                 },
                 TokenMask {
                     eof: false,
-                    new_line: true, // !
-                    comment: true,  // !
+                    new_line: dim == 0,
+                    comment: true, // !
                     value: false,
                 },
             )?;
             if let Token::Value(value) = token {
-                value
-                    .parse::<usize>()
+                Ok(value
+                    .parse::<IT>()
                     .map_err(|_| TensorReadError::ValueError {
                         line,
                         column,
                         value: value.into(),
-                    })?
+                    })?)
             } else {
                 unreachable!();
             }
-        };
+        })
+        .collect::<Result<SmallVec<_>, _>>()?;
 
-        // Read until EOL (or EOF if ndim == 0)
-        let mut eof = is_token_eof(&read_until_token(
-            &mut r,
+    // Read until EOL
+    eof = eof
+        || is_token_eof(&read_until_token(
+            r,
+            header,
             TokenMask {
                 eof: ndim == 0,
                 new_line: true, // !
@@ -161,208 +271,290 @@ where
             },
             TokenMask {
                 eof: false,
-                new_line: true, // !
-                comment: true,  // !
+                new_line: false,
+                comment: true, // !
                 value: false,
             },
         )?);
 
-        // Second line: lower bound of each axis (inclusive)
-        let lower_bound = (0..ndim)
-            .map(|dim| {
-                let (line, column) = r.line_column();
-                let token = read_until_token(
-                    &mut r,
-                    TokenMask {
-                        eof: false,
-                        new_line: false,
-                        comment: false,
-                        value: true, // !
-                    },
-                    TokenMask {
-                        eof: false,
-                        new_line: dim == 0,
-                        comment: true, // !
-                        value: false,
-                    },
-                )?;
-                if let Token::Value(value) = token {
-                    Ok(value
-                        .parse::<IT>()
-                        .map_err(|_| TensorReadError::ValueError {
-                            line,
-                            column,
-                            value: value.into(),
-                        })?)
-                } else {
-                    unreachable!();
-                }
-            })
-            .collect::<Result<SmallVec<_>, _>>()?;
-
-        // Read until EOL
-        eof = eof
-            || is_token_eof(&read_until_token(
-                &mut r,
+    // Third line: upper bound of each axis (exclusive)
+    let upper_bound = (0..ndim)
+        .map(|dim| {
+            let (line, column) = r.line_column();
+            let token = read_until_token(
+                r,
+                header,
                 TokenMask {
-                    eof: ndim == 0,
-                    new_line: true, // !
+                    eof: false,
+                    new_line: false,
                     comment: false,
-                    value: false,
+                    value: true, // !
                 },
                 TokenMask {
                     eof: false,
-                    new_line: false,
+                    new_line: dim == 0,
                     comment: true, // !
-                    value: false,
+                    value: true,
                 },
-            )?);
+            )?;
+            if let Token::Value(value) = token {
+                Ok(value
+                    .parse::<IT>()
+                    .map_err(|_| TensorReadError::ValueError {
+                        line,
+                        column,
+                        value: value.into(),
+                    })?)
+            } else {
+                unreachable!();
+            }
+        })
+        .collect::<Result<SmallVec<_>, _>>()?;
 
-        // Third line: upper bound of each axis (exclusive)
-        let upper_bound = (0..ndim)
-            .map(|dim| {
-                let (line, column) = r.line_column();
-                let token = read_until_token(
-                    &mut r,
-                    TokenMask {
-                        eof: false,
-                        new_line: false,
-                        comment: false,
-                        value: true, // !
-                    },
-                    TokenMask {
-                        eof: false,
-                        new_line: dim == 0,
-                        comment: true, // !
-                        value: true,
-                    },
-                )?;
-                if let Token::Value(value) = token {
-                    Ok(value
-                        .parse::<IT>()
-                        .map_err(|_| TensorReadError::ValueError {
-                            line,
-                            column,
-                            value: value.into(),
-                        })?)
-                } else {
-                    unreachable!();
-                }
-            })
-            .collect::<Result<SmallVec<_>, _>>()?;
+    // Read until EOL
+    eof = eof
+        || is_token_eof(&read_until_token(
+            r,
+            header,
+            TokenMask {
+                eof: ndim == 0,
+                new_line: true, // !
+                comment: false,
+                value: false,
+            },
+            TokenMask {
+                eof: false,
+                new_line: false,
+                comment: true, // !
+                value: false,
+            },
+        )?);
 
-        // Read until EOL
-        eof = eof
-            || is_token_eof(&read_until_token(
-                &mut r,
+    let shape = lower_bound
+        .into_iter()
+        .zip(upper_bound)
+        .map(|(lower, upper)| AxisBuilder::new().range(lower..upper).build())
+        .collect::<Axes<_>>();
+    Ok((shape, eof))
+}
+
+/// Read the elements after the shape until the end of file, appending them to `indices` and `values`.
+///
+/// Each element is the indices of the sparse axes in the order of `shape`,
+/// followed by a dense block in the order of `dense`, which are positions in `shape`.
+/// At most `max_blocks` elements are read.
+/// Returns the lines of the elements read, or `None` if the end of file is reached before any element.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn read_elements<R, P, IT, VT>(
+    r: &mut LineNumberReader<R>,
+    header: &mut TextHeader,
+    shape: &[Axis<IT>],
+    dense: &[usize],
+    parser: &mut P,
+    max_blocks: usize,
+    indices: &mut Vec<IT>,
+    values: &mut Vec<VT>,
+) -> Result<Option<Range<u64>>, TensorReadError>
+where
+    R: io::Read,
+    P: FnMut(&str) -> Option<VT>,
+    IT: traits::IdxType + FromStr,
+{
+    let sparse_axes = shape
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !dense.contains(i))
+        .map(|(_, axis)| axis)
+        .collect::<SmallVec<_>>();
+    let block_len = dense.iter().map(|&i| shape[i].len()).product::<usize>();
+    if sparse_axes.is_empty() && block_len == 0 {
+        // Each element would be an empty line.
+        return Ok(None);
+    }
+    let mut lines: Option<Range<u64>> = None;
+
+    // For each line, until EOF
+    'a: for _ in 0..max_blocks {
+        let mut first_line = None;
+        for token_idx in 0..sparse_axes.len() + block_len {
+            let (line, column) = r.line_column();
+            // EOF may be reached before the first token
+            let token = read_until_token(
+                r,
+                header,
                 TokenMask {
-                    eof: ndim == 0,
-                    new_line: true, // !
+                    eof: token_idx == 0,
+                    new_line: false,
                     comment: false,
-                    value: false,
+                    value: true,
                 },
                 TokenMask {
                     eof: false,
-                    new_line: false,
-                    comment: true, // !
+                    new_line: token_idx == 0,
+                    comment: true,
                     value: false,
                 },
-            )?);
-
-        // Create the tensor
-        let shape = lower_bound
-            .into_iter()
-            .zip(upper_bound.into_iter())
-            .map(|(lower, upper)| AxisBuilder::new().range(lower..upper).build())
-            .collect::<Axes<_>>();
-        let is_axis_dense: SmallVec<_> = smallvec![false; ndim];
-        let mut tensor = COOTensor::zeros(&shape, &is_axis_dense);
-
-        if eof {
-            return Ok(tensor);
-        }
-
-        // Reuse index to save allocations
-        let mut index: SmallVec<_> = smallvec![IT::zero(); ndim];
-
-        // For each line, until EOF
-        'a: loop {
-            // Index
-            for (dim, (idx, axis)) in index.iter_mut().zip(shape.iter()).enumerate() {
-                let (line, column) = r.line_column();
-                // EOF may be reached if dim == 0
-                let token = read_until_token(
-                    &mut r,
-                    TokenMask {
-                        eof: dim == 0,
-                        new_line: false,
-                        comment: false,
-                        value: true,
-                    },
-                    TokenMask {
-                        eof: false,
-                        new_line: dim == 0,
-                        comment: true,
-                        value: false,
-                    },
-                )?;
-                match token {
-                    Token::Value(value) => {
-                        *idx = value
-                            .parse::<IT>()
-                            .map_err(|_| TensorReadError::ValueError {
-                                line,
-                                column,
-                                value: value.into(),
-                            })?;
-                        if !axis.range().contains(idx) {
-                            return Err(TensorReadError::IndexOutOfBoundError { line, column });
-                        }
-                    }
-                    Token::Eof => {
-                        break 'a;
-                    }
-                    _ => unreachable!(),
-                }
-            }
+            )?;
+            let value = match token {
+                Token::Value(value) => value,
+                Token::Eof => break 'a,
+                _ => unreachable!(),
+            };
+            first_line.get_or_insert(r.line_number());
 
-            let value = {
-                let (line, column) = r.line_column();
-                let token = read_until_token(
-                    &mut r,
-                    TokenMask {
-                        eof: false,
-                        new_line: false,
-                        comment: false,
-                        value: true, // !
-                    },
-                    TokenMask {
-                        eof: false,
-                        new_line: false,
-                        comment: true, // !
-                        value: false,
-                    },
-                )?;
-                if let Token::Value(value) = token {
-                    parser(&value).ok_or_else(|| TensorReadError::ValueError {
+            if let Some(axis) = sparse_axes.get(token_idx) {
+                // Index
+                let idx = value
+                    .parse::<IT>()
+                    .map_err(|_| TensorReadError::ValueError {
                         line,
                         column,
                         value: value.into(),
-                    })?
-                } else {
-                    unreachable!();
+                    })?;
+                if !axis.range().contains(&idx) {
+                    return Err(TensorReadError::IndexOutOfBoundError { line, column });
                 }
-            };
-
-            tensor.push_block(aview1(&index), aview0(&value).into_dyn());
+                indices.push(idx);
+            } else {
+                // Value
+                values.push(parser(&value).ok_or_else(|| TensorReadError::ValueError {
+                    line,
+                    column,
+                    value: value.into(),
+                })?);
+            }
         }
 
-        Ok(tensor)
+        let last_line = r.line_number();
+        let first_line = lines
+            .as_ref()
+            .map_or(first_line.unwrap_or(last_line), |lines| lines.start);
+        lines = Some(first_line..last_line + 1);
+    }
+
+    Ok(lines)
+}
+
+/// Check the dense axes declared in the header, and remove them from the header.
+///
+/// Returns the positions of the dense axes in the shape.
+pub(super) fn take_dense_axes(
+    header: &mut TextHeader,
+    ndim: usize,
+) -> Result<SmallVec<usize>, TensorReadError> {
+    let (line, column, dense) = match header.dense.take() {
+        Some(dense) => dense,
+        None => return Ok(SmallVec::new()),
+    };
+    for (i, &axis) in dense.iter().enumerate() {
+        let message = if axis >= ndim {
+            format!("axis {} does not exist", axis)
+        } else if dense[..i].contains(&axis) {
+            format!("axis {} is listed more than once", axis)
+        } else {
+            continue;
+        };
+        return Err(TensorReadError::HeaderError {
+            line,
+            column,
+            message,
+        });
+    }
+    Ok(dense.into())
+}
+
+/// Create a tensor from the elements read by [`read_elements`].
+pub(super) fn from_elements<IT, VT>(
+    shape: &[Axis<IT>],
+    dense: &[usize],
+    indices: Vec<IT>,
+    values: Vec<VT>,
+) -> COOTensor<IT, VT>
+where
+    IT: traits::IdxType,
+    VT: traits::ValType,
+{
+    let is_axis_dense = (0..shape.len())
+        .map(|i| dense.contains(&i))
+        .collect::<SmallVec<_>>();
+    let mut tensor = COOTensor::zeros(shape, &is_axis_dense);
+    let dense_axes = dense.iter().map(|&i| shape[i].clone()).collect::<Axes<_>>();
+    let block_len = dense_axes.iter().map(|axis| axis.len()).product::<usize>();
+    let num_sparse = shape.len() - dense.len();
+    let num_blocks = indices
+        .len()
+        .checked_div(num_sparse)
+        .or_else(|| values.len().checked_div(block_len))
+        .unwrap_or(0);
+    let values_shape = std::iter::once(num_blocks)
+        .chain(dense_axes.iter().map(|axis| axis.len()))
+        .collect::<SmallVec<_>>();
+    // # Safety
+    // Each row of `indices` is the index of each block in `values`, and is inside `shape`.
+    // The dense axes are the same as `COOTensor::zeros` created, only reordered along with the values.
+    let raw_parts = unsafe { tensor.raw_parts_mut() };
+    raw_parts.indices =
+        Array2::from_shape_vec((num_blocks, raw_parts.sparse_axes.len()), indices).unwrap();
+    raw_parts.values = ArrayD::from_shape_vec(IxDyn(&values_shape), values).unwrap();
+    raw_parts.dense_axes = dense_axes;
+    if num_blocks != 0 {
+        raw_parts.sparse_is_sorted = false;
+        raw_parts.sparse_is_coalesced = false;
+    }
+    tensor
+}
+
+/// Set the tensor name and the axis labels found in the comments.
+pub(super) fn apply_header<IT, VT>(
+    mut tensor: COOTensor<IT, VT>,
+    header: TextHeader,
+) -> Result<COOTensor<IT, VT>, TensorReadError>
+where
+    IT: traits::IdxType,
+    VT: traits::ValType,
+{
+    if let Some((line, column, _)) = header.dense {
+        return Err(TensorReadError::HeaderError {
+            line,
+            column,
+            message: "dense axes must be declared before the elements".to_owned(),
+        });
+    }
+    *tensor.name_mut() = header.name;
+    if header.labels.is_empty() {
+        return Ok(tensor);
+    }
+
+    let old_shape = Axes::from(tensor.shape());
+    let mut shape = old_shape.clone();
+    for (line, column, axis, label) in header.labels {
+        let axis = shape
+            .get_mut(axis)
+            .ok_or_else(|| TensorReadError::HeaderError {
+                line,
+                column,
+                message: format!("axis {} does not exist", axis),
+            })?;
+        *axis = AxisBuilder::new().label(label).range(axis.range()).build();
     }
+    let relabel = |axes: &[Axis<IT>]| {
+        map_axes_unwrap(axes, &old_shape)
+            .map(|i| shape[i].clone())
+            .collect::<Axes<_>>()
+    };
+    // # Safety
+    // Only the labels are changed, each axis keeps its range and position.
+    let raw_parts = unsafe { tensor.raw_parts_mut() };
+    raw_parts.sparse_axes = relabel(&raw_parts.sparse_axes);
+    raw_parts.dense_axes = relabel(&raw_parts.dense_axes);
+    raw_parts.sparse_sort_order = relabel(&raw_parts.sparse_sort_order);
+    raw_parts.shape = shape;
+    Ok(tensor)
 }
 
 fn read_next_token<R>(
     r: &mut LineNumberReader<R>,
+    header: &mut TextHeader,
     expect: TokenMask,
 ) -> Result<Token, TensorReadError>
 where
@@ -419,7 +611,7 @@ where
             }
             Some(b'#') => {
                 if expect.comment {
-                    read_next_comment(r)?;
+                    read_next_comment(r, header, (line, column))?;
                     return Ok(Token::Comment);
                 } else {
                     return Err(TensorReadError::TokenizeError {
@@ -458,8 +650,9 @@ where
     }
 }
 
-fn read_until_token<R>(
+pub(super) fn read_until_token<R>(
     r: &mut LineNumberReader<R>,
+    header: &mut TextHeader,
     expect: TokenMask,
     skip: TokenMask,
 ) -> Result<Token, TensorReadError>
@@ -473,7 +666,7 @@ where
         value: skip.value || expect.value,
     };
     loop {
-        let token = read_next_token(r, skip)?;
+        let token = read_next_token(r, header, skip)?;
         match token {
             Token::Eof => {
                 if expect.eof {
@@ -499,10 +692,24 @@ where
     }
 }
 
-fn read_next_comment<R>(r: &mut LineNumberReader<R>) -> Result<(), TensorReadError>
+fn read_next_comment<R>(
+    r: &mut LineNumberReader<R>,
+    header: &mut TextHeader,
+    (comment_line, comment_column): (u64, u64),
+) -> Result<(), TensorReadError>
 where
     R: io::Read,
 {
+    let mut comment = Vec::new();
+    let mut parse_comment = |comment: &[u8]| {
+        header
+            .parse_comment(comment_line, comment_column, comment)
+            .map_err(|message| TensorReadError::HeaderError {
+                line: comment_line,
+                column: comment_column,
+                message,
+            })
+    };
     loop {
         let (line, column) = r.line_column();
         let look_ahead = r.peek_byte().map_err(|source| TensorReadError::IOError {
@@ -517,9 +724,10 @@ where
                     column,
                     source,
                 })?;
-                return Ok(());
+                return parse_comment(&comment);
             }
-            Some(_) => {
+            Some(b) => {
+                comment.push(b);
                 r.read_byte().map_err(|source| TensorReadError::IOError {
                     line,
                     column,
@@ -527,7 +735,7 @@ where
                 })?;
             }
             None => {
-                return Ok(());
+                return parse_comment(&comment);
             }
         }
     }
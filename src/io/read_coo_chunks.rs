@@ -0,0 +1,219 @@
+//! Read a tensor from a text file in bounded-size chunks.
+//!
+//! The real documentation is at [`pattie::structs::tensor::COOTensor`].
+//!
+//! [`pattie::structs::tensor::COOTensor`]: ../../structs/tensor/struct.COOTensor.html#method.read_chunks_from_text
+
+use super::compression::decompress;
+use super::lineno_reader::LineNumberReader;
+use super::read_coo::{
+    apply_header, from_elements, read_elements, read_shape, take_dense_axes, TensorReadError,
+};
+use super::text_header::TextHeader;
+use crate::structs::axis::{Axes, Axis};
+use crate::structs::tensor::{self, COOTensor};
+use crate::structs::vec::SmallVec;
+use crate::traits::{self, Tensor};
+use std::io;
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::str::FromStr;
+
+/// A part of a tensor, yielded by [`COOTensorChunks`].
+#[derive(Clone, Debug)]
+pub struct COOTensorChunk<IT, VT>
+where
+    IT: traits::IdxType,
+    VT: traits::ValType,
+{
+    /// The elements of this chunk.
+    /// Every chunk of the same file has the same name and the same axes, including their ids.
+    pub tensor: COOTensor<IT, VT>,
+    /// The lines of the elements in the file, starting from 1.
+    pub lines: Range<u64>,
+}
+
+/// An iterator over the chunks of a text file, created by [`tensor::COOTensor::read_chunks_from_text`].
+///
+/// Iteration stops after the first error.
+pub struct COOTensorChunks<'a, IT, VT, P = fn(&str) -> Option<VT>>
+where
+    IT: traits::IdxType,
+    VT: traits::ValType,
+{
+    r: LineNumberReader<io::BufReader<Box<dyn io::Read + 'a>>>,
+    name: Option<String>,
+    shape: Axes<IT>,
+    dense: SmallVec<usize>,
+    parser: P,
+    max_blocks: usize,
+    finished: bool,
+    _phantom: PhantomData<VT>,
+}
+
+impl<IT, VT> tensor::COOTensor<IT, VT>
+where
+    IT: traits::IdxType,
+    VT: traits::ValType,
+{
+    /// Read a tensor from the text file in chunks of at most `max_blocks` blocks each.
+    ///
+    /// The format is the same as [`tensor::COOTensor::read_from_text`].
+    /// The shape is read immediately, then each chunk is read when the iterator advances,
+    /// so only one chunk needs to be in memory at a time.
+    ///
+    /// The tensor name, axis labels and dense axes must appear before the end of the line of upper bounds,
+    /// as [`tensor::COOTensor::write_to_text`] does.
+    /// Because the shape shared by every chunk is fixed once this function returns,
+    /// any of them appearing later, even before the first element, is a [`TensorReadError::HeaderError`].
+    ///
+    /// # Arguments
+    ///
+    /// * `r` - A `std::io::Read` object. Examples are `std::fs::File`, `&mut std::io::Stdin`, `&[u8]`. Gzip, Zstandard or XZ compressed input is detected, see [`crate::io::Compression`].
+    /// * `max_blocks` - The maximum number of elements, or dense blocks, in each chunk. Must be greater than 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pattie::structs::tensor::COOTensor;
+    /// use pattie::traits::RawParts;
+    ///
+    /// let input = "2\n0 0\n2 2\n0 0 1.0\n0 1 2.0\n1 1 3.0\n";
+    /// let mut sum = 0.0;
+    /// for chunk in COOTensor::<u32, f32>::read_chunks_from_text(input.as_bytes(), 2).unwrap() {
+    ///     let chunk = chunk.unwrap();
+    ///     sum += chunk.tensor.raw_parts().values.sum();
+    /// }
+    /// assert_eq!(sum, 6.0);
+    /// ```
+    #[inline]
+    pub fn read_chunks_from_text<'a, R>(
+        r: R,
+        max_blocks: usize,
+    ) -> Result<COOTensorChunks<'a, IT, VT>, TensorReadError>
+    where
+        R: io::Read + 'a,
+        IT: FromStr,
+        VT: FromStr,
+    {
+        Self::read_chunks_from_text_with_parser(r, max_blocks, |value| value.parse::<VT>().ok())
+    }
+
+    /// Similar to [`tensor::COOTensor::read_chunks_from_text`], but with a custom parser for values.
+    pub fn read_chunks_from_text_with_parser<'a, R, P>(
+        r: R,
+        max_blocks: usize,
+        parser: P,
+    ) -> Result<COOTensorChunks<'a, IT, VT, P>, TensorReadError>
+    where
+        R: io::Read + 'a,
+        P: FnMut(&str) -> Option<VT>,
+        IT: FromStr,
+    {
+        assert!(max_blocks > 0);
+        let r = decompress(r).map_err(|source| TensorReadError::IOError {
+            line: 1,
+            column: 1,
+            source,
+        })?;
+        let mut r = LineNumberReader::new(io::BufReader::new(r));
+        let mut header = TextHeader::default();
+        let (shape, eof) = read_shape(&mut r, &mut header)?;
+        let dense = take_dense_axes(&mut header, shape.len())?;
+
+        // Label the axes once, so that every chunk shares them.
+        let template: COOTensor<IT, VT> = apply_header(
+            from_elements(&shape, &dense, Vec::new(), Vec::new()),
+            header,
+        )?;
+        Ok(COOTensorChunks {
+            r,
+            name: template.name().map(str::to_owned),
+            shape: Axes::from(template.shape()),
+            dense,
+            parser,
+            max_blocks,
+            finished: eof,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<'a, IT, VT, P> COOTensorChunks<'a, IT, VT, P>
+where
+    IT: traits::IdxType,
+    VT: traits::ValType,
+{
+    /// The name of the tensor.
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// The shape shared by every chunk.
+    #[inline]
+    pub fn shape(&self) -> &[Axis<IT>] {
+        &self.shape
+    }
+}
+
+impl<'a, IT, VT, P> Iterator for COOTensorChunks<'a, IT, VT, P>
+where
+    IT: traits::IdxType + FromStr,
+    VT: traits::ValType,
+    P: FnMut(&str) -> Option<VT>,
+{
+    type Item = Result<COOTensorChunk<IT, VT>, TensorReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let mut header = TextHeader::default();
+        let mut indices = Vec::new();
+        let mut values = Vec::new();
+        let result = read_elements(
+            &mut self.r,
+            &mut header,
+            &self.shape,
+            &self.dense,
+            &mut self.parser,
+            self.max_blocks,
+            &mut indices,
+            &mut values,
+        );
+        let result = result.and_then(|lines| match header.first {
+            Some((line, column)) => Err(TensorReadError::HeaderError {
+                line,
+                column,
+                message: "metadata must appear before the end of the line of upper bounds when reading in chunks".to_owned(),
+            }),
+            None => Ok(lines),
+        });
+        match result {
+            Ok(Some(lines)) => {
+                let mut tensor = from_elements(&self.shape, &self.dense, indices, values);
+                self.finished = tensor.num_blocks() < self.max_blocks;
+                tensor.name_mut().clone_from(&self.name);
+                Some(Ok(COOTensorChunk { tensor, lines }))
+            }
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(err) => {
+                self.finished = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+impl<'a, IT, VT, P> std::iter::FusedIterator for COOTensorChunks<'a, IT, VT, P>
+where
+    IT: traits::IdxType + FromStr,
+    VT: traits::ValType,
+    P: FnMut(&str) -> Option<VT>,
+{
+}
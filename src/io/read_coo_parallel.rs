@@ -0,0 +1,169 @@
+//! Read a tensor from a text file with multiple threads.
+//!
+//! The real documentation is at [`pattie::structs::tensor::COOTensor`].
+//!
+//! [`pattie::structs::tensor::COOTensor`]: ../../structs/tensor/struct.COOTensor.html#method.read_from_text_parallel
+
+use super::compression::decompress;
+use super::lineno_reader::LineNumberReader;
+use super::read_coo::{
+    apply_header, from_elements, read_elements, read_shape, take_dense_axes, TensorReadError,
+};
+use super::text_header::TextHeader;
+use crate::structs::tensor;
+use crate::traits;
+use rayon::prelude::*;
+use std::io::{self, Read};
+use std::str::FromStr;
+
+/// The default number of bytes parsed by each task of [`tensor::COOTensor::read_from_text_parallel`].
+pub const DEFAULT_TEXT_CHUNK_SIZE: usize = 1 << 20;
+
+impl<IT, VT> tensor::COOTensor<IT, VT>
+where
+    IT: traits::IdxType,
+    VT: traits::ValType,
+{
+    /// Read a tensor from the text file with multiple threads.
+    ///
+    /// The format is the same as [`tensor::COOTensor::read_from_text`], and so are the line and column numbers in errors.
+    /// The whole file is loaded into memory, then the elements after the first three lines are split into chunks of lines,
+    /// which are parsed in parallel on the current rayon thread pool.
+    ///
+    /// Each element must be on its own line.
+    ///
+    /// # Arguments
+    ///
+    /// * `r` - A `std::io::Read` object. Examples are `std::fs::File`, `std::io::stdin()`, `Vec<u8>`. Gzip, Zstandard or XZ compressed input is detected, see [`crate::io::Compression`].
+    #[inline]
+    pub fn read_from_text_parallel<R>(
+        r: &mut R,
+    ) -> Result<tensor::COOTensor<IT, VT>, TensorReadError>
+    where
+        R: io::Read,
+        IT: FromStr,
+        VT: FromStr,
+    {
+        Self::read_from_text_parallel_with_parser(r, DEFAULT_TEXT_CHUNK_SIZE, |value| {
+            value.parse::<VT>().ok()
+        })
+    }
+
+    /// Similar to [`tensor::COOTensor::read_from_text_parallel`], but with a custom parser for values.
+    ///
+    /// # Arguments
+    ///
+    /// * `r` - A `std::io::Read` object.
+    /// * `chunk_size` - The approximate number of bytes parsed by each task. A chunk is extended to the end of its last line.
+    /// * `parser` - Parse a value, called from multiple threads.
+    pub fn read_from_text_parallel_with_parser<R, P>(
+        r: &mut R,
+        chunk_size: usize,
+        parser: P,
+    ) -> Result<tensor::COOTensor<IT, VT>, TensorReadError>
+    where
+        R: io::Read,
+        P: Fn(&str) -> Option<VT> + Sync,
+        IT: FromStr,
+    {
+        let mut buffer = Vec::new();
+        decompress(r)
+            .and_then(|mut r| r.read_to_end(&mut buffer))
+            .map_err(|source| TensorReadError::IOError {
+                line: 1,
+                column: 1,
+                source,
+            })?;
+
+        let mut r = LineNumberReader::new(buffer.as_slice());
+        let mut header = TextHeader::default();
+        let (shape, eof) = read_shape(&mut r, &mut header)?;
+        let dense = take_dense_axes(&mut header, shape.len())?;
+        if eof {
+            return apply_header(
+                from_elements(&shape, &dense, Vec::new(), Vec::new()),
+                header,
+            );
+        }
+
+        // The first chunk continues with the reader of the shape, which may have looked ahead by one byte.
+        let start = buffer.len() - r.inner().len();
+        let bounds = split_lines(&buffer, start, chunk_size.max(1));
+        *r.inner() = &buffer[start..bounds[1]];
+        let mut chunks = vec![(r, header)];
+        let mut line = 1 + count_lines(&buffer[..bounds[1]]);
+        for window in bounds[1..].windows(2) {
+            let chunk = &buffer[window[0]..window[1]];
+            chunks.push((
+                LineNumberReader::with_line_number(chunk, line),
+                TextHeader::default(),
+            ));
+            line += count_lines(chunk);
+        }
+
+        let results = chunks
+            .into_par_iter()
+            .map(|(mut r, mut header)| {
+                let mut indices = Vec::new();
+                let mut values = Vec::new();
+                read_elements(
+                    &mut r,
+                    &mut header,
+                    &shape,
+                    &dense,
+                    &mut &parser,
+                    usize::MAX,
+                    &mut indices,
+                    &mut values,
+                )?;
+                Ok((header, indices, values))
+            })
+            .collect::<Vec<Result<_, TensorReadError>>>();
+
+        // Report the first error in the file, and merge the metadata in the order of the file.
+        let mut header = TextHeader::default();
+        let mut indices = Vec::new();
+        let mut values = Vec::new();
+        for result in results {
+            let (chunk_header, chunk_indices, chunk_values) = result?;
+            if chunk_header.name.is_some() {
+                header.name = chunk_header.name;
+            }
+            header.labels.extend(chunk_header.labels);
+            // Dense axes declared after the first element are rejected by `apply_header`.
+            if chunk_header.dense.is_some() {
+                header.dense = chunk_header.dense;
+            }
+            indices.push(chunk_indices);
+            values.push(chunk_values);
+        }
+        apply_header(
+            from_elements(&shape, &dense, indices.concat(), values.concat()),
+            header,
+        )
+    }
+}
+
+/// Split `buffer[start..]` into chunks at line boundaries.
+///
+/// Returns the start of each chunk, followed by the end of the buffer.
+fn split_lines(buffer: &[u8], start: usize, chunk_size: usize) -> Vec<usize> {
+    let mut bounds = vec![start];
+    let mut pos = start;
+    while pos < buffer.len() {
+        let target = (pos + chunk_size).min(buffer.len());
+        pos = buffer[target..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(buffer.len(), |offset| target + offset + 1);
+        bounds.push(pos);
+    }
+    if bounds.len() == 1 {
+        bounds.push(buffer.len());
+    }
+    bounds
+}
+
+fn count_lines(buffer: &[u8]) -> u64 {
+    buffer.iter().filter(|&&b| b == b'\n').count() as u64
+}
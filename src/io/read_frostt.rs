@@ -0,0 +1,169 @@
+//! Read a tensor from a FROSTT text file.
+//!
+//! The real documentation is at [`pattie::structs::tensor::COOTensor`].
+//!
+//! [`pattie::structs::tensor::COOTensor`]: ../../structs/tensor/struct.COOTensor.html#method.read_from_frostt
+
+use super::compression::decompress;
+use super::lineno_reader::LineNumberReader;
+use super::read_coo::{
+    apply_header, from_elements, read_elements, read_until_token, TensorReadError, Token, TokenMask,
+};
+use super::text_header::TextHeader;
+use crate::structs::axis::{Axes, AxisBuilder};
+use crate::structs::tensor;
+use crate::structs::vec::{smallvec, SmallVec};
+use crate::traits;
+use num::Bounded;
+use std::io;
+use std::str::FromStr;
+
+impl<IT, VT> tensor::COOTensor<IT, VT>
+where
+    IT: traits::IdxType,
+    VT: traits::ValType,
+{
+    /// Read a tensor from a text file in the FROSTT format.
+    ///
+    /// # Arguments
+    ///
+    /// * `r` - A `std::io::Read` object. Examples are `std::fs::File`, `std::io::stdin()`, `Vec<u8>`. Gzip, Zstandard or XZ compressed input is detected, see [`crate::io::Compression`].
+    /// * `index_base` - The index of the first element on each axis, which is 1 for FROSTT datasets.
+    ///
+    /// # Example input
+    ///
+    /// ```text
+    /// 1 1 1 1.000000e+00
+    /// 1 1 2 2.000000e+00
+    /// 1 2 1 3.000000e+00
+    /// 3 2 2 8.000000e+00
+    /// ```
+    ///
+    /// There is no header.
+    /// Each line is an element of the tensor, and the number of axes is inferred from the first line.
+    /// `index_base` is subtracted from each index, so that each axis starts from 0,
+    /// and ends after the largest index found on that axis.
+    /// An index equal to `IT::max_value()` is out of bound, because the end of its axis would overflow.
+    ///
+    /// Comments are handled in the same way as [`tensor::COOTensor::read_from_text`].
+    #[inline]
+    pub fn read_from_frostt<R>(
+        r: &mut R,
+        index_base: IT,
+    ) -> Result<tensor::COOTensor<IT, VT>, TensorReadError>
+    where
+        R: io::Read,
+        IT: FromStr + Bounded,
+        VT: FromStr,
+    {
+        Self::read_from_frostt_with_parser(r, index_base, |value| value.parse::<VT>().ok())
+    }
+
+    /// Similar to [`tensor::COOTensor::read_from_frostt`], but with a custom parser for values.
+    pub fn read_from_frostt_with_parser<R, P>(
+        r: &mut R,
+        index_base: IT,
+        parser: P,
+    ) -> Result<tensor::COOTensor<IT, VT>, TensorReadError>
+    where
+        R: io::Read,
+        P: FnMut(&str) -> Option<VT>,
+        IT: FromStr + Bounded,
+    {
+        let mut parser = parser;
+        let r = decompress(r).map_err(|source| TensorReadError::IOError {
+            line: 1,
+            column: 1,
+            source,
+        })?;
+        let mut r = LineNumberReader::new(io::BufReader::new(r));
+        let mut header = TextHeader::default();
+
+        // Keep the largest index below `IT::max_value()`, so its exclusive upper bound never overflows.
+        let max_index = if index_base < IT::zero() {
+            IT::max_value() + index_base
+        } else {
+            IT::max_value()
+        };
+        let index_range = AxisBuilder::new().range(index_base..max_index).build();
+
+        // First line: all tokens until the end of line
+        let mut first_line = Vec::new();
+        loop {
+            let (line, column) = r.line_column();
+            let is_first = first_line.is_empty();
+            let token = read_until_token(
+                &mut r,
+                &mut header,
+                TokenMask {
+                    eof: !is_first,
+                    new_line: !is_first,
+                    comment: !is_first,
+                    value: true,
+                },
+                TokenMask {
+                    eof: false,
+                    new_line: is_first,
+                    comment: is_first,
+                    value: false,
+                },
+            )?;
+            match token {
+                Token::Value(value) => first_line.push((line, column, value)),
+                // Reading again after EOF yields EOF again, which ends the loop below.
+                _ => break,
+            }
+        }
+
+        // The last value of the first line is the value, the others are the index.
+        let ndim = first_line.len() - 1;
+        let mut indices = Vec::new();
+        let mut values = Vec::new();
+        let (line, column, value) = first_line.pop().unwrap();
+        for (line, column, value) in first_line {
+            let idx = value
+                .parse::<IT>()
+                .map_err(|_| TensorReadError::ValueError {
+                    line,
+                    column,
+                    value: value.into(),
+                })?;
+            if !index_range.range().contains(&idx) {
+                return Err(TensorReadError::IndexOutOfBoundError { line, column });
+            }
+            indices.push(idx);
+        }
+        values.push(parser(&value).ok_or_else(|| TensorReadError::ValueError {
+            line,
+            column,
+            value: value.into(),
+        })?);
+
+        // The other lines have the same layout as a fully sparse text file.
+        let shape = vec![index_range; ndim];
+        read_elements(
+            &mut r,
+            &mut header,
+            &shape,
+            &[],
+            &mut parser,
+            usize::MAX,
+            &mut indices,
+            &mut values,
+        )?;
+
+        // Shift each axis to start from 0, and end after the largest index.
+        let mut upper_bound: SmallVec<_> = smallvec![IT::zero(); ndim];
+        for (i, idx) in indices.iter_mut().enumerate() {
+            *idx = *idx - index_base;
+            upper_bound[i % ndim] = upper_bound[i % ndim].max(*idx + IT::one());
+        }
+        let shape = upper_bound
+            .into_iter()
+            .map(|upper| AxisBuilder::new().range(IT::zero()..upper).build())
+            .collect::<Axes<_>>();
+        let tensor = from_elements(&shape, &[], indices, values);
+
+        apply_header(tensor, header)
+    }
+}
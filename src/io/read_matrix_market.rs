@@ -0,0 +1,393 @@
+//! Read a matrix from a Matrix Market file.
+//!
+//! The real documentation is at [`pattie::structs::tensor::COOTensor`].
+//!
+//! [`pattie::structs::tensor::COOTensor`]: ../../structs/tensor/struct.COOTensor.html#method.read_from_matrix_market
+
+use super::compression::decompress;
+use super::matrix_market::{MatrixMarketField, MatrixMarketSymmetry, BANNER};
+use super::read_coo::TensorReadError;
+use crate::structs::axis::{Axes, AxisBuilder};
+use crate::structs::tensor::{self, COOTensor};
+use crate::traits::{IdxType, MatrixMarketScalar, RawParts};
+use ndarray::{Array2, ArrayD, IxDyn};
+use num::NumCast;
+use std::io;
+use std::str::SplitAsciiWhitespace;
+
+impl<IT, VT> tensor::COOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: MatrixMarketScalar,
+{
+    /// Read a matrix from a Matrix Market file.
+    ///
+    /// # Arguments
+    ///
+    /// * `r` - A `std::io::Read` object. Examples are `std::fs::File`, `std::io::stdin()`, `Vec<u8>`. Gzip, Zstandard or XZ compressed input is detected, see [`crate::io::Compression`].
+    ///
+    /// # Example input
+    ///
+    /// ```text
+    /// %%MatrixMarket matrix coordinate real symmetric
+    /// % A comment
+    /// 3 3 4
+    /// 1 1 1.0
+    /// 2 1 2.0
+    /// 3 2 3.0
+    /// 3 3 4.0
+    /// ```
+    ///
+    /// A file in the `coordinate` layout becomes a sparse matrix, unsorted and possibly with duplicate indices.
+    /// A file in the `array` layout becomes a dense matrix.
+    /// Each axis starts from 0, so the entry at row 1 and column 1 in the file is at `[0, 0]`.
+    ///
+    /// Entries omitted by a `symmetric`, `skew-symmetric` or `hermitian` file are filled in.
+    /// Values are parsed with [`MatrixMarketScalar::parse_matrix_market`],
+    /// so a `pattern` file can be read into any type, and a `real` file can be read into a complex type.
+    pub fn read_from_matrix_market<R>(
+        r: &mut R,
+    ) -> Result<tensor::COOTensor<IT, VT>, TensorReadError>
+    where
+        R: io::Read,
+    {
+        let r = decompress(r).map_err(|source| TensorReadError::IOError {
+            line: 1,
+            column: 1,
+            source,
+        })?;
+        let mut r = LineReader {
+            inner: io::BufReader::new(r),
+            line: 0,
+            buf: String::new(),
+        };
+
+        // Banner
+        let (layout, field, symmetry) = {
+            let line = r.line + 1;
+            let banner = r.read_line()?.unwrap_or_default();
+            let mut tokens = tokenize(banner);
+            let mut next_header_token = |what: &str| {
+                tokens.next().ok_or_else(|| TensorReadError::HeaderError {
+                    line,
+                    column: banner.len() as u64 + 1,
+                    message: format!("missing {}", what),
+                })
+            };
+            let (column, token) = next_header_token("banner")?;
+            if token != BANNER {
+                return Err(TensorReadError::HeaderError {
+                    line,
+                    column,
+                    message: format!("expect {}", BANNER),
+                });
+            }
+            let (column, token) = next_header_token("object")?;
+            if !token.eq_ignore_ascii_case("matrix") {
+                return Err(TensorReadError::HeaderError {
+                    line,
+                    column,
+                    message: format!("unsupported object {:?}", token),
+                });
+            }
+            let (column, token) = next_header_token("format")?;
+            let layout = if token.eq_ignore_ascii_case("coordinate") {
+                Layout::Coordinate
+            } else if token.eq_ignore_ascii_case("array") {
+                Layout::Array
+            } else {
+                return Err(TensorReadError::HeaderError {
+                    line,
+                    column,
+                    message: format!("unsupported format {:?}", token),
+                });
+            };
+            let (column, token) = next_header_token("field")?;
+            let field =
+                MatrixMarketField::parse(token).ok_or_else(|| TensorReadError::HeaderError {
+                    line,
+                    column,
+                    message: format!("unsupported field {:?}", token),
+                })?;
+            let (column, token) = next_header_token("symmetry")?;
+            let symmetry =
+                MatrixMarketSymmetry::parse(token).ok_or_else(|| TensorReadError::HeaderError {
+                    line,
+                    column,
+                    message: format!("unsupported symmetry {:?}", token),
+                })?;
+            if layout == Layout::Array && field == MatrixMarketField::Pattern {
+                return Err(TensorReadError::HeaderError {
+                    line,
+                    column,
+                    message: "array format cannot be pattern".into(),
+                });
+            }
+            (layout, field, symmetry)
+        };
+
+        // Size
+        let (nrows, ncols, nnz) = {
+            let mut entry = r.read_entry("matrix size")?;
+            let nrows = entry.parse_size()?;
+            let ncols = entry.parse_size()?;
+            let nnz = match layout {
+                Layout::Coordinate => entry.parse_size()?,
+                Layout::Array => 0,
+            };
+            entry.finish()?;
+            if symmetry != MatrixMarketSymmetry::General && nrows != ncols {
+                return Err(TensorReadError::HeaderError {
+                    line: entry.line,
+                    column: 1,
+                    message: format!("{} matrix must be square", symmetry),
+                });
+            }
+            (nrows, ncols, nnz)
+        };
+        let shape = [nrows, ncols]
+            .into_iter()
+            .map(|len| {
+                let upper =
+                    <IT as NumCast>::from(len).ok_or_else(|| TensorReadError::HeaderError {
+                        line: r.line,
+                        column: 1,
+                        message: format!("size {} is too large", len),
+                    })?;
+                Ok(AxisBuilder::new().range(IT::zero()..upper).build())
+            })
+            .collect::<Result<Axes<_>, _>>()?;
+
+        let result = match layout {
+            Layout::Coordinate => {
+                let mut indices = Vec::new();
+                let mut values = Vec::new();
+                for _ in 0..nnz {
+                    let mut entry = r.read_entry("entry")?;
+                    let row = entry.parse_index(nrows)?;
+                    let col = entry.parse_index(ncols)?;
+                    let value = entry.parse_value::<VT>(field)?;
+                    entry.finish()?;
+                    if row != col && symmetry != MatrixMarketSymmetry::General {
+                        indices.extend([row, col, col, row]);
+                        let mirrored = symmetry.mirror(&value);
+                        values.push(value);
+                        values.push(mirrored);
+                    } else {
+                        indices.extend([row, col]);
+                        values.push(value);
+                    }
+                }
+
+                let is_axis_dense = [false, false];
+                let mut tensor = COOTensor::zeros(&shape, &is_axis_dense);
+                let num_blocks = values.len();
+                let indices = indices
+                    .into_iter()
+                    .map(|idx| <IT as NumCast>::from(idx).unwrap())
+                    .collect();
+                // # Safety
+                // Each pair in `indices` is checked against the size of the matrix.
+                let raw_parts = unsafe { tensor.raw_parts_mut() };
+                raw_parts.indices = Array2::from_shape_vec((num_blocks, 2), indices).unwrap();
+                raw_parts.values = ArrayD::from_shape_vec(IxDyn(&[num_blocks]), values).unwrap();
+                raw_parts.sparse_is_sorted = false;
+                raw_parts.sparse_is_coalesced = false;
+                tensor
+            }
+            Layout::Array => {
+                let mut values = Array2::zeros((nrows, ncols));
+                for col in 0..ncols {
+                    for row in symmetry.first_row(col)..nrows {
+                        let mut entry = r.read_entry("entry")?;
+                        let value = entry.parse_value::<VT>(field)?;
+                        entry.finish()?;
+                        if row != col && symmetry != MatrixMarketSymmetry::General {
+                            values[[col, row]] = symmetry.mirror(&value);
+                        }
+                        values[[row, col]] = value;
+                    }
+                }
+                COOTensor::from_ndarray(values)
+            }
+        };
+
+        if r.next_data_line()? {
+            let (column, found) = tokenize(r.text()).next().unwrap();
+            return Err(TensorReadError::TokenizeError {
+                line: r.line,
+                column,
+                expect: "end of file".into(),
+                found: found.to_owned().into(),
+            });
+        }
+
+        Ok(result)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Layout {
+    Coordinate,
+    Array,
+}
+
+/// A line-based reader that keeps track of the line number.
+struct LineReader<R>
+where
+    R: io::BufRead,
+{
+    inner: R,
+    line: u64,
+    buf: String,
+}
+
+impl<R> LineReader<R>
+where
+    R: io::BufRead,
+{
+    /// Read the next line without the line break, or `None` at EOF.
+    fn read_line(&mut self) -> Result<Option<&str>, TensorReadError> {
+        self.buf.clear();
+        let bytes_read =
+            self.inner
+                .read_line(&mut self.buf)
+                .map_err(|source| TensorReadError::IOError {
+                    line: self.line + 1,
+                    column: 1,
+                    source,
+                })?;
+        if bytes_read == 0 {
+            return Ok(None);
+        }
+        self.line += 1;
+        Ok(Some(self.text()))
+    }
+
+    /// Skip to the next line that is neither empty nor a comment, returns `false` at EOF.
+    fn next_data_line(&mut self) -> Result<bool, TensorReadError> {
+        while let Some(line) = self.read_line()? {
+            if !line.trim_start().is_empty() && !line.starts_with('%') {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// The current line without the line break.
+    fn text(&self) -> &str {
+        self.buf.trim_end_matches(['\n', '\r'])
+    }
+
+    /// Read the next line that is neither empty nor a comment, failing at EOF.
+    fn read_entry(&mut self, expect: &str) -> Result<Entry<'_>, TensorReadError> {
+        if !self.next_data_line()? {
+            return Err(TensorReadError::TokenizeError {
+                line: self.line + 1,
+                column: 1,
+                expect: expect.into(),
+                found: "end of file".into(),
+            });
+        }
+        let text = self.text();
+        Ok(Entry {
+            line: self.line,
+            text,
+            tokens: text.split_ascii_whitespace(),
+        })
+    }
+}
+
+/// Split a line into tokens, each with its column number.
+fn tokenize(line: &str) -> impl Iterator<Item = (u64, &str)> {
+    line.split_ascii_whitespace()
+        .map(move |token| (column_of(line, token), token))
+}
+
+/// The column number of `token`, which is a substring of `line`.
+#[inline]
+fn column_of(line: &str, token: &str) -> u64 {
+    (token.as_ptr() as usize - line.as_ptr() as usize + 1) as u64
+}
+
+/// Tokens of a data line.
+struct Entry<'a> {
+    line: u64,
+    text: &'a str,
+    tokens: SplitAsciiWhitespace<'a>,
+}
+
+impl<'a> Entry<'a> {
+    fn next_token(&mut self, expect: &str) -> Result<(u64, &'a str), TensorReadError> {
+        match self.tokens.next() {
+            Some(token) => Ok((column_of(self.text, token), token)),
+            None => Err(TensorReadError::TokenizeError {
+                line: self.line,
+                column: self.text.len() as u64 + 1,
+                expect: expect.into(),
+                found: "end of line".into(),
+            }),
+        }
+    }
+
+    fn parse_size(&mut self) -> Result<usize, TensorReadError> {
+        let (column, token) = self.next_token("size")?;
+        token.parse().map_err(|_| TensorReadError::ValueError {
+            line: self.line,
+            column,
+            value: token.to_owned().into(),
+        })
+    }
+
+    /// Parse a 1-based index, and convert it into 0-based.
+    fn parse_index(&mut self, len: usize) -> Result<usize, TensorReadError> {
+        let (column, token) = self.next_token("index")?;
+        let idx = token
+            .parse::<usize>()
+            .map_err(|_| TensorReadError::ValueError {
+                line: self.line,
+                column,
+                value: token.to_owned().into(),
+            })?;
+        if idx == 0 || idx > len {
+            return Err(TensorReadError::IndexOutOfBoundError {
+                line: self.line,
+                column,
+            });
+        }
+        Ok(idx - 1)
+    }
+
+    fn parse_value<VT>(&mut self, field: MatrixMarketField) -> Result<VT, TensorReadError>
+    where
+        VT: MatrixMarketScalar,
+    {
+        let mut tokens = Vec::with_capacity(field.num_tokens());
+        let mut column = self.text.len() as u64 + 1;
+        for i in 0..field.num_tokens() {
+            let (token_column, token) = self.next_token("value")?;
+            if i == 0 {
+                column = token_column;
+            }
+            tokens.push(token);
+        }
+        VT::parse_matrix_market(field, &tokens).ok_or_else(|| TensorReadError::ValueError {
+            line: self.line,
+            column,
+            value: tokens.join(" ").into(),
+        })
+    }
+
+    /// Check that there are no more tokens.
+    fn finish(&mut self) -> Result<(), TensorReadError> {
+        match self.tokens.next() {
+            Some(token) => Err(TensorReadError::TokenizeError {
+                line: self.line,
+                column: column_of(self.text, token),
+                expect: "end of line".into(),
+                found: token.to_owned().into(),
+            }),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,227 @@
+//! Read a tensor from a NumPy `.npy` or `.npz` file.
+//!
+//! The real documentation is at [`pattie::structs::tensor::COOTensor`].
+//!
+//! [`pattie::structs::tensor::COOTensor`]: ../../structs/tensor/struct.COOTensor.html#method.read_from_npz
+
+use super::compression::decompress;
+use crate::structs::axis::{Axes, AxisBuilder};
+use crate::structs::tensor::{self, COOTensor};
+use crate::traits::{IdxType, RawParts, ValType};
+use ndarray::{Array1, Array2, ArrayD, Ix1, Ix2, IxDyn, ShapeBuilder};
+use npyz::npz::NpzArchive;
+use npyz::{Deserialize, NpyFile, Order};
+use std::borrow::Cow;
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum NumpyReadError {
+    #[error("{0}")]
+    IOError(#[from] io::Error),
+    #[error("missing array {0:?}")]
+    MissingArrayError(&'static str),
+    #[error("array {array:?}: {source}")]
+    TypeError {
+        array: &'static str,
+        #[source]
+        source: npyz::DTypeError,
+    },
+    #[error("array {array:?}: {message}")]
+    ShapeError {
+        array: &'static str,
+        message: Cow<'static, str>,
+    },
+    #[error("element {element}: index out of bound")]
+    IndexOutOfBoundError { element: usize },
+}
+
+impl<IT, VT> tensor::COOTensor<IT, VT>
+where
+    IT: IdxType + Deserialize,
+    VT: ValType + Deserialize,
+{
+    /// Read a tensor from a NumPy `.npz` archive written by [`tensor::COOTensor::write_to_npz`].
+    ///
+    /// # Arguments
+    ///
+    /// * `r` - A `std::io::Read` and `std::io::Seek` object. Examples are `std::fs::File`, `std::io::Cursor<Vec<u8>>`.
+    ///
+    /// The archive contains these arrays:
+    ///
+    /// * `indices` - Shape `(ndim, nnz)`, the index of each element relative to the lower bound of each axis.
+    /// * `values` - Shape `(nnz,)`, the value of each element.
+    /// * `shape_upper` - Shape `(ndim,)`, the upper bound of each axis.
+    /// * `shape_lower` - Shape `(ndim,)`, the lower bound of each axis. Optional, defaults to zeros.
+    /// * `labels` - Shape `(ndim,)`, the label of each axis, an empty string means no label. Optional.
+    ///
+    /// The result is fully sparse, unsorted and possibly with duplicate indices.
+    /// The element type of each array must match `IT` and `VT`.
+    pub fn read_from_npz<R>(r: &mut R) -> Result<tensor::COOTensor<IT, VT>, NumpyReadError>
+    where
+        R: io::Read + io::Seek,
+    {
+        let mut archive = NpzArchive::new(r)?;
+
+        let shape_upper = read_vector::<IT, _>(&mut archive, "shape_upper")?
+            .ok_or(NumpyReadError::MissingArrayError("shape_upper"))?;
+        let ndim = shape_upper.len();
+        let shape_lower = read_vector::<IT, _>(&mut archive, "shape_lower")?
+            .unwrap_or_else(|| Array1::zeros(ndim));
+        if shape_lower.len() != ndim {
+            return Err(shape_error(
+                "shape_lower",
+                "length differs from shape_upper",
+            ));
+        }
+        let labels = read_vector::<String, _>(&mut archive, "labels")?;
+        if labels.as_ref().is_some_and(|labels| labels.len() != ndim) {
+            return Err(shape_error("labels", "length differs from shape_upper"));
+        }
+        let shape = (0..ndim)
+            .map(|i| {
+                if shape_lower[i] > shape_upper[i] {
+                    return Err(shape_error(
+                        "shape_lower",
+                        "lower bound is larger than upper bound",
+                    ));
+                }
+                let mut builder = AxisBuilder::new().range(shape_lower[i]..shape_upper[i]);
+                match labels.as_ref().map(|labels| labels[i].as_str()) {
+                    Some("") | None => (),
+                    Some(label) => builder = builder.label(label),
+                }
+                Ok(builder.build())
+            })
+            .collect::<Result<Axes<_>, _>>()?;
+
+        let indices = read_array::<IT, _>(&mut archive, "indices")?
+            .ok_or(NumpyReadError::MissingArrayError("indices"))?
+            .into_dimensionality::<Ix2>()
+            .map_err(|_| shape_error("indices", "expect 2 axes"))?;
+        if indices.nrows() != ndim {
+            return Err(shape_error("indices", "expect one row for each axis"));
+        }
+        let values = read_vector::<VT, _>(&mut archive, "values")?
+            .ok_or(NumpyReadError::MissingArrayError("values"))?;
+        let num_blocks = values.len();
+        if indices.ncols() != num_blocks {
+            return Err(shape_error("indices", "expect one column for each value"));
+        }
+
+        // Convert from `(ndim, nnz)` relative indices into `(nnz, ndim)` logical indices.
+        let mut logical_indices = Array2::zeros((num_blocks, ndim));
+        for (element, (index, mut logical_index)) in indices
+            .columns()
+            .into_iter()
+            .zip(logical_indices.rows_mut())
+            .enumerate()
+        {
+            for ((&idx, logical_idx), axis) in
+                index.iter().zip(logical_index.iter_mut()).zip(shape.iter())
+            {
+                if idx < IT::zero() || idx >= axis.upper() - axis.lower() {
+                    return Err(NumpyReadError::IndexOutOfBoundError { element });
+                }
+                *logical_idx = idx + axis.lower();
+            }
+        }
+
+        let is_axis_dense = vec![false; ndim];
+        let mut tensor = COOTensor::zeros(&shape, &is_axis_dense);
+        // # Safety
+        // Each index is checked against the shape.
+        let raw_parts = unsafe { tensor.raw_parts_mut() };
+        raw_parts.indices = logical_indices;
+        raw_parts.values = values.into_dyn();
+        raw_parts.sparse_is_sorted = false;
+        raw_parts.sparse_is_coalesced = false;
+        Ok(tensor)
+    }
+
+    /// Read a dense tensor from a NumPy `.npy` file.
+    ///
+    /// # Arguments
+    ///
+    /// * `r` - A `std::io::Read` object. Examples are `std::fs::File`, `std::io::stdin()`, `&[u8]`. Gzip, Zstandard or XZ compressed input is detected, see [`crate::io::Compression`].
+    ///
+    /// The result is the same as [`tensor::COOTensor::from_ndarray`], each axis starts from 0.
+    /// Both C order and Fortran order are supported.
+    pub fn read_from_npy<R>(r: &mut R) -> Result<tensor::COOTensor<IT, VT>, NumpyReadError>
+    where
+        R: io::Read,
+    {
+        let file = NpyFile::new(io::BufReader::new(decompress(r)?))?;
+        Ok(COOTensor::from_ndarray(into_ndarray::<VT, _>(
+            file, "array",
+        )?))
+    }
+}
+
+fn shape_error(array: &'static str, message: &'static str) -> NumpyReadError {
+    NumpyReadError::ShapeError {
+        array,
+        message: message.into(),
+    }
+}
+
+/// Read an array in standard layout, or `None` if it does not exist.
+fn read_array<T, R>(
+    archive: &mut NpzArchive<R>,
+    array: &'static str,
+) -> Result<Option<ArrayD<T>>, NumpyReadError>
+where
+    T: Deserialize + Clone,
+    R: io::Read + io::Seek,
+{
+    archive
+        .by_name(array)?
+        .map(|file| into_ndarray(file, array))
+        .transpose()
+}
+
+/// Read a 1-D array, or `None` if it does not exist.
+fn read_vector<T, R>(
+    archive: &mut NpzArchive<R>,
+    array: &'static str,
+) -> Result<Option<Array1<T>>, NumpyReadError>
+where
+    T: Deserialize + Clone,
+    R: io::Read + io::Seek,
+{
+    read_array(archive, array)?
+        .map(|result| {
+            result
+                .into_dimensionality::<Ix1>()
+                .map_err(|_| shape_error(array, "expect 1 axis"))
+        })
+        .transpose()
+}
+
+fn into_ndarray<T, R>(file: NpyFile<R>, array: &'static str) -> Result<ArrayD<T>, NumpyReadError>
+where
+    T: Deserialize + Clone,
+    R: io::Read,
+{
+    let shape = file
+        .shape()
+        .iter()
+        .map(|&len| usize::try_from(len))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| shape_error(array, "too large"))?;
+    let order = file.order();
+    let values = file
+        .data::<T>()
+        .map_err(|source| NumpyReadError::TypeError { array, source })?
+        .collect::<io::Result<Vec<_>>>()?;
+    let result = match order {
+        Order::C => ArrayD::from_shape_vec(IxDyn(&shape), values),
+        Order::Fortran => ArrayD::from_shape_vec(IxDyn(&shape).f(), values),
+    }
+    .map_err(|_| shape_error(array, "length differs from shape"))?;
+    Ok(if result.is_standard_layout() {
+        result
+    } else {
+        result.as_standard_layout().into_owned()
+    })
+}
@@ -0,0 +1,181 @@
+//! Metadata stored in the comments of a text file.
+//!
+//! Comments starting with `#@` are metadata, other comments are ignored:
+//!
+//! ```text
+//! #@ name: <name of the tensor>
+//! #@ axis <i>: <label of the i-th axis>
+//! #@ dense: <i> <j> ...
+//! ```
+//!
+//! `dense` lists the dense axes, in the order of the dimensions of each dense block.
+//!
+//! Backslashes and line breaks inside a value are escaped as `\\`, `\n` and `\r`.
+//! Unknown keys are ignored, so that newer files can still be read.
+//!
+//! Older versions wrote the metadata as plain comments instead:
+//!
+//! ```text
+//! # <name of the tensor>
+//! # Axis <i>: <label of the i-th axis>
+//! # Axis <i> has no label
+//! ```
+//!
+//! Axis labels in this form are still read, without unescaping.
+//! The name is not, because it can not be told apart from other comments.
+
+use crate::structs::axis::Axis;
+use crate::traits::IdxType;
+use std::io;
+
+/// Prefix of a metadata comment.
+const HEADER_PREFIX: &str = "#@ ";
+/// Prefix of an axis label comment written by older versions.
+const LEGACY_AXIS_PREFIX: &str = "# Axis ";
+
+#[derive(Debug, Default)]
+pub(super) struct TextHeader {
+    pub name: Option<String>,
+    /// Line, column, axis number, and label of each axis label.
+    pub labels: Vec<(u64, u64, usize, String)>,
+    /// Line, column, and axis numbers of the dense axes.
+    pub dense: Option<(u64, u64, Vec<usize>)>,
+    /// Line and column of the first comment setting any of the above.
+    pub first: Option<(u64, u64)>,
+}
+
+impl TextHeader {
+    /// Parse a comment line, starting with `#` and without the line break.
+    ///
+    /// Returns `Err` with a message if the comment is metadata but malformed.
+    pub fn parse_comment(&mut self, line: u64, column: u64, comment: &[u8]) -> Result<(), String> {
+        let comment = match std::str::from_utf8(comment) {
+            Ok(comment) => comment,
+            // Not our business, plain comments may be in any encoding.
+            Err(_) => return Ok(()),
+        };
+        let comment = match comment.strip_prefix(HEADER_PREFIX) {
+            Some(comment) => comment,
+            None => {
+                self.parse_legacy_comment(line, column, comment);
+                return Ok(());
+            }
+        };
+        let (key, value) = comment
+            .split_once(": ")
+            .ok_or_else(|| format!("expect \"key: value\", but found {:?}", comment))?;
+        let value = unescape(value)?;
+        let mut key_iter = key.split(' ');
+        match (key_iter.next(), key_iter.next(), key_iter.next()) {
+            (Some("name"), None, _) => {
+                self.first.get_or_insert((line, column));
+                self.name = Some(value);
+            }
+            (Some("axis"), Some(axis), None) => {
+                let axis = axis
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid axis number {:?}", axis))?;
+                self.first.get_or_insert((line, column));
+                self.labels.push((line, column, axis, value));
+            }
+            (Some("dense"), None, _) => {
+                let axes = value
+                    .split_ascii_whitespace()
+                    .map(|axis| {
+                        axis.parse::<usize>()
+                            .map_err(|_| format!("invalid axis number {:?}", axis))
+                    })
+                    .collect::<Result<_, _>>()?;
+                self.first.get_or_insert((line, column));
+                self.dense = Some((line, column, axes));
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    /// Parse an axis label written by older versions, other comments are ignored.
+    fn parse_legacy_comment(&mut self, line: u64, column: u64, comment: &str) {
+        let parsed = comment
+            .strip_prefix(LEGACY_AXIS_PREFIX)
+            .and_then(|comment| comment.split_once(": "))
+            .and_then(|(axis, label)| Some((axis.parse::<usize>().ok()?, label)));
+        if let Some((axis, label)) = parsed {
+            self.first.get_or_insert((line, column));
+            self.labels.push((line, column, axis, label.to_owned()));
+        }
+    }
+}
+
+/// Escape a value so that it fits in one line.
+fn escape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+fn unescape(value: &str) -> Result<String, String> {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => result.push('\\'),
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some(c) => return Err(format!("unknown escape sequence \"\\{}\"", c)),
+            None => return Err("unterminated escape sequence".to_owned()),
+        }
+    }
+    Ok(result)
+}
+
+/// Write the tensor name as a metadata comment.
+pub(super) fn write_name<W>(w: &mut W, name: Option<&str>) -> io::Result<()>
+where
+    W: io::Write,
+{
+    if let Some(name) = name {
+        writeln!(w, "{}name: {}", HEADER_PREFIX, escape(name))?;
+    }
+    Ok(())
+}
+
+/// Write the dense axes as a metadata comment, if there is any.
+pub(super) fn write_dense<W>(w: &mut W, dense: &[usize]) -> io::Result<()>
+where
+    W: io::Write,
+{
+    if dense.is_empty() {
+        return Ok(());
+    }
+    write!(w, "{}dense:", HEADER_PREFIX)?;
+    for axis in dense {
+        write!(w, " {}", axis)?;
+    }
+    writeln!(w)
+}
+
+/// Write the axis labels as metadata comments.
+pub(super) fn write_labels<W, IT>(w: &mut W, shape: &[Axis<IT>]) -> io::Result<()>
+where
+    W: io::Write,
+    IT: IdxType,
+{
+    for (i, axis) in shape.iter().enumerate() {
+        if let Some(label) = axis.label() {
+            writeln!(w, "{}axis {}: {}", HEADER_PREFIX, i, escape(label))?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,147 @@
+//! Write a tensor to a binary file.
+//!
+//! The real documentation is at [`pattie::structs::tensor::COOTensor`].
+//!
+//! [`pattie::structs::tensor::COOTensor`]: ../../structs/tensor/struct.COOTensor.html#method.write_to_binary
+
+use super::binary_format::{align_offset, FLAG_COALESCED, FLAG_SORTED, MAGIC, NO_STRING, VERSION};
+use crate::structs::axis::{map_axes_unwrap, Axis};
+use crate::structs::tensor;
+use crate::traits::{BinaryScalar, IdxType, RawParts, Tensor, ValType};
+use std::io;
+
+/// Number of scalars converted at once.
+const CHUNK_SIZE: usize = 4096;
+
+impl<IT, VT> tensor::COOTensor<IT, VT>
+where
+    IT: IdxType + BinaryScalar,
+    VT: ValType + BinaryScalar,
+{
+    /// Write the tensor to a binary file.
+    ///
+    /// # Arguments
+    ///
+    /// * `w` - A [`std::io::Write`] object. Examples are [`std::fs::File`], [`std::io::Stdout`], [`Vec<u8>`].
+    ///
+    /// The file keeps everything of the tensor:
+    /// the name, the range and label of each axis, which axes are dense, the sort order, and whether it is coalesced.
+    /// Dense axes are stored as they are, without expanding each element into its own index.
+    ///
+    /// The file is read back with [`tensor::COOTensor::read_from_binary`] using the same `IT` and `VT`.
+    pub fn write_to_binary<W>(&self, w: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        use std::io::Write;
+
+        let raw_parts = self.raw_parts();
+        let shape = self.shape();
+        let num_blocks = self.num_blocks();
+
+        let mut flags = 0;
+        if self.sparse_sort_order().is_some() {
+            flags |= FLAG_SORTED;
+        }
+        if self.is_coalesced() {
+            flags |= FLAG_COALESCED;
+        }
+
+        // Build the header first, so that the array offsets are known.
+        let mut header = Vec::new();
+        header.extend_from_slice(&MAGIC);
+        header.extend_from_slice(&VERSION.to_le_bytes());
+        header.extend_from_slice(&[IT::TYPE_TAG, VT::TYPE_TAG, flags, 0]);
+        header.extend_from_slice(&to_u32(shape.len())?.to_le_bytes());
+        header.extend_from_slice(&to_u32(self.sparse_axes().len())?.to_le_bytes());
+        header.extend_from_slice(&(num_blocks as u64).to_le_bytes());
+        let offsets_position = header.len();
+        header.extend_from_slice(&[0; 16]);
+        push_string(&mut header, self.name())?;
+        for axis in shape.iter() {
+            push_scalar(&mut header, axis.lower());
+            push_scalar(&mut header, axis.upper());
+            push_string(&mut header, axis.label())?;
+        }
+        push_positions(&mut header, self.sparse_axes(), shape)?;
+        push_positions(&mut header, self.dense_axes(), shape)?;
+        let sort_order = self.sparse_sort_order().unwrap_or(&[]);
+        header.extend_from_slice(&to_u32(sort_order.len())?.to_le_bytes());
+        push_positions(&mut header, sort_order, shape)?;
+
+        let indices_offset = align_offset(header.len() as u64);
+        let indices_end = indices_offset + (raw_parts.indices.len() * IT::SIZE) as u64;
+        let values_offset = align_offset(indices_end);
+        header[offsets_position..offsets_position + 8]
+            .copy_from_slice(&indices_offset.to_le_bytes());
+        header[offsets_position + 8..offsets_position + 16]
+            .copy_from_slice(&values_offset.to_le_bytes());
+
+        let mut w = io::BufWriter::new(w);
+        w.write_all(&header)?;
+        write_padding(&mut w, indices_offset - header.len() as u64)?;
+        write_scalars(&mut w, raw_parts.indices.iter().copied())?;
+        write_padding(&mut w, values_offset - indices_end)?;
+        write_scalars(&mut w, raw_parts.values.iter().copied())?;
+        w.flush()
+    }
+}
+
+fn to_u32(value: usize) -> io::Result<u32> {
+    u32::try_from(value).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "too large"))
+}
+
+fn push_scalar<T: BinaryScalar>(buf: &mut Vec<u8>, value: T) {
+    let start = buf.len();
+    buf.resize(start + T::SIZE, 0);
+    value.write_le(&mut buf[start..]);
+}
+
+fn push_string(buf: &mut Vec<u8>, value: Option<&str>) -> io::Result<()> {
+    match value {
+        Some(value) => {
+            let len = to_u32(value.len())?;
+            if len == NO_STRING {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, "too large"));
+            }
+            buf.extend_from_slice(&len.to_le_bytes());
+            buf.extend_from_slice(value.as_bytes());
+        }
+        None => buf.extend_from_slice(&NO_STRING.to_le_bytes()),
+    }
+    Ok(())
+}
+
+/// Push the position of each axis of `axes` in `shape`.
+fn push_positions<IT>(buf: &mut Vec<u8>, axes: &[Axis<IT>], shape: &[Axis<IT>]) -> io::Result<()>
+where
+    IT: IdxType,
+{
+    for position in map_axes_unwrap(axes, shape) {
+        buf.extend_from_slice(&to_u32(position)?.to_le_bytes());
+    }
+    Ok(())
+}
+
+fn write_padding<W: io::Write>(w: &mut W, len: u64) -> io::Result<()> {
+    use std::io::Read;
+
+    io::copy(&mut io::repeat(0).take(len), w)?;
+    Ok(())
+}
+
+fn write_scalars<W, T>(w: &mut W, values: impl Iterator<Item = T>) -> io::Result<()>
+where
+    W: io::Write,
+    T: BinaryScalar,
+{
+    let mut buf = Vec::with_capacity(CHUNK_SIZE * T::SIZE);
+    for value in values {
+        push_scalar(&mut buf, value);
+        if buf.len() >= CHUNK_SIZE * T::SIZE {
+            w.write_all(&buf)?;
+            buf.clear();
+        }
+    }
+    w.write_all(&buf)
+}
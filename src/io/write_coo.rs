@@ -4,11 +4,13 @@
 //!
 //! [`pattie::structs::tensor::COOTensor`]: ../../structs/tensor/struct.COOTensor.html#method.write_to_text
 
+use super::text_header::{write_dense, write_labels, write_name};
+use crate::structs::axis::{map_axes_ok, map_axes_unwrap};
 use crate::structs::tensor;
-use crate::traits::{IdxType, Tensor, ValType};
+use crate::structs::vec::SmallVec;
+use crate::traits::{IdxType, RawParts, Tensor, ValType};
 use std::fmt;
 use std::io;
-use streaming_iterator::StreamingIterator;
 
 impl<IT, VT> tensor::COOTensor<IT, VT>
 where
@@ -41,6 +43,13 @@ where
     /// Second line is the lower bound of each axis (inclusive).
     /// Third line is the upper bound of each axis (exclusive).
     /// The following lines are the elements of the tensor.
+    ///
+    /// The tensor name and axis labels are written as `#@ name: ...` and `#@ axis i: ...` comments,
+    /// which [`tensor::COOTensor::read_from_text`] reads back.
+    ///
+    /// If the tensor has dense axes, they are listed in a `#@ dense: i j ...` comment,
+    /// and each following line is a dense block instead of a single element:
+    /// the indices of the sparse axes, then the values of the block in row-major order of the listed axes.
     #[inline]
     pub fn write_to_text<W>(&self, w: &mut W) -> io::Result<()>
     where
@@ -61,24 +70,16 @@ where
         let mut formatter = formatter;
         let mut w = io::BufWriter::new(w);
 
-        if let Some(name) = self.name() {
-            writeln!(w, "# {}", name)?;
-        }
+        write_name(&mut w, self.name())?;
 
         writeln!(w, "{}", self.ndim())?;
         if self.ndim() == 0 {
             return Ok(());
         }
 
-        if self.shape().iter().any(|axis| axis.label().is_some()) {
-            for (i, axis) in self.shape().iter().enumerate() {
-                if let Some(label) = axis.label() {
-                    writeln!(w, "# Axis {}: {}", i, label)?;
-                } else {
-                    writeln!(w, "# Axis {} has no label", i)?;
-                }
-            }
-        }
+        write_labels(&mut w, self.shape())?;
+        let dense = map_axes_unwrap(self.dense_axes(), self.shape()).collect::<SmallVec<_>>();
+        write_dense(&mut w, &dense)?;
 
         let mut shape_iter = self.shape().iter();
         if let Some(axis) = shape_iter.next() {
@@ -98,12 +99,26 @@ where
         }
         writeln!(w)?;
 
-        let mut tensor_iter = self.iter();
-        while let Some(&(index, value)) = tensor_iter.next() {
-            for index in index.iter() {
-                write!(w, "{}\t", index)?;
+        // Sparse indices in the order of the shape, then the dense block.
+        let raw_parts = self.raw_parts();
+        let columns = map_axes_ok(self.shape(), &raw_parts.sparse_axes)
+            .flatten()
+            .collect::<SmallVec<_>>();
+        for (index, block) in raw_parts
+            .indices
+            .outer_iter()
+            .zip(raw_parts.values.outer_iter())
+        {
+            for &column in columns.iter() {
+                write!(w, "{}\t", index[column])?;
+            }
+            let mut block_iter = block.iter();
+            if let Some(value) = block_iter.next() {
+                write!(w, "{}", formatter(value))?;
+            }
+            for value in block_iter {
+                write!(w, "\t{}", formatter(value))?;
             }
-            write!(w, "{}", formatter(value))?;
             writeln!(w)?;
         }
 
@@ -0,0 +1,77 @@
+//! Write a tensor to a FROSTT text file.
+//!
+//! The real documentation is at [`pattie::structs::tensor::COOTensor`].
+//!
+//! [`pattie::structs::tensor::COOTensor`]: ../../structs/tensor/struct.COOTensor.html#method.write_to_frostt
+
+use super::text_header::{write_labels, write_name};
+use crate::structs::tensor;
+use crate::traits::{IdxType, Tensor, ValType};
+use std::fmt;
+use std::io;
+use streaming_iterator::StreamingIterator;
+
+impl<IT, VT> tensor::COOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Write the tensor to a text file in the FROSTT format.
+    ///
+    /// # Arguments
+    ///
+    /// * `w` - A [`std::io::Write`] object. Examples are [`std::fs::File`], [`std::io::Stdout`], [`Vec<u8>`].
+    /// * `index_base` - The index of the first element on each axis, which is 1 for FROSTT datasets.
+    ///
+    /// # Example output
+    ///
+    /// ```text
+    /// 1       1       1       1.000000e+00
+    /// 1       1       2       2.000000e+00
+    /// 1       2       1       3.000000e+00
+    /// 3       2       2       8.000000e+00
+    /// ```
+    ///
+    /// There is no header.
+    /// Each index is written relative to the lower bound of its axis, plus `index_base`.
+    /// The tensor name and axis labels are written as comments, like [`tensor::COOTensor::write_to_text`].
+    #[inline]
+    pub fn write_to_frostt<W>(&self, w: &mut W, index_base: IT) -> io::Result<()>
+    where
+        W: io::Write,
+        VT: fmt::LowerExp,
+    {
+        self.write_to_frostt_with_formatter(w, index_base, |value| format!("{:.6e}", value))
+    }
+
+    /// Similar to [`tensor::COOTensor::write_to_frostt`], but with a custom formatter for values.
+    pub fn write_to_frostt_with_formatter<W, F>(
+        &self,
+        w: &mut W,
+        index_base: IT,
+        formatter: F,
+    ) -> io::Result<()>
+    where
+        W: io::Write,
+        F: FnMut(&VT) -> String,
+    {
+        use std::io::Write;
+
+        let mut formatter = formatter;
+        let mut w = io::BufWriter::new(w);
+
+        write_name(&mut w, self.name())?;
+        write_labels(&mut w, self.shape())?;
+
+        let mut tensor_iter = self.iter();
+        while let Some(&(index, value)) = tensor_iter.next() {
+            for (&index, axis) in index.iter().zip(self.shape().iter()) {
+                write!(w, "{}\t", index - axis.lower() + index_base)?;
+            }
+            write!(w, "{}", formatter(value))?;
+            writeln!(w)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+//! Write a matrix to a Matrix Market file.
+//!
+//! The real documentation is at [`pattie::structs::tensor::COOTensor`].
+//!
+//! [`pattie::structs::tensor::COOTensor`]: ../../structs/tensor/struct.COOTensor.html#method.write_to_matrix_market
+
+use super::matrix_market::{MatrixMarketSymmetry, BANNER};
+use crate::structs::tensor;
+use crate::traits::{IdxType, MatrixMarketScalar, Tensor};
+use ndarray::Array2;
+use std::collections::BTreeMap;
+use std::io;
+use streaming_iterator::StreamingIterator;
+
+impl<IT, VT> tensor::COOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: MatrixMarketScalar,
+{
+    /// Write the matrix to a Matrix Market file.
+    ///
+    /// # Arguments
+    ///
+    /// * `w` - A [`std::io::Write`] object. Examples are [`std::fs::File`], [`std::io::Stdout`], [`Vec<u8>`].
+    /// * `symmetry` - The symmetry written to the file.
+    ///
+    /// # Example output
+    ///
+    /// ```text
+    /// %%MatrixMarket matrix coordinate real symmetric
+    /// 3 3 4
+    /// 1 1 1e0
+    /// 2 1 2e0
+    /// 3 2 3e0
+    /// 3 3 4e0
+    /// ```
+    ///
+    /// A fully dense matrix is written in the `array` layout, otherwise in the `coordinate` layout.
+    /// The first row and the first column of the matrix are written as 1.
+    ///
+    /// With [`MatrixMarketSymmetry::General`], each block is written as it is.
+    /// Otherwise the matrix is folded: duplicate indices are summed up, and only the lower triangle is written.
+    /// If the matrix does not have the requested symmetry, an error of [`std::io::ErrorKind::InvalidInput`] is returned.
+    pub fn write_to_matrix_market<W>(
+        &self,
+        w: &mut W,
+        symmetry: MatrixMarketSymmetry,
+    ) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        use std::io::Write;
+
+        if self.ndim() != 2 {
+            return Err(invalid_input("the tensor must have 2 axes"));
+        }
+        let shape = self.shape();
+        let (nrows, ncols) = (shape[0].len(), shape[1].len());
+        if symmetry != MatrixMarketSymmetry::General && nrows != ncols {
+            return Err(invalid_input("the matrix must be square"));
+        }
+        // Convert the logical index into the 0-based row and column.
+        let position = |index: &[IT]| {
+            (
+                (index[0] - shape[0].lower()).to_usize().unwrap(),
+                (index[1] - shape[1].lower()).to_usize().unwrap(),
+            )
+        };
+
+        let mut w = io::BufWriter::new(w);
+        let is_dense = self.sparse_axes().is_empty();
+        writeln!(
+            w,
+            "{} matrix {} {} {}",
+            BANNER,
+            if is_dense { "array" } else { "coordinate" },
+            VT::FIELD,
+            symmetry,
+        )?;
+
+        if is_dense {
+            let mut values = Array2::zeros((nrows, ncols));
+            let mut tensor_iter = self.iter();
+            while let Some(&(index, value)) = tensor_iter.next() {
+                values[position(index)] = value.clone();
+            }
+            if symmetry != MatrixMarketSymmetry::General
+                && values
+                    .indexed_iter()
+                    .any(|((row, col), value)| symmetry.mirror(value) != values[[col, row]])
+            {
+                return Err(invalid_input(format!("the matrix is not {}", symmetry)));
+            }
+
+            writeln!(w, "{} {}", nrows, ncols)?;
+            for col in 0..ncols {
+                for row in symmetry.first_row(col)..nrows {
+                    writeln!(w, "{}", values[[row, col]].format_matrix_market())?;
+                }
+            }
+        } else if symmetry == MatrixMarketSymmetry::General {
+            writeln!(w, "{} {} {}", nrows, ncols, self.num_non_zeros())?;
+            let mut tensor_iter = self.iter();
+            while let Some(&(index, value)) = tensor_iter.next() {
+                let (row, col) = position(index);
+                writeln!(
+                    w,
+                    "{} {} {}",
+                    row + 1,
+                    col + 1,
+                    value.format_matrix_market()
+                )?;
+            }
+        } else {
+            // Sorted by column first, which is the order of the `array` layout.
+            let mut entries = BTreeMap::new();
+            let mut tensor_iter = self.iter();
+            while let Some(&(index, value)) = tensor_iter.next() {
+                let (row, col) = position(index);
+                let entry = entries.entry((col, row)).or_insert_with(VT::zero);
+                *entry = entry.clone() + value.clone();
+            }
+            let zero = VT::zero();
+            if entries.iter().any(|(&(col, row), value)| {
+                &symmetry.mirror(value) != entries.get(&(row, col)).unwrap_or(&zero)
+            }) {
+                return Err(invalid_input(format!("the matrix is not {}", symmetry)));
+            }
+
+            let is_stored = |&(col, row): &(usize, usize)| row >= symmetry.first_row(col);
+            let nnz = entries.keys().filter(|&key| is_stored(key)).count();
+            writeln!(w, "{} {} {}", nrows, ncols, nnz)?;
+            for (&(col, row), value) in entries.iter() {
+                if is_stored(&(col, row)) {
+                    writeln!(
+                        w,
+                        "{} {} {}",
+                        row + 1,
+                        col + 1,
+                        value.format_matrix_market()
+                    )?;
+                }
+            }
+        }
+
+        w.flush()
+    }
+}
+
+fn invalid_input(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message.into())
+}
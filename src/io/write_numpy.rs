@@ -0,0 +1,158 @@
+//! Write a tensor to a NumPy `.npy` or `.npz` file.
+//!
+//! The real documentation is at [`pattie::structs::tensor::COOTensor`].
+//!
+//! [`pattie::structs::tensor::COOTensor`]: ../../structs/tensor/struct.COOTensor.html#method.write_to_npz
+
+use crate::structs::axis::map_axes_unwrap;
+use crate::structs::tensor;
+use crate::traits::{IdxType, RawParts, Tensor, ValType};
+use npyz::npz::NpzWriter;
+use npyz::{AutoSerialize, DType, TypeStr, WriteOptions, WriterBuilder};
+use std::io;
+use streaming_iterator::StreamingIterator;
+
+impl<IT, VT> tensor::COOTensor<IT, VT>
+where
+    IT: IdxType + AutoSerialize,
+    VT: ValType + AutoSerialize,
+{
+    /// Write the tensor to a NumPy `.npz` archive.
+    ///
+    /// # Arguments
+    ///
+    /// * `w` - A [`std::io::Write`] and [`std::io::Seek`] object. Examples are [`std::fs::File`], [`std::io::Cursor<Vec<u8>>`].
+    ///
+    /// The arrays are described in [`tensor::COOTensor::read_from_npz`].
+    /// `indices` and `values` have the same layout as `coords` and `data` of [`sparse.COO`],
+    /// so the archive can be loaded in Python with:
+    ///
+    /// ```python
+    /// with np.load("tensor.npz") as f:
+    ///     tensor = sparse.COO(f["indices"], f["values"], shape=f["shape_upper"] - f["shape_lower"])
+    /// ```
+    ///
+    /// Dense axes are written as sparse, each element of a dense block becomes its own entry.
+    ///
+    /// [`sparse.COO`]: https://sparse.pydata.org/en/stable/generated/sparse.COO.html
+    pub fn write_to_npz<W>(&self, w: &mut W) -> io::Result<()>
+    where
+        W: io::Write + io::Seek,
+    {
+        let shape = self.shape();
+        let ndim = shape.len();
+        let nnz = self.num_non_zeros();
+
+        // Transpose into one row for each axis.
+        let mut indices = vec![Vec::with_capacity(nnz); ndim];
+        let mut values = Vec::with_capacity(nnz);
+        let mut tensor_iter = self.iter();
+        while let Some(&(index, value)) = tensor_iter.next() {
+            for ((row, &idx), axis) in indices.iter_mut().zip(index.iter()).zip(shape.iter()) {
+                row.push(idx - axis.lower());
+            }
+            values.push(value.clone());
+        }
+
+        let mut npz = NpzWriter::new(io::BufWriter::new(w));
+        let options = Default::default();
+        let mut writer = npz
+            .array::<IT>("indices", options)?
+            .default_dtype()
+            .shape(&[ndim as u64, nnz as u64])
+            .begin_nd()?;
+        for row in indices {
+            writer.extend(row)?;
+        }
+        writer.finish()?;
+        let mut writer = npz
+            .array::<VT>("values", options)?
+            .default_dtype()
+            .shape(&[nnz as u64])
+            .begin_nd()?;
+        writer.extend(values)?;
+        writer.finish()?;
+        for (name, bounds) in [
+            (
+                "shape_lower",
+                shape.iter().map(|axis| axis.lower()).collect::<Vec<_>>(),
+            ),
+            (
+                "shape_upper",
+                shape.iter().map(|axis| axis.upper()).collect(),
+            ),
+        ] {
+            let mut writer = npz
+                .array::<IT>(name, options)?
+                .default_dtype()
+                .shape(&[ndim as u64])
+                .begin_nd()?;
+            writer.extend(bounds)?;
+            writer.finish()?;
+        }
+
+        let labels = shape
+            .iter()
+            .map(|axis| axis.label().unwrap_or(""))
+            .collect::<Vec<_>>();
+        // NumPy strings are fixed-length UTF-32.
+        let max_len = labels
+            .iter()
+            .map(|label| label.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let dtype = DType::Plain(format!("<U{}", max_len).parse::<TypeStr>().unwrap());
+        let mut writer = npz
+            .array::<str>("labels", options)?
+            .dtype(dtype)
+            .shape(&[ndim as u64])
+            .begin_nd()?;
+        for label in labels {
+            writer.push(label)?;
+        }
+        writer.finish()?;
+
+        npz.zip_writer().finish()?.into_inner()?;
+        Ok(())
+    }
+
+    /// Write a dense tensor to a NumPy `.npy` file.
+    ///
+    /// # Arguments
+    ///
+    /// * `w` - A [`std::io::Write`] object. Examples are [`std::fs::File`], [`std::io::Stdout`], [`Vec<u8>`].
+    ///
+    /// The tensor must be fully dense, like those created by [`tensor::COOTensor::from_ndarray`].
+    /// The array is written in C order, with axes in the order of the shape.
+    /// If the tensor has no block, an array of zeros is written; if it has more than one block, the blocks are summed up.
+    pub fn write_to_npy<W>(&self, w: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        if !self.sparse_axes().is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the tensor must be fully dense",
+            ));
+        }
+
+        // Sum up the blocks to remove the block axis, then reorder the dense axes into the order of the shape.
+        let values = self.raw_parts().values.sum_axis(ndarray::Axis(0));
+        let values = values
+            .permuted_axes(map_axes_unwrap(self.shape(), self.dense_axes()).collect::<Vec<_>>());
+        let shape = values
+            .shape()
+            .iter()
+            .map(|&len| len as u64)
+            .collect::<Vec<_>>();
+
+        let mut writer = WriteOptions::new()
+            .default_dtype()
+            .shape(&shape)
+            .writer(io::BufWriter::new(w))
+            .begin_nd()?;
+        writer.extend(values.iter().cloned())?;
+        writer.finish()
+    }
+}
@@ -14,7 +14,7 @@ where
     range: Option<Range<IT>>,
 }
 
-static mut COUNTER: AtomicI64 = AtomicI64::new(0);
+static COUNTER: AtomicI64 = AtomicI64::new(0);
 
 impl<'a, IT> AxisBuilder<'a, IT>
 where
@@ -75,10 +75,7 @@ where
     #[inline]
     pub fn build(self) -> Axis<IT> {
         Axis {
-            // # Safety
-            // `COUNTER` is a static variable, accessing it requires an unsafe block.
-            // This access is safe because we are using an atomic operation.
-            id: unsafe { COUNTER.fetch_add(1, Ordering::Relaxed) },
+            id: COUNTER.fetch_add(1, Ordering::Relaxed),
             label: self.label.map(Cow::into_owned),
             range: self.range.expect("range not set"),
         }
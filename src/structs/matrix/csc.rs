@@ -0,0 +1,135 @@
+use crate::structs::axis::{Axes, Axis};
+use crate::traits::{IdxType, RawParts, Tensor, ValType};
+use ndarray::{Array1, ArrayView1};
+use std::fmt::Debug;
+
+/// A CSC (Compressed Sparse Column) format matrix.
+///
+/// The elements are grouped by columns, and sorted by rows inside each column.
+/// Duplicate indices are allowed, and are summed up by algorithms.
+///
+/// Convert from and to a 2-D [`COOTensor`](crate::structs::tensor::COOTensor) with [`CSCMatrix::from_coo`] and
+/// [`COOTensor::from_csc`](crate::structs::tensor::COOTensor::from_csc).
+#[derive(Clone, Debug)]
+pub struct CSCMatrix<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    inner: CSCMatrixInner<IT, VT>,
+}
+
+/// The inner representation of a `CSCMatrix`.
+///
+/// This is provided to allow algorithm implementations to access the underlying data while ensuring they have the responsibility for data integrity.
+///
+/// Check [`RawParts`] for more information.
+#[derive(Clone, Debug)]
+pub struct CSCMatrixInner<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    pub name: Option<String>,
+
+    /// The row axis, followed by the column axis.
+    pub shape: Axes<IT>,
+
+    /// The elements of column `j` are `col_offsets[j]..col_offsets[j + 1]`.
+    /// The length is the number of columns plus 1.
+    pub col_offsets: Vec<usize>,
+    /// The row index of each element.
+    pub row_indices: Vec<IT>,
+    /// The value of each element.
+    pub values: Array1<VT>,
+}
+
+impl<IT, VT> CSCMatrix<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.inner.name.as_deref()
+    }
+
+    #[inline]
+    fn name_mut(&mut self) -> &mut Option<String> {
+        &mut self.inner.name
+    }
+
+    #[inline]
+    pub fn row_axis(&self) -> &Axis<IT> {
+        &self.inner.shape[0]
+    }
+
+    #[inline]
+    pub fn col_axis(&self) -> &Axis<IT> {
+        &self.inner.shape[1]
+    }
+
+    /// The row indices and the values of the `j`-th column, counting from 0 instead of the lower bound of the column axis.
+    #[inline]
+    pub fn col(&self, j: usize) -> (&[IT], ArrayView1<'_, VT>) {
+        let range = self.inner.col_offsets[j]..self.inner.col_offsets[j + 1];
+        (
+            &self.inner.row_indices[range.clone()],
+            self.inner.values.slice(ndarray::s![range]),
+        )
+    }
+}
+
+impl<IT, VT> Tensor<IT, VT> for CSCMatrix<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.name()
+    }
+
+    #[inline]
+    fn name_mut(&mut self) -> &mut Option<String> {
+        self.name_mut()
+    }
+
+    #[inline]
+    fn num_non_zeros(&self) -> usize {
+        self.inner.values.len()
+    }
+
+    #[inline]
+    fn shape(&self) -> &[Axis<IT>] {
+        &self.inner.shape
+    }
+}
+
+impl<IT, VT> RawParts for CSCMatrix<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    type Inner = CSCMatrixInner<IT, VT>;
+
+    #[inline]
+    unsafe fn from_raw_parts(raw_parts: Self::Inner) -> Self {
+        Self { inner: raw_parts }
+    }
+
+    #[inline]
+    fn into_raw_parts(self) -> Self::Inner {
+        self.inner
+    }
+
+    #[inline]
+    fn raw_parts(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    #[inline]
+    unsafe fn raw_parts_mut(&mut self) -> &mut Self::Inner {
+        &mut self.inner
+    }
+}
@@ -0,0 +1,293 @@
+use super::csc::{CSCMatrix, CSCMatrixInner};
+use super::csr::{CSRMatrix, CSRMatrixInner};
+use crate::structs::axis::{Axes, Axis};
+use crate::structs::tensor::{COOTensor, COOTensorInner};
+use crate::traits::{IdxType, RawParts, Tensor, ValType};
+use ndarray::{Array1, Array2, ArrayD, IxDyn};
+use streaming_iterator::StreamingIterator;
+
+impl<IT, VT> CSRMatrix<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Create a new `CSRMatrix` from a 2-D [`COOTensor`].
+    ///
+    /// The first axis of `tensor` becomes the rows, and the second axis becomes the columns.
+    /// Dense axes of `tensor` are allowed, each element of their blocks becomes an element of the matrix.
+    pub fn from_coo(tensor: &COOTensor<IT, VT>) -> Self {
+        assert_eq!(tensor.ndim(), 2);
+        let shape = Axes::from(tensor.shape());
+        let (row_offsets, col_indices, values) = compress(&shape[0], coo_entries(tensor, false));
+        let raw_parts = CSRMatrixInner {
+            name: tensor.name().map(str::to_owned),
+            shape,
+            row_offsets,
+            col_indices,
+            values,
+        };
+        // # Safety
+        // Every element is grouped into its row, and every index is inside the shape.
+        unsafe { Self::from_raw_parts(raw_parts) }
+    }
+
+    /// Create a new `CSRMatrix` from a [`CSCMatrix`] with the same shape.
+    pub fn from_csc(matrix: &CSCMatrix<IT, VT>) -> Self {
+        let shape = Axes::from(matrix.shape());
+        let (row_offsets, col_indices, values) = compress(&shape[0], csc_entries(matrix, false));
+        let raw_parts = CSRMatrixInner {
+            name: matrix.name().map(str::to_owned),
+            shape,
+            row_offsets,
+            col_indices,
+            values,
+        };
+        // # Safety
+        // Every element is grouped into its row, and every index is inside the shape.
+        unsafe { Self::from_raw_parts(raw_parts) }
+    }
+}
+
+impl<IT, VT> CSCMatrix<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Create a new `CSCMatrix` from a 2-D [`COOTensor`].
+    ///
+    /// The first axis of `tensor` becomes the rows, and the second axis becomes the columns.
+    /// Dense axes of `tensor` are allowed, each element of their blocks becomes an element of the matrix.
+    pub fn from_coo(tensor: &COOTensor<IT, VT>) -> Self {
+        assert_eq!(tensor.ndim(), 2);
+        let shape = Axes::from(tensor.shape());
+        let (col_offsets, row_indices, values) = compress(&shape[1], coo_entries(tensor, true));
+        let raw_parts = CSCMatrixInner {
+            name: tensor.name().map(str::to_owned),
+            shape,
+            col_offsets,
+            row_indices,
+            values,
+        };
+        // # Safety
+        // Every element is grouped into its column, and every index is inside the shape.
+        unsafe { Self::from_raw_parts(raw_parts) }
+    }
+
+    /// Create a new `CSCMatrix` from a [`CSRMatrix`] with the same shape.
+    pub fn from_csr(matrix: &CSRMatrix<IT, VT>) -> Self {
+        let shape = Axes::from(matrix.shape());
+        let (col_offsets, row_indices, values) = compress(&shape[1], csr_entries(matrix, true));
+        let raw_parts = CSCMatrixInner {
+            name: matrix.name().map(str::to_owned),
+            shape,
+            col_offsets,
+            row_indices,
+            values,
+        };
+        // # Safety
+        // Every element is grouped into its column, and every index is inside the shape.
+        unsafe { Self::from_raw_parts(raw_parts) }
+    }
+}
+
+impl<IT, VT> COOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Create a new fully sparse `COOTensor` from a [`CSRMatrix`].
+    ///
+    /// The result is sorted by rows, then by columns.
+    pub fn from_csr(matrix: &CSRMatrix<IT, VT>) -> Self {
+        let shape = Axes::from(matrix.shape());
+        let sort_order = shape.clone();
+        coo_from_entries(matrix.name(), shape, sort_order, csr_entries(matrix, false))
+    }
+
+    /// Create a new fully sparse `COOTensor` from a [`CSCMatrix`].
+    ///
+    /// The result is sorted by columns, then by rows.
+    pub fn from_csc(matrix: &CSCMatrix<IT, VT>) -> Self {
+        let shape = Axes::from(matrix.shape());
+        let sort_order = shape.iter().rev().cloned().collect();
+        coo_from_entries(matrix.name(), shape, sort_order, csc_entries(matrix, false))
+    }
+}
+
+impl<IT, VT> From<&CSRMatrix<IT, VT>> for COOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    #[inline]
+    fn from(matrix: &CSRMatrix<IT, VT>) -> Self {
+        Self::from_csr(matrix)
+    }
+}
+
+impl<IT, VT> From<&CSCMatrix<IT, VT>> for COOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    #[inline]
+    fn from(matrix: &CSCMatrix<IT, VT>) -> Self {
+        Self::from_csc(matrix)
+    }
+}
+
+/// Each element of a 2-D `COOTensor` as `(row, column, value)`, or `(column, row, value)` if `transpose`.
+fn coo_entries<IT, VT>(tensor: &COOTensor<IT, VT>, transpose: bool) -> Vec<(IT, IT, VT)>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    let mut entries = Vec::with_capacity(tensor.num_non_zeros());
+    let mut tensor_iter = tensor.iter();
+    while let Some(&(index, value)) = tensor_iter.next() {
+        let (row, col) = (index[0], index[1]);
+        entries.push(if transpose {
+            (col, row, value.clone())
+        } else {
+            (row, col, value.clone())
+        });
+    }
+    entries
+}
+
+/// Each element of a `CSRMatrix` as `(row, column, value)`, or `(column, row, value)` if `transpose`.
+fn csr_entries<IT, VT>(matrix: &CSRMatrix<IT, VT>, transpose: bool) -> Vec<(IT, IT, VT)>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    let raw_parts = matrix.raw_parts();
+    let row_lower = matrix.row_axis().lower();
+    outer_entries(
+        &raw_parts.row_offsets,
+        &raw_parts.col_indices,
+        &raw_parts.values,
+        row_lower,
+        transpose,
+    )
+}
+
+/// Each element of a `CSCMatrix` as `(row, column, value)`, or `(column, row, value)` if `transpose`.
+fn csc_entries<IT, VT>(matrix: &CSCMatrix<IT, VT>, transpose: bool) -> Vec<(IT, IT, VT)>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    let raw_parts = matrix.raw_parts();
+    let col_lower = matrix.col_axis().lower();
+    outer_entries(
+        &raw_parts.col_offsets,
+        &raw_parts.row_indices,
+        &raw_parts.values,
+        col_lower,
+        !transpose,
+    )
+}
+
+/// Expand compressed storage into `(outer, inner, value)`, or `(inner, outer, value)` if `transpose`.
+fn outer_entries<IT, VT>(
+    offsets: &[usize],
+    inner_indices: &[IT],
+    values: &Array1<VT>,
+    outer_lower: IT,
+    transpose: bool,
+) -> Vec<(IT, IT, VT)>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    let mut entries = Vec::with_capacity(values.len());
+    let mut outer = outer_lower;
+    for range in offsets.windows(2) {
+        for k in range[0]..range[1] {
+            let inner = inner_indices[k];
+            entries.push(if transpose {
+                (inner, outer, values[k].clone())
+            } else {
+                (outer, inner, values[k].clone())
+            });
+        }
+        outer = outer + IT::one();
+    }
+    entries
+}
+
+/// Group `(outer, inner, value)` entries by `outer`, and sort them by `inner` inside each group.
+///
+/// Returns the offsets of each group, the inner indices, and the values.
+fn compress<IT, VT>(
+    outer_axis: &Axis<IT>,
+    entries: Vec<(IT, IT, VT)>,
+) -> (Vec<usize>, Vec<IT>, Array1<VT>)
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    let outer_lower = outer_axis.lower();
+    let group_of = |outer: IT| (outer - outer_lower).to_usize().unwrap();
+
+    // Counting sort by the outer index.
+    let mut offsets = vec![0; outer_axis.len() + 1];
+    for &(outer, _, _) in entries.iter() {
+        offsets[group_of(outer) + 1] += 1;
+    }
+    for i in 1..offsets.len() {
+        offsets[i] += offsets[i - 1];
+    }
+    let mut order = vec![0; entries.len()];
+    let mut next = offsets.clone();
+    for (k, &(outer, _, _)) in entries.iter().enumerate() {
+        let group = group_of(outer);
+        order[next[group]] = k;
+        next[group] += 1;
+    }
+    // Stable, so that duplicate indices keep their original order.
+    for range in offsets.windows(2) {
+        order[range[0]..range[1]].sort_by_key(|&k| entries[k].1);
+    }
+
+    let inner_indices = order.iter().map(|&k| entries[k].1).collect();
+    let values = order.iter().map(|&k| entries[k].2.clone()).collect();
+    (offsets, inner_indices, values)
+}
+
+/// Create a fully sparse 2-D `COOTensor` from `(row, column, value)` entries, which are sorted by `sort_order`.
+fn coo_from_entries<IT, VT>(
+    name: Option<&str>,
+    shape: Axes<IT>,
+    sort_order: Axes<IT>,
+    entries: Vec<(IT, IT, VT)>,
+) -> COOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    let num_blocks = entries.len();
+    let mut indices = Array2::zeros((num_blocks, 2));
+    let mut values = Vec::with_capacity(num_blocks);
+    for (block, (row, col, value)) in entries.into_iter().enumerate() {
+        indices[(block, 0)] = row;
+        indices[(block, 1)] = col;
+        values.push(value);
+    }
+
+    let raw_parts = COOTensorInner {
+        name: name.map(str::to_owned),
+        sparse_axes: shape.clone(),
+        shape,
+        dense_axes: Axes::new(),
+        indices,
+        values: ArrayD::from_shape_vec(IxDyn(&[num_blocks]), values).unwrap(),
+        sparse_is_sorted: true,
+        sparse_sort_order: sort_order,
+        sparse_is_coalesced: false,
+    };
+    // # Safety
+    // The entries come from a valid matrix in the order of `sort_order`.
+    unsafe { COOTensor::from_raw_parts(raw_parts) }
+}
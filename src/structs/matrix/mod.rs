@@ -0,0 +1,8 @@
+//! Sparse matrices.
+
+mod csc;
+mod csr;
+mod csr_from_coo;
+
+pub use csc::{CSCMatrix, CSCMatrixInner};
+pub use csr::{CSRMatrix, CSRMatrixInner};
@@ -1,5 +1,6 @@
 //! Data structures.
 
 pub mod axis;
+pub mod matrix;
 pub mod tensor;
 pub mod vec;
@@ -1,23 +1,30 @@
 use super::coo_iter::COOIter;
 use super::coo_iter_mut::COOIterMut;
 use crate::structs::axis::{Axes, Axis};
+use crate::structs::vec::SmallVec;
 use crate::traits::{IdxType, RawParts, Tensor, ValType};
 use ndarray::{self, Array2, ArrayD, ArrayView1, ArrayViewD};
-use std::fmt::Debug;
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
 use std::iter;
+use std::sync::OnceLock;
 
 /// A COO format tensor.
 ///
 /// The tensor may have zero or more non-sparse axes.
 /// If the tensor has full non-sparse axes, it is considered dense.
 /// Therefore, `COOTensor` can be used to represent dense matrices.
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct COOTensor<IT, VT>
 where
     IT: IdxType,
     VT: ValType,
 {
     inner: COOTensorInner<IT, VT>,
+
+    /// Maps each sparse index, counting from the lower bounds, to its first block.
+    /// Built by [`COOTensor::get`] on unsorted tensors, and dropped whenever the indices may change.
+    block_lookup: OnceLock<HashMap<SmallVec<usize>, usize>>,
 }
 
 /// The inner representation of a `COOTensor`.
@@ -51,6 +58,8 @@ where
     pub sparse_is_sorted: bool,
     /// Which sparse axis is the outermost?
     pub sparse_sort_order: Axes<IT>,
+    /// Is each sparse index known to appear only once?
+    pub sparse_is_coalesced: bool,
 }
 
 impl<IT, VT> COOTensor<IT, VT>
@@ -79,6 +88,7 @@ where
                 .collect::<Vec<_>>(),
         );
         Self {
+            block_lookup: OnceLock::new(),
             inner: COOTensorInner {
                 name: None,
                 shape: Axes::from(shape),
@@ -88,6 +98,7 @@ where
                 values,
                 sparse_is_sorted: true,
                 sparse_sort_order,
+                sparse_is_coalesced: true,
             },
         }
     }
@@ -126,6 +137,14 @@ where
         self.inner.sparse_is_sorted = false;
     }
 
+    /// Returns `true` if each sparse index is known to appear only once.
+    ///
+    /// Use [`CoalesceCOOTensor`](crate::algos::tensor::CoalesceCOOTensor) to merge duplicate indices.
+    #[inline]
+    pub fn is_coalesced(&self) -> bool {
+        self.inner.sparse_is_coalesced
+    }
+
     #[inline]
     pub fn num_blocks(&self) -> usize {
         self.inner.indices.nrows()
@@ -141,10 +160,24 @@ where
         assert_eq!(value.shape(), &self.inner.values.shape()[1..]);
 
         self.clear_sparse_sort_order();
+        self.inner.sparse_is_coalesced = false;
+        self.block_lookup.take();
         self.inner.indices.push_row(sparse_index).unwrap();
         self.inner.values.push(ndarray::Axis(0), value).unwrap();
     }
 
+    /// The map built by [`COOTensor::get`] on unsorted tensors.
+    #[inline]
+    pub(super) fn block_lookup(&self) -> &OnceLock<HashMap<SmallVec<usize>, usize>> {
+        &self.block_lookup
+    }
+
+    /// Only the value is changed, so the map built by [`COOTensor::get`] stays valid.
+    #[inline]
+    pub(super) fn value_mut(&mut self, value_index: &[usize]) -> &mut VT {
+        &mut self.inner.values[value_index]
+    }
+
     #[inline]
     pub fn iter(&self) -> COOIter<'_, IT, VT> {
         COOIter::new(self)
@@ -191,7 +224,10 @@ where
 
     #[inline]
     unsafe fn from_raw_parts(raw_parts: Self::Inner) -> Self {
-        Self { inner: raw_parts }
+        Self {
+            inner: raw_parts,
+            block_lookup: OnceLock::new(),
+        }
     }
 
     #[inline]
@@ -206,6 +242,19 @@ where
 
     #[inline]
     unsafe fn raw_parts_mut(&mut self) -> &mut Self::Inner {
+        self.block_lookup.take();
         &mut self.inner
     }
 }
+
+impl<IT, VT> Debug for COOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("COOTensor")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
@@ -1,8 +1,9 @@
 use super::coo::{COOTensor, COOTensorInner};
+use super::DenseTensor;
 use crate::structs::axis::{Axes, AxisBuilder};
 use crate::traits::{IdxType, RawParts, ValType};
 use ndarray;
-use ndarray::{Array2, ArrayBase, Data, Dimension, RawData};
+use ndarray::{Array2, ArrayBase, ArrayD, Data, Dimension, RawData};
 use num::NumCast;
 
 impl<IT, VT> COOTensor<IT, VT>
@@ -39,11 +40,22 @@ where
             values,
             sparse_is_sorted: true,
             sparse_sort_order: Axes::new(),
+            sparse_is_coalesced: true,
         };
         // # Safety
         // We have checked the data integrity
         unsafe { Self::from_raw_parts(raw_parts) }
     }
+
+    /// Copy the tensor into a full [`ndarray::ArrayD`], in the order of the shape.
+    ///
+    /// Each array axis counts from the lower bound of its axis.
+    /// Elements not stored in the tensor are zero, duplicate elements are summed up.
+    /// Every element is allocated, so this is only suitable for small tensors.
+    #[inline]
+    pub fn to_ndarray(&self) -> ArrayD<VT> {
+        DenseTensor::from_coo(self).into_raw_parts().values
+    }
 }
 
 impl<S, D, IT> From<ArrayBase<S, D>> for COOTensor<IT, S::Elem>
@@ -0,0 +1,139 @@
+use super::COOTensor;
+use crate::structs::axis::map_axes_unwrap;
+use crate::structs::vec::SmallVec;
+use crate::traits::{IdxType, RawParts, ValType};
+use ndarray::Array2;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+impl<IT, VT> COOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// The value at `index`, in the order of the shape.
+    ///
+    /// Returns `None` if the element is not stored, or `index` is out of bound.
+    /// If the tensor is not coalesced, only the first stored block of a duplicate index is looked at.
+    ///
+    /// A sorted tensor is looked up with binary search.
+    /// Otherwise, a hash map of the sparse indices is built on the first call, and reused until the indices change.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pattie::structs::tensor::COOTensor;
+    ///
+    /// let input = "2\n0 0\n2 3\n0 1 1.0\n1 2 2.0\n";
+    /// let tensor = COOTensor::<u32, f32>::read_from_text(&mut input.as_bytes()).unwrap();
+    /// assert_eq!(tensor.get(&[1, 2]), Some(&2.0));
+    /// assert_eq!(tensor.get(&[1, 1]), None);
+    /// ```
+    pub fn get(&self, index: &[IT]) -> Option<&VT> {
+        let (block, mut value_index) = self.find_block(index)?;
+        value_index.insert(0, block);
+        Some(&self.raw_parts().values[value_index.as_slice()])
+    }
+
+    /// Same as [`COOTensor::get`], but returns a mutable reference.
+    pub fn get_mut(&mut self, index: &[IT]) -> Option<&mut VT> {
+        let (block, mut value_index) = self.find_block(index)?;
+        value_index.insert(0, block);
+        Some(self.value_mut(&value_index))
+    }
+
+    /// Find the block containing `index`, and the offsets of `index` inside the dense block.
+    fn find_block(&self, index: &[IT]) -> Option<(usize, SmallVec<usize>)> {
+        let raw_parts = self.raw_parts();
+        let shape = raw_parts.shape.as_slice();
+        assert_eq!(index.len(), shape.len());
+        if !index
+            .iter()
+            .zip(shape.iter())
+            .all(|(idx, axis)| axis.range().contains(idx))
+        {
+            return None;
+        }
+
+        let sparse_index = map_axes_unwrap(&raw_parts.sparse_axes, shape)
+            .map(|axis_idx| index[axis_idx])
+            .collect::<SmallVec<_>>();
+        let dense_index = map_axes_unwrap(&raw_parts.dense_axes, shape)
+            .map(|axis_idx| {
+                (index[axis_idx] - shape[axis_idx].lower())
+                    .to_usize()
+                    .unwrap()
+            })
+            .collect::<SmallVec<_>>();
+
+        let block = match self.sparse_sort_order() {
+            Some(sort_order) => {
+                let sort_to_sparse =
+                    map_axes_unwrap(sort_order, &raw_parts.sparse_axes).collect::<SmallVec<_>>();
+                binary_search(&raw_parts.indices, &sort_to_sparse, &sparse_index)?
+            }
+            None => {
+                let key = sparse_index
+                    .iter()
+                    .zip(raw_parts.sparse_axes.iter())
+                    .map(|(&idx, axis)| (idx - axis.lower()).to_usize().unwrap())
+                    .collect::<SmallVec<_>>();
+                *self
+                    .block_lookup()
+                    .get_or_init(|| build_block_lookup(self))
+                    .get(&key)?
+            }
+        };
+        Some((block, dense_index))
+    }
+}
+
+/// Find the first block equal to `sparse_index`, with blocks sorted by the columns in `sort_to_sparse`.
+fn binary_search<IT>(
+    indices: &Array2<IT>,
+    sort_to_sparse: &[usize],
+    sparse_index: &[IT],
+) -> Option<usize>
+where
+    IT: IdxType,
+{
+    let compare = |block: usize| {
+        sort_to_sparse
+            .iter()
+            .map(|&col| indices[(block, col)].cmp(&sparse_index[col]))
+            .find(|&ordering| ordering != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    };
+
+    // Find the first block not less than `sparse_index`.
+    let mut lower = 0;
+    let mut upper = indices.nrows();
+    while lower < upper {
+        let middle = lower + (upper - lower) / 2;
+        if compare(middle) == Ordering::Less {
+            lower = middle + 1;
+        } else {
+            upper = middle;
+        }
+    }
+    (lower < indices.nrows() && compare(lower) == Ordering::Equal).then_some(lower)
+}
+
+/// Map each sparse index, counting from the lower bounds, to its first block.
+fn build_block_lookup<IT, VT>(tensor: &COOTensor<IT, VT>) -> HashMap<SmallVec<usize>, usize>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    let raw_parts = tensor.raw_parts();
+    let mut block_lookup = HashMap::with_capacity(raw_parts.indices.nrows());
+    for (block, sparse_index) in raw_parts.indices.outer_iter().enumerate() {
+        let key = sparse_index
+            .iter()
+            .zip(raw_parts.sparse_axes.iter())
+            .map(|(&idx, axis)| (idx - axis.lower()).to_usize().unwrap())
+            .collect::<SmallVec<_>>();
+        block_lookup.entry(key).or_insert(block);
+    }
+    block_lookup
+}
@@ -0,0 +1,55 @@
+use super::COOTensor;
+use crate::algos::tensor_tensor::ElementwiseCOOTensors;
+use crate::traits::{IdxType, ValType};
+use std::ops::{Add, Mul, Sub};
+
+/// Element-wise addition, see [`ElementwiseCOOTensors`].
+///
+/// # Panics
+/// Panics if the tensors do not have the same sparse axes and the same dense axes.
+impl<'a, IT, VT> Add<&'a COOTensor<IT, VT>> for &'a COOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    type Output = COOTensor<IT, VT>;
+
+    #[inline]
+    fn add(self, rhs: &'a COOTensor<IT, VT>) -> Self::Output {
+        ElementwiseCOOTensors::add(self, rhs).execute().unwrap()
+    }
+}
+
+/// Element-wise subtraction, see [`ElementwiseCOOTensors`].
+///
+/// # Panics
+/// Panics if the tensors do not have the same sparse axes and the same dense axes.
+impl<'a, IT, VT> Sub<&'a COOTensor<IT, VT>> for &'a COOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    type Output = COOTensor<IT, VT>;
+
+    #[inline]
+    fn sub(self, rhs: &'a COOTensor<IT, VT>) -> Self::Output {
+        ElementwiseCOOTensors::sub(self, rhs).execute().unwrap()
+    }
+}
+
+/// Element-wise (Hadamard) product, see [`ElementwiseCOOTensors`].
+///
+/// # Panics
+/// Panics if the tensors do not have the same sparse axes and the same dense axes.
+impl<'a, IT, VT> Mul<&'a COOTensor<IT, VT>> for &'a COOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    type Output = COOTensor<IT, VT>;
+
+    #[inline]
+    fn mul(self, rhs: &'a COOTensor<IT, VT>) -> Self::Output {
+        ElementwiseCOOTensors::mul(self, rhs).execute().unwrap()
+    }
+}
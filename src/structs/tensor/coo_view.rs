@@ -0,0 +1,190 @@
+use super::{COOTensor, COOTensorInner};
+use crate::structs::axis::Axis;
+use crate::traits::{IdxType, RawParts, ValType};
+use ndarray::{ArrayView2, ArrayViewD};
+
+/// A read-only view of a COO format tensor.
+///
+/// The indices and the values are borrowed, either from a [`COOTensor`], or from a memory-mapped file.
+/// Use [`COOTensor::view`] to create a view of an owned tensor.
+///
+/// Algorithms that only read the tensor accept a `COOTensorView`, so they work on both.
+#[derive(Debug)]
+pub struct COOTensorView<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    name: Option<&'a str>,
+    shape: &'a [Axis<IT>],
+    sparse_axes: &'a [Axis<IT>],
+    dense_axes: &'a [Axis<IT>],
+    indices: ArrayView2<'a, IT>,
+    values: ArrayViewD<'a, VT>,
+    sparse_sort_order: Option<&'a [Axis<IT>]>,
+    sparse_is_coalesced: bool,
+}
+
+impl<'a, IT, VT> COOTensorView<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Create a view from its parts.
+    ///
+    /// # Safety
+    ///
+    /// The parts must satisfy the same invariants as [`COOTensorInner`].
+    /// `sparse_sort_order` is `None` if the tensor is not sorted.
+    #[allow(clippy::too_many_arguments)]
+    #[inline]
+    pub(crate) unsafe fn from_parts(
+        name: Option<&'a str>,
+        shape: &'a [Axis<IT>],
+        sparse_axes: &'a [Axis<IT>],
+        dense_axes: &'a [Axis<IT>],
+        indices: ArrayView2<'a, IT>,
+        values: ArrayViewD<'a, VT>,
+        sparse_sort_order: Option<&'a [Axis<IT>]>,
+        sparse_is_coalesced: bool,
+    ) -> Self {
+        Self {
+            name,
+            shape,
+            sparse_axes,
+            dense_axes,
+            indices,
+            values,
+            sparse_sort_order,
+            sparse_is_coalesced,
+        }
+    }
+
+    #[inline]
+    pub fn name(&self) -> Option<&'a str> {
+        self.name
+    }
+
+    #[inline]
+    pub fn ndim(&self) -> usize {
+        self.shape.len()
+    }
+
+    #[inline]
+    pub fn shape(&self) -> &'a [Axis<IT>] {
+        self.shape
+    }
+
+    #[inline]
+    pub fn sparse_axes(&self) -> &'a [Axis<IT>] {
+        self.sparse_axes
+    }
+
+    #[inline]
+    pub fn dense_axes(&self) -> &'a [Axis<IT>] {
+        self.dense_axes
+    }
+
+    #[inline]
+    pub fn sparse_sort_order(&self) -> Option<&'a [Axis<IT>]> {
+        self.sparse_sort_order
+    }
+
+    /// Returns `true` if each sparse index is known to appear only once.
+    #[inline]
+    pub fn is_coalesced(&self) -> bool {
+        self.sparse_is_coalesced
+    }
+
+    #[inline]
+    pub fn num_blocks(&self) -> usize {
+        self.indices.nrows()
+    }
+
+    /// The number of elements taking storage space, same as [`crate::traits::Tensor::num_non_zeros`].
+    #[inline]
+    pub fn num_non_zeros(&self) -> usize {
+        self.values.len()
+    }
+
+    /// First index is each non-zero block, second index is each sparse axis, in the order of `sparse_axes`.
+    #[inline]
+    pub fn indices(&self) -> ArrayView2<'a, IT> {
+        self.indices
+    }
+
+    /// First index is each non-zero block, remaining indices are each dense axis.
+    #[inline]
+    pub fn values(&self) -> ArrayViewD<'a, VT> {
+        self.values.clone()
+    }
+
+    /// Copy the viewed tensor into an owned [`COOTensor`].
+    pub fn to_owned(&self) -> COOTensor<IT, VT> {
+        let result = COOTensorInner {
+            name: self.name.map(str::to_owned),
+            shape: self.shape.into(),
+            sparse_axes: self.sparse_axes.into(),
+            dense_axes: self.dense_axes.into(),
+            indices: self.indices.to_owned(),
+            values: self.values.to_owned(),
+            sparse_is_sorted: self.sparse_sort_order.is_some(),
+            sparse_sort_order: self.sparse_sort_order.unwrap_or(self.sparse_axes).into(),
+            sparse_is_coalesced: self.sparse_is_coalesced,
+        };
+        // # Safety
+        // The view satisfies the same invariants.
+        unsafe { COOTensor::from_raw_parts(result) }
+    }
+}
+
+impl<'a, IT, VT> Clone for COOTensorView<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+            ..*self
+        }
+    }
+}
+
+impl<IT, VT> COOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Borrow the tensor as a [`COOTensorView`].
+    #[inline]
+    pub fn view(&self) -> COOTensorView<'_, IT, VT> {
+        let raw_parts = self.raw_parts();
+        // # Safety
+        // The tensor is in valid state.
+        unsafe {
+            COOTensorView::from_parts(
+                raw_parts.name.as_deref(),
+                &raw_parts.shape,
+                &raw_parts.sparse_axes,
+                &raw_parts.dense_axes,
+                raw_parts.indices.view(),
+                raw_parts.values.view(),
+                self.sparse_sort_order(),
+                raw_parts.sparse_is_coalesced,
+            )
+        }
+    }
+}
+
+impl<'a, IT, VT> From<&'a COOTensor<IT, VT>> for COOTensorView<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    #[inline]
+    fn from(tensor: &'a COOTensor<IT, VT>) -> Self {
+        tensor.view()
+    }
+}
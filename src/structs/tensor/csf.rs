@@ -0,0 +1,156 @@
+use super::csf_iter::CSFIter;
+use crate::structs::axis::{Axes, Axis};
+use crate::traits::{IdxType, RawParts, Tensor, ValType};
+use ndarray::ArrayD;
+use std::fmt::Debug;
+
+/// A CSF (Compressed Sparse Fiber) format tensor.
+///
+/// The sparse axes are stored as a tree of fibers, with one level per sparse axis in the order of `sparse_sort_order`.
+/// Each leaf of the tree is a non-zero block, which may span zero or more dense axes, similar to [`super::COOTensor`].
+///
+/// Unlike `COOTensor`, the index of an outer axis is stored only once for all of its children.
+#[derive(Clone, Debug)]
+pub struct CSFTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    inner: CSFTensorInner<IT, VT>,
+}
+
+/// The inner representation of a `CSFTensor`.
+///
+/// This is provided to allow algorithm implementations to access the underlying data while ensuring they have the responsibility for data integrity.
+///
+/// Check [`RawParts`] for more information.
+#[derive(Clone, Debug)]
+pub struct CSFTensorInner<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    pub name: Option<String>,
+
+    /// The logical shape of this tensor, expressed as an array of axes.
+    /// Each axis contains a lower bound and an upper bound.
+    pub shape: Axes<IT>,
+
+    /// All sparse axes, from the root level to the leaf level of the fiber tree.
+    pub sparse_sort_order: Axes<IT>,
+    /// All dense axes.
+    pub dense_axes: Axes<IT>,
+
+    /// First index is each non-leaf level, second index is each node in that level.
+    /// The children of node `i` at level `l` are `fiber_offsets[l][i]..fiber_offsets[l][i + 1]` at level `l + 1`.
+    pub fiber_offsets: Vec<Vec<usize>>,
+    /// First index is each level, second index is each node in that level.
+    /// The value is the index of the node along `sparse_sort_order[l]`.
+    pub fiber_indices: Vec<Vec<IT>>,
+    /// First index is each leaf node, remaining indices are each dense axis.
+    pub values: ArrayD<VT>,
+}
+
+impl<IT, VT> CSFTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.inner.name.as_deref()
+    }
+
+    #[inline]
+    fn name_mut(&mut self) -> &mut Option<String> {
+        &mut self.inner.name
+    }
+
+    /// All sparse axes, from the root level to the leaf level of the fiber tree.
+    #[inline]
+    pub fn sparse_sort_order(&self) -> &[Axis<IT>] {
+        &self.inner.sparse_sort_order
+    }
+
+    #[inline]
+    pub fn dense_axes(&self) -> &[Axis<IT>] {
+        &self.inner.dense_axes
+    }
+
+    /// The number of levels in the fiber tree, which equals to the number of sparse axes.
+    #[inline]
+    pub fn num_levels(&self) -> usize {
+        self.inner.sparse_sort_order.len()
+    }
+
+    /// The number of nodes at `level` of the fiber tree.
+    #[inline]
+    pub fn num_fibers(&self, level: usize) -> usize {
+        self.inner.fiber_indices[level].len()
+    }
+
+    /// The number of leaf nodes, each of them is a non-zero block.
+    #[inline]
+    pub fn num_blocks(&self) -> usize {
+        self.inner.values.shape()[0]
+    }
+
+    #[inline]
+    pub fn iter(&self) -> CSFIter<'_, IT, VT> {
+        CSFIter::new(self)
+    }
+}
+
+impl<IT, VT> Tensor<IT, VT> for CSFTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.name()
+    }
+
+    #[inline]
+    fn name_mut(&mut self) -> &mut Option<String> {
+        self.name_mut()
+    }
+
+    #[inline]
+    fn num_non_zeros(&self) -> usize {
+        self.inner.values.len()
+    }
+
+    #[inline]
+    fn shape(&self) -> &[Axis<IT>] {
+        &self.inner.shape
+    }
+}
+
+impl<IT, VT> RawParts for CSFTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    type Inner = CSFTensorInner<IT, VT>;
+
+    #[inline]
+    unsafe fn from_raw_parts(raw_parts: Self::Inner) -> Self {
+        Self { inner: raw_parts }
+    }
+
+    #[inline]
+    fn into_raw_parts(self) -> Self::Inner {
+        self.inner
+    }
+
+    #[inline]
+    fn raw_parts(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    #[inline]
+    unsafe fn raw_parts_mut(&mut self) -> &mut Self::Inner {
+        &mut self.inner
+    }
+}
@@ -0,0 +1,138 @@
+use super::coo::{COOTensor, COOTensorInner};
+use super::csf::{CSFTensor, CSFTensorInner};
+use crate::algos::tensor::SortCOOTensor;
+use crate::structs::axis::{map_axes_unwrap, Axis};
+use crate::structs::vec::{smallvec, SmallVec};
+use crate::traits::{IdxType, RawParts, ValType};
+use ndarray::Array2;
+use std::borrow::Cow;
+
+impl<IT, VT> CSFTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Create a new `CSFTensor` from a [`COOTensor`].
+    ///
+    /// `order` must contain every sparse axis of `tensor` exactly once, and becomes the level order of the fiber tree.
+    /// If `tensor` is not sorted by `order`, a sorted copy is made with [`SortCOOTensor`].
+    ///
+    /// Dense axes of `tensor` are kept as dense axes.
+    pub fn from_coo(tensor: &COOTensor<IT, VT>, order: &[Axis<IT>]) -> Self {
+        assert_eq!(order.len(), tensor.sparse_axes().len());
+        let tensor = if tensor.sparse_sort_order() == Some(order) {
+            Cow::Borrowed(tensor)
+        } else {
+            let mut tensor = tensor.clone();
+            SortCOOTensor::new(&mut tensor, order).execute();
+            Cow::Owned(tensor)
+        };
+        let raw_parts = tensor.raw_parts();
+
+        let order_index = map_axes_unwrap(order, &raw_parts.sparse_axes).collect::<SmallVec<_>>();
+        let num_levels = order.len();
+        let num_blocks = raw_parts.indices.nrows();
+
+        let mut fiber_offsets = vec![Vec::new(); num_levels.saturating_sub(1)];
+        let mut fiber_indices = vec![Vec::new(); num_levels];
+
+        if num_levels != 0 {
+            for block in 0..num_blocks {
+                // Find the outermost level where this block differs from the previous one.
+                // Duplicate indices still produce a new leaf node.
+                let first_new_level = if block == 0 {
+                    0
+                } else {
+                    order_index
+                        .iter()
+                        .position(|&col| {
+                            raw_parts.indices[(block, col)] != raw_parts.indices[(block - 1, col)]
+                        })
+                        .unwrap_or(num_levels - 1)
+                };
+                for level in first_new_level..num_levels {
+                    if level + 1 < num_levels {
+                        fiber_offsets[level].push(fiber_indices[level + 1].len());
+                    }
+                    fiber_indices[level].push(raw_parts.indices[(block, order_index[level])]);
+                }
+            }
+            for level in 0..num_levels - 1 {
+                fiber_offsets[level].push(fiber_indices[level + 1].len());
+            }
+        }
+
+        let raw_parts = CSFTensorInner {
+            name: raw_parts.name.clone(),
+            shape: raw_parts.shape.clone(),
+            sparse_sort_order: order.iter().cloned().collect(),
+            dense_axes: raw_parts.dense_axes.clone(),
+            fiber_offsets,
+            fiber_indices,
+            values: raw_parts.values.clone(),
+        };
+        // # Safety
+        // Every leaf node corresponds to one block of the sorted `COOTensor`.
+        unsafe { Self::from_raw_parts(raw_parts) }
+    }
+}
+
+impl<IT, VT> COOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Create a new `COOTensor` from a [`CSFTensor`].
+    ///
+    /// The sparse axes of the result are in the level order of the fiber tree,
+    /// and the result is sorted in that order.
+    pub fn from_csf(tensor: &CSFTensor<IT, VT>) -> Self {
+        let raw_parts = tensor.raw_parts();
+        let num_levels = raw_parts.sparse_sort_order.len();
+        let num_blocks = tensor.num_blocks();
+
+        let mut indices = Array2::zeros((num_blocks, num_levels));
+        let mut fiber_path: SmallVec<usize> = smallvec![0; num_levels];
+        for block in 0..num_blocks {
+            if num_levels != 0 {
+                fiber_path[num_levels - 1] = block;
+                for level in (0..num_levels - 1).rev() {
+                    if fiber_path[level + 1]
+                        >= raw_parts.fiber_offsets[level][fiber_path[level] + 1]
+                    {
+                        fiber_path[level] += 1;
+                    }
+                }
+            }
+            for (level, &node) in fiber_path.iter().enumerate() {
+                indices[(block, level)] = raw_parts.fiber_indices[level][node];
+            }
+        }
+
+        let raw_parts = COOTensorInner {
+            name: raw_parts.name.clone(),
+            shape: raw_parts.shape.clone(),
+            sparse_axes: raw_parts.sparse_sort_order.clone(),
+            dense_axes: raw_parts.dense_axes.clone(),
+            indices,
+            values: raw_parts.values.clone(),
+            sparse_is_sorted: true,
+            sparse_sort_order: raw_parts.sparse_sort_order.clone(),
+            sparse_is_coalesced: false,
+        };
+        // # Safety
+        // Leaf nodes are visited in the order of the fiber tree.
+        unsafe { Self::from_raw_parts(raw_parts) }
+    }
+}
+
+impl<IT, VT> From<&CSFTensor<IT, VT>> for COOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    #[inline]
+    fn from(tensor: &CSFTensor<IT, VT>) -> Self {
+        Self::from_csf(tensor)
+    }
+}
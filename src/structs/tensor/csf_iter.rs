@@ -0,0 +1,179 @@
+use super::CSFTensor;
+use crate::structs::axis::{map_axes_unwrap, Axis};
+use crate::structs::vec::{smallvec, SmallVec};
+use crate::traits::{IdxType, RawParts, ValType};
+use ndarray::{ArrayD, Ix};
+use num::{NumCast, ToPrimitive};
+use streaming_iterator::StreamingIterator;
+
+/// Iterator for [`CSFTensor`].
+///
+/// Elements are visited in the order of the fiber tree.
+/// Elements inside a dense block are visited in row-major order.
+pub struct CSFIter<'a, IT, VT>
+where
+    IT: 'a + IdxType,
+    VT: 'a + ValType,
+{
+    dense_axes: &'a [Axis<IT>],
+    fiber_offsets: &'a [Vec<usize>],
+    fiber_indices: &'a [Vec<IT>],
+    values: &'a ArrayD<VT>,
+
+    dense_index_to_logic: SmallVec<usize>,
+    sparse_level_to_logic: SmallVec<usize>,
+
+    fiber_path: SmallVec<usize>,
+    dense_index_buffer: SmallVec<Ix>,
+    logic_index_buffer: SmallVec<IT>,
+    result_buffer: Option<(&'a [IT], &'a VT)>,
+}
+
+impl<'a, IT, VT> CSFIter<'a, IT, VT>
+where
+    IT: 'a + IdxType,
+    VT: 'a + ValType,
+{
+    #[inline]
+    pub(super) fn new(tensor: &'a CSFTensor<IT, VT>) -> Self {
+        let raw_parts = tensor.raw_parts();
+
+        let shape = raw_parts.shape.as_slice();
+        let sparse_sort_order = raw_parts.sparse_sort_order.as_slice();
+        let dense_axes = raw_parts.dense_axes.as_slice();
+        let values = &raw_parts.values;
+
+        let dense_index_to_logic = map_axes_unwrap(dense_axes, shape).collect::<SmallVec<_>>();
+        let sparse_level_to_logic =
+            map_axes_unwrap(sparse_sort_order, shape).collect::<SmallVec<_>>();
+
+        let fiber_path = smallvec![0; sparse_sort_order.len()];
+        let dense_index_buffer = smallvec![0; values.ndim()];
+        let logic_index_buffer = smallvec![IT::zero(); shape.len()];
+
+        Self {
+            dense_axes,
+            fiber_offsets: &raw_parts.fiber_offsets,
+            fiber_indices: &raw_parts.fiber_indices,
+            values,
+            dense_index_to_logic,
+            sparse_level_to_logic,
+            fiber_path,
+            dense_index_buffer,
+            logic_index_buffer,
+            result_buffer: None,
+        }
+    }
+
+    /// Move `fiber_path` to the leaf node `leaf`, which must be the successor of the current leaf node.
+    #[inline]
+    fn advance_fiber_path(&mut self, leaf: usize) {
+        let num_levels = self.fiber_path.len();
+        if num_levels == 0 {
+            return;
+        }
+        self.fiber_path[num_levels - 1] = leaf;
+        for level in (0..num_levels - 1).rev() {
+            // Fibers are never empty, so the parent moves at most one step.
+            if self.fiber_path[level + 1] >= self.fiber_offsets[level][self.fiber_path[level] + 1] {
+                self.fiber_path[level] += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    #[inline]
+    fn calc_result(&mut self) -> (&'a [IT], &'a VT) {
+        for ((&node, indices), &axis_idx) in self
+            .fiber_path
+            .iter()
+            .zip(self.fiber_indices.iter())
+            .zip(self.sparse_level_to_logic.iter())
+        {
+            self.logic_index_buffer[axis_idx] = indices[node];
+        }
+
+        for ((&index, axis), &axis_idx) in self
+            .dense_index_buffer
+            .iter()
+            .skip(1)
+            .zip(self.dense_axes.iter())
+            .zip(self.dense_index_to_logic.iter())
+        {
+            // Checking overflow, since we are converting usize into IT
+            self.logic_index_buffer[axis_idx] = <IT as NumCast>::from(
+                index
+                    .to_isize()
+                    .unwrap()
+                    .checked_add(axis.lower().to_isize().unwrap())
+                    .unwrap(),
+            )
+            .unwrap();
+        }
+
+        let result: (&[IT], &VT) = (
+            &self.logic_index_buffer,
+            &self.values[self.dense_index_buffer.as_slice()],
+        );
+        // # Safety
+        // Same as `COOIter`, the buffer lives as long as the iterator.
+        unsafe {
+            (
+                (result.0 as *const [IT]).as_ref::<'a>().unwrap_unchecked(),
+                (result.1 as *const VT).as_ref::<'a>().unwrap_unchecked(),
+            )
+        }
+    }
+}
+
+impl<'a, IT, VT> StreamingIterator for CSFIter<'a, IT, VT>
+where
+    IT: 'a + IdxType,
+    VT: 'a + ValType,
+{
+    type Item = (&'a [IT], &'a VT);
+
+    #[inline]
+    fn advance(&mut self) {
+        if self.result_buffer.is_some() {
+            // Move to the next element inside the current dense block.
+            for dense_axis in (1..self.dense_index_buffer.len()).rev() {
+                self.dense_index_buffer[dense_axis] += 1;
+                if self.dense_index_buffer[dense_axis] >= self.values.shape()[dense_axis] {
+                    self.dense_index_buffer[dense_axis] = 0;
+                } else {
+                    self.result_buffer = Some(self.calc_result());
+                    return;
+                }
+            }
+            // Move to the next leaf node.
+            let leaf = self.dense_index_buffer[0] + 1;
+            if leaf >= self.values.shape()[0] {
+                self.result_buffer = None;
+                return;
+            }
+            self.dense_index_buffer[0] = leaf;
+            self.advance_fiber_path(leaf);
+            self.result_buffer = Some(self.calc_result());
+        } else {
+            if self.values.is_empty() {
+                return;
+            }
+            self.dense_index_buffer.fill(0);
+            self.fiber_path.fill(0);
+            self.result_buffer = Some(self.calc_result());
+        }
+    }
+
+    #[inline]
+    fn get(&self) -> Option<&Self::Item> {
+        self.result_buffer.as_ref()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let size_hint = self.values.len();
+        (size_hint, Some(size_hint))
+    }
+}
@@ -0,0 +1,145 @@
+use super::dense_iter::{DenseIter, DenseIterMut};
+use crate::structs::axis::{Axes, Axis};
+use crate::traits::{IdxType, RawParts, Tensor, ValType};
+use ndarray::ArrayD;
+use std::fmt::Debug;
+
+/// A dense tensor.
+///
+/// Every element takes storage space, in an [`ndarray::ArrayD`] with one array axis for each axis of the tensor.
+///
+/// A `DenseTensor` converts cheaply from and to a [`COOTensor`](super::COOTensor) without sparse axes,
+/// and [`DenseTensor::coo_view`] lets algorithms accepting a [`COOTensorView`](super::COOTensorView) read it directly.
+#[derive(Clone, Debug)]
+pub struct DenseTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    inner: DenseTensorInner<IT, VT>,
+}
+
+/// The inner representation of a `DenseTensor`.
+///
+/// This is provided to allow algorithm implementations to access the underlying data while ensuring they have the responsibility for data integrity.
+///
+/// Check [`RawParts`] for more information.
+#[derive(Clone, Debug)]
+pub struct DenseTensorInner<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    pub name: Option<String>,
+
+    /// The logical shape of this tensor, expressed as an array of axes.
+    /// Each axis contains a lower bound and an upper bound.
+    pub shape: Axes<IT>,
+
+    /// Each array axis is the axis of `shape` at the same position, counting from the lower bound.
+    /// The array is in standard layout.
+    pub values: ArrayD<VT>,
+}
+
+impl<IT, VT> DenseTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    #[inline]
+    pub fn zeros(shape: &[Axis<IT>]) -> Self {
+        let values = ArrayD::zeros(shape.iter().map(|axis| axis.len()).collect::<Vec<_>>());
+        Self {
+            inner: DenseTensorInner {
+                name: None,
+                shape: Axes::from(shape),
+                values,
+            },
+        }
+    }
+
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.inner.name.as_deref()
+    }
+
+    #[inline]
+    fn name_mut(&mut self) -> &mut Option<String> {
+        &mut self.inner.name
+    }
+
+    #[inline]
+    pub fn values(&self) -> &ArrayD<VT> {
+        &self.inner.values
+    }
+
+    /// The values can be changed, but not the shape.
+    #[inline]
+    pub fn values_mut(&mut self) -> ndarray::ArrayViewMutD<'_, VT> {
+        self.inner.values.view_mut()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> DenseIter<'_, IT, VT> {
+        DenseIter::new(self)
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> DenseIterMut<'_, IT, VT> {
+        DenseIterMut::new(self)
+    }
+}
+
+impl<IT, VT> Tensor<IT, VT> for DenseTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.name()
+    }
+
+    #[inline]
+    fn name_mut(&mut self) -> &mut Option<String> {
+        self.name_mut()
+    }
+
+    #[inline]
+    fn num_non_zeros(&self) -> usize {
+        self.inner.values.len()
+    }
+
+    #[inline]
+    fn shape(&self) -> &[Axis<IT>] {
+        &self.inner.shape
+    }
+}
+
+impl<IT, VT> RawParts for DenseTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    type Inner = DenseTensorInner<IT, VT>;
+
+    #[inline]
+    unsafe fn from_raw_parts(raw_parts: Self::Inner) -> Self {
+        Self { inner: raw_parts }
+    }
+
+    #[inline]
+    fn into_raw_parts(self) -> Self::Inner {
+        self.inner
+    }
+
+    #[inline]
+    fn raw_parts(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    #[inline]
+    unsafe fn raw_parts_mut(&mut self) -> &mut Self::Inner {
+        &mut self.inner
+    }
+}
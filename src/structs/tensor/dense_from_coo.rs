@@ -0,0 +1,213 @@
+use super::{COOTensor, COOTensorInner, COOTensorView, DenseTensor, DenseTensorInner};
+use crate::structs::axis::{map_axes_unwrap, Axes, AxisBuilder};
+use crate::traits::{IdxType, RawParts, Tensor, ValType};
+use ndarray::{Array2, ArrayBase, ArrayView2, Data, Dimension, RawData};
+use num::NumCast;
+use streaming_iterator::StreamingIterator;
+
+impl<IT, VT> DenseTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Create a new `DenseTensor` from a [`ndarray::Array`].
+    ///
+    /// Each axis is a new axis counting from 0.
+    /// The values are copied into standard layout if `array` is in any other layout.
+    #[inline]
+    pub fn from_ndarray<S, D>(array: ArrayBase<S, D>) -> Self
+    where
+        S: Data + RawData<Elem = VT>,
+        D: Dimension,
+    {
+        let shape = array
+            .shape()
+            .iter()
+            .map(|&x| {
+                AxisBuilder::new()
+                    .range(IT::zero()..<IT as NumCast>::from(x).unwrap())
+                    .build()
+            })
+            .collect::<Axes<_>>();
+        let values = if array.is_standard_layout() {
+            array.into_owned()
+        } else {
+            array.as_standard_layout().into_owned()
+        };
+        let raw_parts = DenseTensorInner {
+            name: None,
+            shape,
+            values: values.into_dyn(),
+        };
+        // # Safety
+        // The shape is created from the array.
+        unsafe { Self::from_raw_parts(raw_parts) }
+    }
+
+    /// Create a new `DenseTensor` from a [`COOTensor`] with the same shape.
+    ///
+    /// Elements not stored in `tensor` are zero, duplicate elements are summed up.
+    pub fn from_coo(tensor: &COOTensor<IT, VT>) -> Self {
+        if tensor.sparse_axes().is_empty() && tensor.num_blocks() == 1 {
+            // Fast path: the only block is the whole tensor.
+            return Self::from(tensor.clone());
+        }
+
+        let mut result = Self::zeros(tensor.shape());
+        *result.name_mut() = tensor.name().map(str::to_owned);
+        let shape = tensor.shape();
+        let mut dense_index = vec![0; shape.len()];
+        let mut result_values = result.values_mut();
+        let mut tensor_iter = tensor.iter();
+        while let Some(&(index, value)) = tensor_iter.next() {
+            for ((dense_index, &index), axis) in dense_index.iter_mut().zip(index).zip(shape) {
+                *dense_index = (index - axis.lower()).to_usize().unwrap();
+            }
+            let element = &mut result_values[dense_index.as_slice()];
+            *element = element.clone() + value.clone();
+        }
+        result
+    }
+
+    /// Borrow the tensor as a [`COOTensorView`] without sparse axes.
+    ///
+    /// Algorithms accepting a `COOTensorView` can read a `DenseTensor` without copying.
+    #[inline]
+    pub fn coo_view(&self) -> COOTensorView<'_, IT, VT> {
+        let raw_parts = self.raw_parts();
+        // # Safety
+        // One block with an empty sparse index, holding every element.
+        unsafe {
+            COOTensorView::from_parts(
+                raw_parts.name.as_deref(),
+                &raw_parts.shape,
+                &[],
+                &raw_parts.shape,
+                ArrayView2::from_shape((1, 0), &[]).unwrap(),
+                raw_parts.values.view().insert_axis(ndarray::Axis(0)),
+                Some(&[]),
+                true,
+            )
+        }
+    }
+}
+
+impl<IT, VT> COOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Create a new dense `COOTensor` from a [`DenseTensor`].
+    ///
+    /// The result has no sparse axes, and its dense axes are in the order of the shape.
+    #[inline]
+    pub fn from_dense(tensor: &DenseTensor<IT, VT>) -> Self {
+        Self::from(tensor.clone())
+    }
+}
+
+impl<S, D, IT> From<ArrayBase<S, D>> for DenseTensor<IT, S::Elem>
+where
+    S: Data,
+    D: Dimension,
+    IT: IdxType,
+    S::Elem: ValType,
+{
+    #[inline]
+    fn from(array: ArrayBase<S, D>) -> Self {
+        Self::from_ndarray(array)
+    }
+}
+
+/// Move the values into a `COOTensor` without copying.
+impl<IT, VT> From<DenseTensor<IT, VT>> for COOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    fn from(tensor: DenseTensor<IT, VT>) -> Self {
+        let DenseTensorInner {
+            name,
+            shape,
+            values,
+        } = tensor.into_raw_parts();
+        let raw_parts = COOTensorInner {
+            name,
+            dense_axes: shape.clone(),
+            shape,
+            sparse_axes: Axes::new(),
+            indices: Array2::zeros((1, 0)),
+            values: values.insert_axis(ndarray::Axis(0)),
+            sparse_is_sorted: true,
+            sparse_sort_order: Axes::new(),
+            sparse_is_coalesced: true,
+        };
+        // # Safety
+        // One block with an empty sparse index, holding every element.
+        unsafe { Self::from_raw_parts(raw_parts) }
+    }
+}
+
+impl<IT, VT> From<&DenseTensor<IT, VT>> for COOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    #[inline]
+    fn from(tensor: &DenseTensor<IT, VT>) -> Self {
+        Self::from_dense(tensor)
+    }
+}
+
+/// Move the values into a `DenseTensor`.
+///
+/// If `tensor` has no sparse axes and exactly one block, and its dense axes are in the order of the shape,
+/// the values are moved without copying. Otherwise they are copied, in standard layout.
+impl<IT, VT> From<COOTensor<IT, VT>> for DenseTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    fn from(tensor: COOTensor<IT, VT>) -> Self {
+        if !tensor.sparse_axes().is_empty() || tensor.num_blocks() != 1 {
+            return Self::from_coo(&tensor);
+        }
+
+        let COOTensorInner {
+            name,
+            shape,
+            dense_axes,
+            values,
+            ..
+        } = tensor.into_raw_parts();
+        let permutation = map_axes_unwrap(&shape, &dense_axes).collect::<Vec<_>>();
+        let values = values
+            .index_axis_move(ndarray::Axis(0), 0)
+            .permuted_axes(permutation);
+        // Keep the values in standard layout, other algorithms reshape them.
+        let values = if values.is_standard_layout() {
+            values
+        } else {
+            values.as_standard_layout().into_owned()
+        };
+        let raw_parts = DenseTensorInner {
+            name,
+            shape,
+            values,
+        };
+        // # Safety
+        // The array axes are permuted into the order of the shape, in standard layout.
+        unsafe { Self::from_raw_parts(raw_parts) }
+    }
+}
+
+impl<'a, IT, VT> From<&'a DenseTensor<IT, VT>> for COOTensorView<'a, IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    #[inline]
+    fn from(tensor: &'a DenseTensor<IT, VT>) -> Self {
+        tensor.coo_view()
+    }
+}
@@ -0,0 +1,203 @@
+use super::DenseTensor;
+use crate::structs::axis::Axis;
+use crate::structs::vec::{smallvec, SmallVec};
+use crate::traits::{IdxType, RawParts, ValType};
+use ndarray::{ArrayD, Ix};
+use num::NumCast;
+use streaming_iterator::{StreamingIterator, StreamingIteratorMut};
+
+/// Iterator for [`DenseTensor`].
+///
+/// Elements are visited in lexicographical order of their indices.
+pub struct DenseIter<'a, IT, VT>
+where
+    IT: 'a + IdxType,
+    VT: 'a + ValType,
+{
+    values: &'a ArrayD<VT>,
+    cursor: DenseCursor<'a, IT>,
+    result_buffer: Option<(&'a [IT], &'a VT)>,
+}
+
+/// Mutable iterator for [`DenseTensor`].
+///
+/// Elements are visited in lexicographical order of their indices.
+pub struct DenseIterMut<'a, IT, VT>
+where
+    IT: 'a + IdxType,
+    VT: 'a + ValType,
+{
+    values: &'a mut ArrayD<VT>,
+    cursor: DenseCursor<'a, IT>,
+    result_buffer: Option<(&'a [IT], &'a mut VT)>,
+}
+
+/// The position shared by [`DenseIter`] and [`DenseIterMut`].
+struct DenseCursor<'a, IT>
+where
+    IT: 'a + IdxType,
+{
+    shape: &'a [Axis<IT>],
+    started: bool,
+    dense_index_buffer: SmallVec<Ix>,
+    logic_index_buffer: SmallVec<IT>,
+}
+
+impl<'a, IT> DenseCursor<'a, IT>
+where
+    IT: 'a + IdxType,
+{
+    #[inline]
+    fn new(shape: &'a [Axis<IT>]) -> Self {
+        Self {
+            shape,
+            started: false,
+            dense_index_buffer: smallvec![0; shape.len()],
+            logic_index_buffer: shape.iter().map(|axis| axis.lower()).collect(),
+        }
+    }
+
+    /// Move to the next element, returns `false` if there are no more elements.
+    #[inline]
+    fn advance(&mut self) -> bool {
+        if !self.started {
+            self.started = true;
+            return self.shape.iter().all(|axis| !axis.is_empty());
+        }
+        for (axis_idx, axis) in self.shape.iter().enumerate().rev() {
+            self.dense_index_buffer[axis_idx] += 1;
+            if self.dense_index_buffer[axis_idx] < axis.len() {
+                // Checking overflow, since we are converting usize into IT
+                self.logic_index_buffer[axis_idx] = axis.lower()
+                    + <IT as NumCast>::from(self.dense_index_buffer[axis_idx]).unwrap();
+                return true;
+            }
+            self.dense_index_buffer[axis_idx] = 0;
+            self.logic_index_buffer[axis_idx] = axis.lower();
+        }
+        false
+    }
+}
+
+impl<'a, IT, VT> DenseIter<'a, IT, VT>
+where
+    IT: 'a + IdxType,
+    VT: 'a + ValType,
+{
+    #[inline]
+    pub(super) fn new(tensor: &'a DenseTensor<IT, VT>) -> Self {
+        let raw_parts = tensor.raw_parts();
+        Self {
+            values: &raw_parts.values,
+            cursor: DenseCursor::new(&raw_parts.shape),
+            result_buffer: None,
+        }
+    }
+}
+
+impl<'a, IT, VT> StreamingIterator for DenseIter<'a, IT, VT>
+where
+    IT: 'a + IdxType,
+    VT: 'a + ValType,
+{
+    type Item = (&'a [IT], &'a VT);
+
+    #[inline]
+    fn advance(&mut self) {
+        if !self.cursor.advance() {
+            self.result_buffer = None;
+            return;
+        }
+        let result: (&[IT], &VT) = (
+            &self.cursor.logic_index_buffer,
+            &self.values[self.cursor.dense_index_buffer.as_slice()],
+        );
+        // # Safety
+        // The index buffer lives as long as the iterator, and is only changed by the next `advance`.
+        self.result_buffer = unsafe {
+            Some((
+                (result.0 as *const [IT]).as_ref::<'a>().unwrap_unchecked(),
+                (result.1 as *const VT).as_ref::<'a>().unwrap_unchecked(),
+            ))
+        };
+    }
+
+    #[inline]
+    fn get(&self) -> Option<&Self::Item> {
+        self.result_buffer.as_ref()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let size_hint = self.values.len();
+        (size_hint, Some(size_hint))
+    }
+}
+
+impl<'a, IT, VT> DenseIterMut<'a, IT, VT>
+where
+    IT: 'a + IdxType,
+    VT: 'a + ValType,
+{
+    #[inline]
+    pub(super) fn new(tensor: &'a mut DenseTensor<IT, VT>) -> Self {
+        // # Safety
+        // The iterator only changes the values, not the shape.
+        let raw_parts = unsafe { tensor.raw_parts_mut() };
+        Self {
+            values: &mut raw_parts.values,
+            cursor: DenseCursor::new(&raw_parts.shape),
+            result_buffer: None,
+        }
+    }
+}
+
+impl<'a, IT, VT> StreamingIterator for DenseIterMut<'a, IT, VT>
+where
+    IT: 'a + IdxType,
+    VT: 'a + ValType,
+{
+    type Item = (&'a [IT], &'a mut VT);
+
+    #[inline]
+    fn advance(&mut self) {
+        if !self.cursor.advance() {
+            self.result_buffer = None;
+            return;
+        }
+        let result: (&[IT], &mut VT) = (
+            &self.cursor.logic_index_buffer,
+            &mut self.values[self.cursor.dense_index_buffer.as_slice()],
+        );
+        // # Safety
+        // Each element is visited only once, so no two mutable references alias.
+        self.result_buffer = unsafe {
+            Some((
+                (result.0 as *const [IT]).as_ref::<'a>().unwrap_unchecked(),
+                (result.1 as *mut VT).as_mut::<'a>().unwrap_unchecked(),
+            ))
+        };
+    }
+
+    #[inline]
+    fn get(&self) -> Option<&Self::Item> {
+        self.result_buffer.as_ref()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let size_hint = self.values.len();
+        (size_hint, Some(size_hint))
+    }
+}
+
+impl<'a, IT, VT> StreamingIteratorMut for DenseIterMut<'a, IT, VT>
+where
+    IT: 'a + IdxType,
+    VT: 'a + ValType,
+{
+    #[inline]
+    fn get_mut(&mut self) -> Option<&mut Self::Item> {
+        self.result_buffer.as_mut()
+    }
+}
@@ -0,0 +1,251 @@
+use super::{COOTensor, COOTensorInner};
+use crate::structs::axis::{Axes, Axis};
+use crate::traits::{IdxType, RawParts, Tensor, ValType};
+use ndarray::{Array2, ArrayD, IxDyn};
+use num::NumCast;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use streaming_iterator::StreamingIterator;
+
+/// A DOK (Dictionary Of Keys) format tensor.
+///
+/// Each element is stored in a hash map, keyed by its index packed into a single integer.
+/// Inserting, updating and removing an element takes constant time on average,
+/// so a `DOKTensor` is suitable for building a tensor element by element.
+/// Use [`DOKTensor::freeze`] to convert it into a [`COOTensor`] for algorithms.
+///
+/// All axes are sparse. The product of the axis lengths must fit in a `u128`.
+#[derive(Clone, Debug)]
+pub struct DOKTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    inner: DOKTensorInner<IT, VT>,
+}
+
+/// The inner representation of a `DOKTensor`.
+///
+/// This is provided to allow algorithm implementations to access the underlying data while ensuring they have the responsibility for data integrity.
+///
+/// Check [`RawParts`] for more information.
+#[derive(Clone, Debug)]
+pub struct DOKTensorInner<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    pub name: Option<String>,
+
+    /// The logical shape of this tensor, expressed as an array of axes.
+    /// Each axis contains a lower bound and an upper bound.
+    pub shape: Axes<IT>,
+
+    /// The key is the offset of the element in a row-major dense layout of `shape`.
+    pub elements: HashMap<u128, VT>,
+}
+
+impl<IT, VT> DOKTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Create an empty `DOKTensor`.
+    ///
+    /// Panics if the product of the axis lengths does not fit in a `u128`.
+    pub fn new(shape: &[Axis<IT>]) -> Self {
+        assert!(
+            shape
+                .iter()
+                .try_fold(1u128, |size, axis| size.checked_mul(axis.len() as u128))
+                .is_some(),
+            "the tensor is too large to be packed into a u128"
+        );
+        Self {
+            inner: DOKTensorInner {
+                name: None,
+                shape: Axes::from(shape),
+                elements: HashMap::new(),
+            },
+        }
+    }
+
+    /// Create a new `DOKTensor` from a [`COOTensor`], duplicate elements are summed up.
+    pub fn from_coo(tensor: &COOTensor<IT, VT>) -> Self {
+        let mut result = Self::new(tensor.shape());
+        result.inner.name = tensor.name().map(str::to_owned);
+        result.inner.elements.reserve(tensor.num_non_zeros());
+        let mut tensor_iter = tensor.iter();
+        while let Some(&(index, value)) = tensor_iter.next() {
+            result.update(index, |element| *element = element.clone() + value.clone());
+        }
+        result
+    }
+
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.inner.name.as_deref()
+    }
+
+    #[inline]
+    fn name_mut(&mut self) -> &mut Option<String> {
+        &mut self.inner.name
+    }
+
+    /// The value at `index`, or `None` if it is not stored.
+    ///
+    /// Panics if `index` is out of bound.
+    #[inline]
+    pub fn get(&self, index: &[IT]) -> Option<&VT> {
+        self.inner.elements.get(&self.pack(index))
+    }
+
+    /// Store `value` at `index`, returns the old value.
+    ///
+    /// Panics if `index` is out of bound.
+    #[inline]
+    pub fn insert(&mut self, index: &[IT], value: VT) -> Option<VT> {
+        let key = self.pack(index);
+        self.inner.elements.insert(key, value)
+    }
+
+    /// Change the value at `index` with `f`, which starts from zero if the value is not stored.
+    ///
+    /// Panics if `index` is out of bound.
+    #[inline]
+    pub fn update(&mut self, index: &[IT], f: impl FnOnce(&mut VT)) {
+        let key = self.pack(index);
+        f(self.inner.elements.entry(key).or_insert_with(VT::zero));
+    }
+
+    /// Remove the value at `index`, returns the old value.
+    ///
+    /// Panics if `index` is out of bound.
+    #[inline]
+    pub fn remove(&mut self, index: &[IT]) -> Option<VT> {
+        let key = self.pack(index);
+        self.inner.elements.remove(&key)
+    }
+
+    /// Convert into a fully sparse `COOTensor`, sorted in the order of the shape, and coalesced.
+    pub fn freeze(self) -> COOTensor<IT, VT> {
+        let DOKTensorInner {
+            name,
+            shape,
+            elements,
+        } = self.inner;
+        let mut elements = elements.into_iter().collect::<Vec<_>>();
+        elements.sort_unstable_by_key(|&(key, _)| key);
+
+        let num_blocks = elements.len();
+        let mut indices = Array2::zeros((num_blocks, shape.len()));
+        let mut values = Vec::with_capacity(num_blocks);
+        for (block, (mut key, value)) in elements.into_iter().enumerate() {
+            for (axis_idx, axis) in shape.iter().enumerate().rev() {
+                let len = axis.len() as u128;
+                indices[(block, axis_idx)] =
+                    axis.lower() + <IT as NumCast>::from(key % len).unwrap();
+                key /= len;
+            }
+            values.push(value);
+        }
+
+        let raw_parts = COOTensorInner {
+            name,
+            sparse_axes: shape.clone(),
+            sparse_sort_order: shape.clone(),
+            shape,
+            dense_axes: Axes::new(),
+            indices,
+            values: ArrayD::from_shape_vec(IxDyn(&[num_blocks]), values).unwrap(),
+            sparse_is_sorted: true,
+            sparse_is_coalesced: true,
+        };
+        // # Safety
+        // The keys are unique and sorted, which is the same order as the unpacked indices.
+        unsafe { COOTensor::from_raw_parts(raw_parts) }
+    }
+
+    /// Pack `index` into the key of `elements`.
+    fn pack(&self, index: &[IT]) -> u128 {
+        let shape = &self.inner.shape;
+        assert_eq!(index.len(), shape.len());
+        let mut key = 0u128;
+        for (&index, axis) in index.iter().zip(shape.iter()) {
+            assert!(
+                axis.range().contains(&index),
+                "index {} is out of bound for axis {}",
+                index,
+                axis
+            );
+            let offset = (index - axis.lower()).to_u128().unwrap();
+            key = key * axis.len() as u128 + offset;
+        }
+        key
+    }
+}
+
+impl<IT, VT> Tensor<IT, VT> for DOKTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.name()
+    }
+
+    #[inline]
+    fn name_mut(&mut self) -> &mut Option<String> {
+        self.name_mut()
+    }
+
+    #[inline]
+    fn num_non_zeros(&self) -> usize {
+        self.inner.elements.len()
+    }
+
+    #[inline]
+    fn shape(&self) -> &[Axis<IT>] {
+        &self.inner.shape
+    }
+}
+
+impl<IT, VT> RawParts for DOKTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    type Inner = DOKTensorInner<IT, VT>;
+
+    #[inline]
+    unsafe fn from_raw_parts(raw_parts: Self::Inner) -> Self {
+        Self { inner: raw_parts }
+    }
+
+    #[inline]
+    fn into_raw_parts(self) -> Self::Inner {
+        self.inner
+    }
+
+    #[inline]
+    fn raw_parts(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    #[inline]
+    unsafe fn raw_parts_mut(&mut self) -> &mut Self::Inner {
+        &mut self.inner
+    }
+}
+
+impl<IT, VT> From<DOKTensor<IT, VT>> for COOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    #[inline]
+    fn from(tensor: DOKTensor<IT, VT>) -> Self {
+        tensor.freeze()
+    }
+}
@@ -0,0 +1,165 @@
+use super::hicoo_iter::HiCOOIter;
+use crate::structs::axis::{Axes, Axis};
+use crate::traits::{IdxType, RawParts, Tensor, ValType};
+use ndarray::{Array1, Array2};
+use std::fmt::Debug;
+use std::ops::Range;
+
+/// A HiCOO (Hierarchical COO) format tensor.
+///
+/// Non-zero elements are grouped into small cubic blocks of `2^block_bits` along each axis.
+/// The index of each block is stored once, while each element only stores a narrow [`u8`] offset inside its block.
+/// Blocks are further grouped into superblocks of `2^superblock_bits` along each axis, which is the unit of parallel scheduling.
+///
+/// All axes of a `HiCOOTensor` are sparse.
+#[derive(Clone, Debug)]
+pub struct HiCOOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    inner: HiCOOTensorInner<IT, VT>,
+}
+
+/// The inner representation of a `HiCOOTensor`.
+///
+/// This is provided to allow algorithm implementations to access the underlying data while ensuring they have the responsibility for data integrity.
+///
+/// Check [`RawParts`] for more information.
+#[derive(Clone, Debug)]
+pub struct HiCOOTensorInner<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    pub name: Option<String>,
+
+    /// The logical shape of this tensor, expressed as an array of axes.
+    /// Each axis contains a lower bound and an upper bound.
+    pub shape: Axes<IT>,
+
+    /// Each block spans `2^block_bits` indices along each axis.
+    pub block_bits: u32,
+    /// Each superblock spans `2^superblock_bits` indices along each axis.
+    pub superblock_bits: u32,
+
+    /// The blocks of superblock `i` are `superblock_offsets[i]..superblock_offsets[i + 1]`.
+    pub superblock_offsets: Vec<usize>,
+    /// The elements of block `i` are `block_offsets[i]..block_offsets[i + 1]`.
+    pub block_offsets: Vec<usize>,
+    /// First index is each block, second index is each axis.
+    /// The value is `(index - axis.lower()) >> block_bits`.
+    pub block_indices: Array2<IT>,
+    /// First index is each non-zero element, second index is each axis.
+    /// The value is `(index - axis.lower())` modulo `2^block_bits`.
+    pub element_indices: Array2<u8>,
+    /// The value of each non-zero element.
+    pub values: Array1<VT>,
+}
+
+impl<IT, VT> HiCOOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.inner.name.as_deref()
+    }
+
+    #[inline]
+    fn name_mut(&mut self) -> &mut Option<String> {
+        &mut self.inner.name
+    }
+
+    #[inline]
+    pub fn block_bits(&self) -> u32 {
+        self.inner.block_bits
+    }
+
+    #[inline]
+    pub fn superblock_bits(&self) -> u32 {
+        self.inner.superblock_bits
+    }
+
+    #[inline]
+    pub fn num_blocks(&self) -> usize {
+        self.inner.block_offsets.len() - 1
+    }
+
+    #[inline]
+    pub fn num_superblocks(&self) -> usize {
+        self.inner.superblock_offsets.len() - 1
+    }
+
+    /// The range of elements inside block `block`.
+    #[inline]
+    pub fn block_range(&self, block: usize) -> Range<usize> {
+        self.inner.block_offsets[block]..self.inner.block_offsets[block + 1]
+    }
+
+    /// The range of blocks inside superblock `superblock`.
+    #[inline]
+    pub fn superblock_range(&self, superblock: usize) -> Range<usize> {
+        self.inner.superblock_offsets[superblock]..self.inner.superblock_offsets[superblock + 1]
+    }
+
+    #[inline]
+    pub fn iter(&self) -> HiCOOIter<'_, IT, VT> {
+        HiCOOIter::new(self)
+    }
+}
+
+impl<IT, VT> Tensor<IT, VT> for HiCOOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.name()
+    }
+
+    #[inline]
+    fn name_mut(&mut self) -> &mut Option<String> {
+        self.name_mut()
+    }
+
+    #[inline]
+    fn num_non_zeros(&self) -> usize {
+        self.inner.values.len()
+    }
+
+    #[inline]
+    fn shape(&self) -> &[Axis<IT>] {
+        &self.inner.shape
+    }
+}
+
+impl<IT, VT> RawParts for HiCOOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    type Inner = HiCOOTensorInner<IT, VT>;
+
+    #[inline]
+    unsafe fn from_raw_parts(raw_parts: Self::Inner) -> Self {
+        Self { inner: raw_parts }
+    }
+
+    #[inline]
+    fn into_raw_parts(self) -> Self::Inner {
+        self.inner
+    }
+
+    #[inline]
+    fn raw_parts(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    #[inline]
+    unsafe fn raw_parts_mut(&mut self) -> &mut Self::Inner {
+        &mut self.inner
+    }
+}
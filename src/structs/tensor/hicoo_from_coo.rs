@@ -0,0 +1,176 @@
+use super::coo::{COOTensor, COOTensorInner};
+use super::hicoo::{HiCOOTensor, HiCOOTensorInner};
+use crate::structs::axis::Axes;
+use crate::traits::{IdxType, RawParts, Tensor, ValType};
+use ndarray::{Array1, Array2, Ix1};
+use num::NumCast;
+use std::cmp::Ordering;
+use streaming_iterator::StreamingIterator;
+
+impl<IT, VT> HiCOOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Create a new `HiCOOTensor` from a fully sparse [`COOTensor`].
+    ///
+    /// Each block spans `2^block_bits` indices along each axis, and `block_bits` must not exceed 8.
+    /// Each superblock spans `2^superblock_bits` indices along each axis, and `superblock_bits` must not be less than `block_bits`,
+    /// and must be less than `usize::BITS`.
+    ///
+    /// # Allocation
+    /// This function requires `O(n)` auxiliary memory, where `n` is the number of non-zero elements.
+    pub fn from_coo(tensor: &COOTensor<IT, VT>, block_bits: u32, superblock_bits: u32) -> Self {
+        assert!(
+            tensor.dense_axes().is_empty(),
+            "The tensor must be fully sparse."
+        );
+        assert!(block_bits <= u8::BITS);
+        assert!(superblock_bits >= block_bits);
+        assert!(superblock_bits < usize::BITS);
+
+        let raw_parts = tensor.raw_parts();
+        let shape = raw_parts.shape.clone();
+        let ndim = shape.len();
+        let num_non_zeros = raw_parts.indices.nrows();
+
+        // Offsets of each element from the lower bound of each axis, in the order of `shape`.
+        let mut offsets = Array2::<usize>::zeros((num_non_zeros, ndim));
+        for (sparse_idx, axis) in raw_parts.sparse_axes.iter().enumerate() {
+            let axis_idx = shape.iter().position(|ax| ax == axis).unwrap();
+            for (offset, &index) in offsets
+                .column_mut(axis_idx)
+                .iter_mut()
+                .zip(raw_parts.indices.column(sparse_idx).iter())
+            {
+                *offset = (index - axis.lower()).to_usize().unwrap();
+            }
+        }
+
+        // Sort elements by superblock, then by block, then by element.
+        let mut order = (0..num_non_zeros).collect::<Vec<_>>();
+        order.sort_unstable_by(|&a, &b| {
+            let a = offsets.row(a);
+            let b = offsets.row(b);
+            let cmp_shifted = |bits: u32| {
+                a.iter()
+                    .zip(b.iter())
+                    .map(|(&a, &b)| (a >> bits).cmp(&(b >> bits)))
+                    .find(|&ord| ord != Ordering::Equal)
+                    .unwrap_or(Ordering::Equal)
+            };
+            cmp_shifted(superblock_bits)
+                .then_with(|| cmp_shifted(block_bits))
+                .then_with(|| cmp_shifted(0))
+        });
+
+        let element_mask = (1usize << block_bits) - 1;
+        let mut superblock_offsets = Vec::new();
+        let mut block_offsets = Vec::new();
+        let mut block_indices = Vec::new();
+        let mut element_indices = Vec::with_capacity(num_non_zeros * ndim);
+        let mut last_element: Option<usize> = None;
+        for (i, &element) in order.iter().enumerate() {
+            let row = offsets.row(element);
+            let same_shifted = |bits: u32| {
+                last_element
+                    .map(|last| {
+                        row.iter()
+                            .zip(offsets.row(last).iter())
+                            .all(|(&a, &b)| a >> bits == b >> bits)
+                    })
+                    .unwrap_or(false)
+            };
+            if !same_shifted(superblock_bits) {
+                superblock_offsets.push(block_offsets.len());
+            }
+            if !same_shifted(block_bits) {
+                block_offsets.push(i);
+                block_indices.extend(
+                    row.iter()
+                        .map(|&offset| <IT as NumCast>::from(offset >> block_bits).unwrap()),
+                );
+            }
+            element_indices.extend(row.iter().map(|&offset| (offset & element_mask) as u8));
+            last_element = Some(element);
+        }
+        superblock_offsets.push(block_offsets.len());
+        block_offsets.push(num_non_zeros);
+
+        let values = raw_parts
+            .values
+            .view()
+            .into_dimensionality::<Ix1>()
+            .unwrap();
+        let values = order
+            .iter()
+            .map(|&element| values[element].clone())
+            .collect::<Array1<_>>();
+
+        let raw_parts = HiCOOTensorInner {
+            name: raw_parts.name.clone(),
+            shape,
+            block_bits,
+            superblock_bits,
+            superblock_offsets,
+            block_indices: Array2::from_shape_vec((block_offsets.len() - 1, ndim), block_indices)
+                .unwrap(),
+            block_offsets,
+            element_indices: Array2::from_shape_vec((num_non_zeros, ndim), element_indices)
+                .unwrap(),
+            values,
+        };
+        // # Safety
+        // Every element is assigned to exactly one block and one superblock.
+        unsafe { Self::from_raw_parts(raw_parts) }
+    }
+}
+
+impl<IT, VT> COOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    /// Create a new fully sparse `COOTensor` from a [`HiCOOTensor`].
+    ///
+    /// Elements are kept in the storage order of the `HiCOOTensor`, therefore the result is not sorted.
+    pub fn from_hicoo(tensor: &HiCOOTensor<IT, VT>) -> Self {
+        let shape = tensor.raw_parts().shape.clone();
+        let mut indices = Array2::zeros((tensor.num_non_zeros(), shape.len()));
+        let mut tensor_iter = tensor.iter();
+        let mut rows = indices.rows_mut().into_iter();
+        while let Some(&(index, _)) = tensor_iter.next() {
+            rows.next()
+                .unwrap()
+                .iter_mut()
+                .zip(index.iter())
+                .for_each(|(dst, &src)| *dst = src);
+        }
+
+        let raw_parts = COOTensorInner {
+            name: tensor.raw_parts().name.clone(),
+            shape: shape.clone(),
+            sparse_axes: shape.clone(),
+            dense_axes: Axes::new(),
+            indices,
+            values: tensor.raw_parts().values.clone().into_dyn(),
+            sparse_is_sorted: false,
+            sparse_sort_order: shape,
+            sparse_is_coalesced: false,
+        };
+        // # Safety
+        // Each row of `indices` is the index of each element in `values`.
+        unsafe { Self::from_raw_parts(raw_parts) }
+    }
+}
+
+impl<IT, VT> From<&HiCOOTensor<IT, VT>> for COOTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    #[inline]
+    fn from(tensor: &HiCOOTensor<IT, VT>) -> Self {
+        Self::from_hicoo(tensor)
+    }
+}
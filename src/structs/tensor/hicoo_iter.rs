@@ -0,0 +1,114 @@
+use super::HiCOOTensor;
+use crate::structs::axis::Axis;
+use crate::structs::vec::{smallvec, SmallVec};
+use crate::traits::{IdxType, RawParts, ValType};
+use ndarray::{Array1, Array2};
+use num::NumCast;
+use streaming_iterator::StreamingIterator;
+
+/// Iterator for [`HiCOOTensor`].
+///
+/// Elements are visited in storage order, that is, block by block.
+pub struct HiCOOIter<'a, IT, VT>
+where
+    IT: 'a + IdxType,
+    VT: 'a + ValType,
+{
+    shape: &'a [Axis<IT>],
+    block_bits: u32,
+    block_offsets: &'a [usize],
+    block_indices: &'a Array2<IT>,
+    element_indices: &'a Array2<u8>,
+    values: &'a Array1<VT>,
+
+    block: usize,
+    element: usize,
+    logic_index_buffer: SmallVec<IT>,
+    result_buffer: Option<(&'a [IT], &'a VT)>,
+}
+
+impl<'a, IT, VT> HiCOOIter<'a, IT, VT>
+where
+    IT: 'a + IdxType,
+    VT: 'a + ValType,
+{
+    #[inline]
+    pub(super) fn new(tensor: &'a HiCOOTensor<IT, VT>) -> Self {
+        let raw_parts = tensor.raw_parts();
+        let shape = raw_parts.shape.as_slice();
+
+        Self {
+            shape,
+            block_bits: raw_parts.block_bits,
+            block_offsets: &raw_parts.block_offsets,
+            block_indices: &raw_parts.block_indices,
+            element_indices: &raw_parts.element_indices,
+            values: &raw_parts.values,
+            block: 0,
+            element: 0,
+            logic_index_buffer: smallvec![IT::zero(); shape.len()],
+            result_buffer: None,
+        }
+    }
+
+    #[inline]
+    fn calc_result(&mut self) -> (&'a [IT], &'a VT) {
+        for (axis_idx, axis) in self.shape.iter().enumerate() {
+            let block_index = self.block_indices[(self.block, axis_idx)]
+                .to_usize()
+                .unwrap();
+            let element_index = self.element_indices[(self.element, axis_idx)] as usize;
+            let offset =
+                <IT as NumCast>::from((block_index << self.block_bits) + element_index).unwrap();
+            self.logic_index_buffer[axis_idx] = axis.lower() + offset;
+        }
+
+        let result: (&[IT], &VT) = (&self.logic_index_buffer, &self.values[self.element]);
+        // # Safety
+        // Same as `COOIter`, the buffer lives as long as the iterator.
+        unsafe {
+            (
+                (result.0 as *const [IT]).as_ref::<'a>().unwrap_unchecked(),
+                (result.1 as *const VT).as_ref::<'a>().unwrap_unchecked(),
+            )
+        }
+    }
+}
+
+impl<'a, IT, VT> StreamingIterator for HiCOOIter<'a, IT, VT>
+where
+    IT: 'a + IdxType,
+    VT: 'a + ValType,
+{
+    type Item = (&'a [IT], &'a VT);
+
+    #[inline]
+    fn advance(&mut self) {
+        if self.result_buffer.is_some() {
+            self.element += 1;
+        } else {
+            self.block = 0;
+            self.element = 0;
+        }
+        if self.element >= self.values.len() {
+            self.result_buffer = None;
+            return;
+        }
+        // Blocks are never empty, so we move at most one block forward.
+        if self.element >= self.block_offsets[self.block + 1] {
+            self.block += 1;
+        }
+        self.result_buffer = Some(self.calc_result());
+    }
+
+    #[inline]
+    fn get(&self) -> Option<&Self::Item> {
+        self.result_buffer.as_ref()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let size_hint = self.values.len();
+        (size_hint, Some(size_hint))
+    }
+}
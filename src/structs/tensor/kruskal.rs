@@ -0,0 +1,151 @@
+use super::COOTensor;
+use crate::structs::axis::{Axes, Axis};
+use crate::traits::{IdxType, RawParts, Tensor, ValType};
+use ndarray::Array1;
+use std::fmt::Debug;
+
+/// A Kruskal tensor, the result of a CANDECOMP/PARAFAC decomposition.
+///
+/// The tensor is represented as a weighted sum of rank-one tensors.
+/// Each axis of the tensor has a factor matrix, which is a dense `COOTensor` with two axes:
+/// the first one is the axis of the tensor, the second one is the rank axis shared by all factors.
+#[derive(Clone, Debug)]
+pub struct KruskalTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    inner: KruskalTensorInner<IT, VT>,
+}
+
+/// The inner representation of a `KruskalTensor`.
+///
+/// This is provided to allow algorithm implementations to access the underlying data while ensuring they have the responsibility for data integrity.
+///
+/// Check [`RawParts`] for more information.
+#[derive(Clone, Debug)]
+pub struct KruskalTensorInner<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    pub name: Option<String>,
+
+    /// The logical shape of this tensor, expressed as an array of axes.
+    /// Each axis contains a lower bound and an upper bound.
+    pub shape: Axes<IT>,
+
+    /// The axis enumerating the rank-one components.
+    pub rank_axis: Axis<IT>,
+    /// The weight of each rank-one component.
+    pub weights: Array1<VT>,
+    /// The factor matrix of each axis, in the same order as `shape`.
+    pub factors: Vec<COOTensor<IT, VT>>,
+}
+
+impl<IT, VT> KruskalTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.inner.name.as_deref()
+    }
+
+    #[inline]
+    fn name_mut(&mut self) -> &mut Option<String> {
+        &mut self.inner.name
+    }
+
+    #[inline]
+    pub fn rank_axis(&self) -> &Axis<IT> {
+        &self.inner.rank_axis
+    }
+
+    /// The number of rank-one components.
+    #[inline]
+    pub fn rank(&self) -> usize {
+        self.inner.rank_axis.len()
+    }
+
+    #[inline]
+    pub fn weights(&self) -> &Array1<VT> {
+        &self.inner.weights
+    }
+
+    #[inline]
+    pub fn factors(&self) -> &[COOTensor<IT, VT>] {
+        &self.inner.factors
+    }
+
+    /// The factor matrix of `axis`, or `None` if `axis` is not an axis of the tensor.
+    #[inline]
+    pub fn factor(&self, axis: &Axis<IT>) -> Option<&COOTensor<IT, VT>> {
+        self.inner
+            .shape
+            .iter()
+            .position(|ax| ax == axis)
+            .map(|axis_idx| &self.inner.factors[axis_idx])
+    }
+}
+
+impl<IT, VT> Tensor<IT, VT> for KruskalTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.name()
+    }
+
+    #[inline]
+    fn name_mut(&mut self) -> &mut Option<String> {
+        self.name_mut()
+    }
+
+    #[inline]
+    fn num_non_zeros(&self) -> usize {
+        self.inner.weights.len()
+            + self
+                .inner
+                .factors
+                .iter()
+                .map(|factor| factor.num_non_zeros())
+                .sum::<usize>()
+    }
+
+    #[inline]
+    fn shape(&self) -> &[Axis<IT>] {
+        &self.inner.shape
+    }
+}
+
+impl<IT, VT> RawParts for KruskalTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    type Inner = KruskalTensorInner<IT, VT>;
+
+    #[inline]
+    unsafe fn from_raw_parts(raw_parts: Self::Inner) -> Self {
+        Self { inner: raw_parts }
+    }
+
+    #[inline]
+    fn into_raw_parts(self) -> Self::Inner {
+        self.inner
+    }
+
+    #[inline]
+    fn raw_parts(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    #[inline]
+    unsafe fn raw_parts_mut(&mut self) -> &mut Self::Inner {
+        &mut self.inner
+    }
+}
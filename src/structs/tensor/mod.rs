@@ -2,9 +2,34 @@
 
 mod coo;
 mod coo_from_ndarray;
+mod coo_get;
 mod coo_iter;
 mod coo_iter_mut;
+mod coo_ops;
+mod coo_view;
+mod csf;
+mod csf_from_coo;
+mod csf_iter;
+mod dense;
+mod dense_from_coo;
+mod dense_iter;
+mod dok;
+mod hicoo;
+mod hicoo_from_coo;
+mod hicoo_iter;
+mod kruskal;
+mod tucker;
 
 pub use coo::{COOTensor, COOTensorInner};
 pub use coo_iter::COOIter;
 pub use coo_iter_mut::COOIterMut;
+pub use coo_view::COOTensorView;
+pub use csf::{CSFTensor, CSFTensorInner};
+pub use csf_iter::CSFIter;
+pub use dense::{DenseTensor, DenseTensorInner};
+pub use dense_iter::{DenseIter, DenseIterMut};
+pub use dok::{DOKTensor, DOKTensorInner};
+pub use hicoo::{HiCOOTensor, HiCOOTensorInner};
+pub use hicoo_iter::HiCOOIter;
+pub use kruskal::{KruskalTensor, KruskalTensorInner};
+pub use tucker::{TuckerTensor, TuckerTensorInner};
@@ -0,0 +1,137 @@
+use super::COOTensor;
+use crate::structs::axis::{Axes, Axis};
+use crate::traits::{IdxType, RawParts, Tensor, ValType};
+use std::fmt::Debug;
+
+/// A Tucker tensor, the result of a Tucker decomposition.
+///
+/// The tensor is represented as a small dense core tensor multiplied by a factor matrix along each axis.
+/// Each factor matrix is a dense `COOTensor` with two axes:
+/// the first one is the axis of the tensor, the second one is the corresponding axis of the core tensor.
+#[derive(Clone, Debug)]
+pub struct TuckerTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    inner: TuckerTensorInner<IT, VT>,
+}
+
+/// The inner representation of a `TuckerTensor`.
+///
+/// This is provided to allow algorithm implementations to access the underlying data while ensuring they have the responsibility for data integrity.
+///
+/// Check [`RawParts`] for more information.
+#[derive(Clone, Debug)]
+pub struct TuckerTensorInner<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    pub name: Option<String>,
+
+    /// The logical shape of this tensor, expressed as an array of axes.
+    /// Each axis contains a lower bound and an upper bound.
+    pub shape: Axes<IT>,
+
+    /// The dense core tensor, its axes are in the same order as `shape`.
+    pub core: COOTensor<IT, VT>,
+    /// The factor matrix of each axis, in the same order as `shape`.
+    pub factors: Vec<COOTensor<IT, VT>>,
+}
+
+impl<IT, VT> TuckerTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.inner.name.as_deref()
+    }
+
+    #[inline]
+    fn name_mut(&mut self) -> &mut Option<String> {
+        &mut self.inner.name
+    }
+
+    #[inline]
+    pub fn core(&self) -> &COOTensor<IT, VT> {
+        &self.inner.core
+    }
+
+    #[inline]
+    pub fn factors(&self) -> &[COOTensor<IT, VT>] {
+        &self.inner.factors
+    }
+
+    /// The factor matrix of `axis`, or `None` if `axis` is not an axis of the tensor.
+    #[inline]
+    pub fn factor(&self, axis: &Axis<IT>) -> Option<&COOTensor<IT, VT>> {
+        self.inner
+            .shape
+            .iter()
+            .position(|ax| ax == axis)
+            .map(|axis_idx| &self.inner.factors[axis_idx])
+    }
+}
+
+impl<IT, VT> Tensor<IT, VT> for TuckerTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.name()
+    }
+
+    #[inline]
+    fn name_mut(&mut self) -> &mut Option<String> {
+        self.name_mut()
+    }
+
+    #[inline]
+    fn num_non_zeros(&self) -> usize {
+        self.inner.core.num_non_zeros()
+            + self
+                .inner
+                .factors
+                .iter()
+                .map(|factor| factor.num_non_zeros())
+                .sum::<usize>()
+    }
+
+    #[inline]
+    fn shape(&self) -> &[Axis<IT>] {
+        &self.inner.shape
+    }
+}
+
+impl<IT, VT> RawParts for TuckerTensor<IT, VT>
+where
+    IT: IdxType,
+    VT: ValType,
+{
+    type Inner = TuckerTensorInner<IT, VT>;
+
+    #[inline]
+    unsafe fn from_raw_parts(raw_parts: Self::Inner) -> Self {
+        Self { inner: raw_parts }
+    }
+
+    #[inline]
+    fn into_raw_parts(self) -> Self::Inner {
+        self.inner
+    }
+
+    #[inline]
+    fn raw_parts(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    #[inline]
+    unsafe fn raw_parts_mut(&mut self) -> &mut Self::Inner {
+        &mut self.inner
+    }
+}
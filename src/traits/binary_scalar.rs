@@ -0,0 +1,70 @@
+/// A scalar type that can be stored in binary tensor files.
+///
+/// Each type has a unique tag, so that a file can only be read back into the same type.
+///
+/// ```
+/// use pattie::traits::BinaryScalar;
+///
+/// let mut buf = [0; 4];
+/// 42u32.write_le(&mut buf);
+/// assert_eq!(buf, [42, 0, 0, 0]);
+/// assert_eq!(u32::read_le(&buf), 42);
+/// ```
+pub trait BinaryScalar: Copy {
+    /// The tag identifying this type in binary files.
+    const TYPE_TAG: u8;
+    /// The number of bytes of each value.
+    const SIZE: usize;
+
+    /// Write the value into `buf` in little-endian, `buf` must be `SIZE` bytes long.
+    fn write_le(self, buf: &mut [u8]);
+
+    /// Read a value from `buf` in little-endian, `buf` must be `SIZE` bytes long.
+    fn read_le(buf: &[u8]) -> Self;
+}
+
+/// A [`BinaryScalar`] that can be used in place inside a memory-mapped binary tensor file.
+///
+/// # Safety
+///
+/// Every `SIZE` bytes must be a valid value, and `SIZE` must be the size of the type.
+/// On little-endian machines, [`BinaryScalar::read_le`] must be the same as reinterpreting the bytes.
+pub unsafe trait ZeroCopyScalar: BinaryScalar {}
+
+macro_rules! impl_binary_scalar {
+    ($($ty:ty => $tag:expr),* $(,)?) => {
+        $(
+            impl BinaryScalar for $ty {
+                const TYPE_TAG: u8 = $tag;
+                const SIZE: usize = std::mem::size_of::<$ty>();
+
+                #[inline]
+                fn write_le(self, buf: &mut [u8]) {
+                    buf.copy_from_slice(&self.to_le_bytes());
+                }
+
+                #[inline]
+                fn read_le(buf: &[u8]) -> Self {
+                    Self::from_le_bytes(buf.try_into().unwrap())
+                }
+            }
+
+            // # Safety
+            // Primitive numbers have no invalid bit patterns.
+            unsafe impl ZeroCopyScalar for $ty {}
+        )*
+    };
+}
+
+impl_binary_scalar! {
+    u8 => 0x01,
+    u16 => 0x02,
+    u32 => 0x03,
+    u64 => 0x04,
+    i8 => 0x11,
+    i16 => 0x12,
+    i32 => 0x13,
+    i64 => 0x14,
+    f32 => 0x23,
+    f64 => 0x24,
+}
@@ -0,0 +1,112 @@
+use super::ValType;
+use crate::io::MatrixMarketField;
+use num::Complex;
+
+/// A scalar type that can be stored in Matrix Market files.
+///
+/// ```
+/// use pattie::io::MatrixMarketField;
+/// use pattie::traits::MatrixMarketScalar;
+///
+/// assert_eq!(f64::parse_matrix_market(MatrixMarketField::Integer, &["42"]), Some(42.0));
+/// assert_eq!(f64::parse_matrix_market(MatrixMarketField::Pattern, &[]), Some(1.0));
+/// assert_eq!(u32::parse_matrix_market(MatrixMarketField::Real, &["0.5"]), None);
+/// assert_eq!(0.5f64.format_matrix_market(), "5e-1");
+/// ```
+pub trait MatrixMarketScalar: ValType {
+    /// The field written to the file.
+    const FIELD: MatrixMarketField;
+
+    /// Parse a value from a file of `field`.
+    ///
+    /// `tokens` contains two tokens for complex files, none for pattern files, and one otherwise.
+    /// Returns `None` if the value is invalid, or the field cannot be converted into this type.
+    fn parse_matrix_market(field: MatrixMarketField, tokens: &[&str]) -> Option<Self>;
+
+    /// Format the value as the tokens of [`MatrixMarketScalar::FIELD`], separated by spaces.
+    fn format_matrix_market(&self) -> String;
+
+    /// The complex conjugate, used for hermitian files.
+    #[inline]
+    fn conj(&self) -> Self {
+        self.clone()
+    }
+}
+
+macro_rules! impl_matrix_market_real {
+    ($($ty:ty),*) => {
+        $(
+            impl MatrixMarketScalar for $ty {
+                const FIELD: MatrixMarketField = MatrixMarketField::Real;
+
+                #[inline]
+                fn parse_matrix_market(field: MatrixMarketField, tokens: &[&str]) -> Option<Self> {
+                    match field {
+                        MatrixMarketField::Real | MatrixMarketField::Integer => tokens[0].parse().ok(),
+                        MatrixMarketField::Pattern => Some(1.0),
+                        MatrixMarketField::Complex => None,
+                    }
+                }
+
+                #[inline]
+                fn format_matrix_market(&self) -> String {
+                    format!("{:e}", self)
+                }
+            }
+
+            impl MatrixMarketScalar for Complex<$ty> {
+                const FIELD: MatrixMarketField = MatrixMarketField::Complex;
+
+                #[inline]
+                fn parse_matrix_market(field: MatrixMarketField, tokens: &[&str]) -> Option<Self> {
+                    match field {
+                        MatrixMarketField::Real | MatrixMarketField::Integer => {
+                            Some(Complex::new(tokens[0].parse().ok()?, 0.0))
+                        }
+                        MatrixMarketField::Complex => {
+                            Some(Complex::new(tokens[0].parse().ok()?, tokens[1].parse().ok()?))
+                        }
+                        MatrixMarketField::Pattern => Some(Complex::new(1.0, 0.0)),
+                    }
+                }
+
+                #[inline]
+                fn format_matrix_market(&self) -> String {
+                    format!("{:e} {:e}", self.re, self.im)
+                }
+
+                #[inline]
+                fn conj(&self) -> Self {
+                    Complex::conj(self)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_matrix_market_integer {
+    ($($ty:ty),*) => {
+        $(
+            impl MatrixMarketScalar for $ty {
+                const FIELD: MatrixMarketField = MatrixMarketField::Integer;
+
+                #[inline]
+                fn parse_matrix_market(field: MatrixMarketField, tokens: &[&str]) -> Option<Self> {
+                    match field {
+                        MatrixMarketField::Integer => tokens[0].parse().ok(),
+                        MatrixMarketField::Pattern => Some(1),
+                        MatrixMarketField::Real | MatrixMarketField::Complex => None,
+                    }
+                }
+
+                #[inline]
+                fn format_matrix_market(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    };
+}
+
+impl_matrix_market_real!(f32, f64);
+impl_matrix_market_integer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
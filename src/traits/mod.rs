@@ -1,14 +1,18 @@
 //! Rust traits for scalars, matrices, tensors, iterators, etc.
 
 mod axis;
+mod binary_scalar;
 mod idxtype;
+mod matrix_market_scalar;
 mod raw_parts;
 mod tensor;
 mod tensor_iter;
 mod valtype;
 
 pub use axis::IntoAxis;
+pub use binary_scalar::{BinaryScalar, ZeroCopyScalar};
 pub use idxtype::IdxType;
+pub use matrix_market_scalar::MatrixMarketScalar;
 pub use raw_parts::RawParts;
 pub use tensor::Tensor;
 pub use tensor_iter::{TensorIntoIter, TensorIter, TensorIterMut};
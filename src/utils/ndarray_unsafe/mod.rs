@@ -18,7 +18,7 @@ use ndarray::{ArrayBase, Dimension, RawData, RawDataMut};
 /// assert_eq!(*value, 42);
 /// ```
 #[inline]
-pub fn uncheck_arr<S, D>(array: &ArrayBase<S, D>) -> UncheckedArray<S, D>
+pub fn uncheck_arr<S, D>(array: &ArrayBase<S, D>) -> UncheckedArray<'_, S, D>
 where
     S: RawData,
     D: Dimension,
@@ -38,7 +38,7 @@ where
 /// assert_eq!(array[1], 42);
 /// ```
 #[inline]
-pub fn uncheck_arr_mut<S, D>(array: &mut ArrayBase<S, D>) -> UncheckedArrayMut<S, D>
+pub fn uncheck_arr_mut<S, D>(array: &mut ArrayBase<S, D>) -> UncheckedArrayMut<'_, S, D>
 where
     S: RawDataMut,
     D: Dimension,
@@ -7,7 +7,7 @@ use std::fs::File;
 use std::io;
 use std::path::Path;
 use std::sync::atomic;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, PoisonError};
 use std::thread;
 use std::time::Instant;
 
@@ -43,7 +43,8 @@ struct Record {
 
 #[derive(Debug)]
 struct ThreadWaiter {
-    parker: crossbeam_utils::sync::Parker,
+    // `Parker` is not `Sync`. The mutex is never locked, it only lets `Tracer` be shared between threads.
+    parker: Mutex<crossbeam_utils::sync::Parker>,
 }
 
 impl Tracer {
@@ -67,7 +68,9 @@ impl Tracer {
             .spawn(move || Self::thread_main_with_file(rx, unparker, file, epoch))?;
         Ok(Self(Some(TracerInner {
             tx,
-            _waiter: Arc::new(ThreadWaiter { parker }),
+            _waiter: Arc::new(ThreadWaiter {
+                parker: Mutex::new(parker),
+            }),
         })))
     }
 
@@ -89,7 +92,9 @@ impl Tracer {
             .spawn(move || Self::thread_main_with_file(rx, unparker, file, epoch))?;
         Ok(Self(Some(TracerInner {
             tx,
-            _waiter: Arc::new(ThreadWaiter { parker }),
+            _waiter: Arc::new(ThreadWaiter {
+                parker: Mutex::new(parker),
+            }),
         })))
     }
 
@@ -106,7 +111,9 @@ impl Tracer {
             .spawn(move || Self::thread_main_with_stdout(rx, unparker))?;
         Ok(Self(Some(TracerInner {
             tx,
-            _waiter: Arc::new(ThreadWaiter { parker }),
+            _waiter: Arc::new(ThreadWaiter {
+                parker: Mutex::new(parker),
+            }),
         })))
     }
 
@@ -118,7 +125,7 @@ impl Tracer {
     /// You can call `finish()` multiple times to record multiple events with same starting point.
     #[inline(always)]
     #[must_use]
-    pub fn start(&self) -> Event {
+    pub fn start(&self) -> Event<'_> {
         if let Some(TracerInner { ref tx, _waiter: _ }) = self.0 {
             atomic::compiler_fence(atomic::Ordering::SeqCst);
             let start_time = Instant::now();
@@ -218,6 +225,9 @@ impl Event<'_> {
 impl Drop for ThreadWaiter {
     #[inline(always)]
     fn drop(&mut self) {
-        self.parker.park();
+        self.parker
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .park();
     }
 }
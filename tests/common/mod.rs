@@ -0,0 +1,24 @@
+//! Helpers shared by the integration tests.
+
+#![allow(dead_code)]
+
+use ndarray::{Array2, Array3};
+use pattie::structs::tensor::COOTensor;
+use pattie::traits::ValType;
+use std::fs::File;
+
+/// Read a tensor from a text file in `data/tensors`.
+pub fn load_tensor(filename: &str) -> COOTensor<u32, f64> {
+    let mut input_file = File::open(format!("data/tensors/{}", filename)).unwrap();
+    COOTensor::read_from_text(&mut input_file).unwrap()
+}
+
+/// Sum up each element into a dense matrix, with the axes in the order of the shape.
+pub fn to_array2<VT: ValType>(tensor: &COOTensor<u32, VT>) -> Array2<VT> {
+    tensor.to_ndarray().into_dimensionality().unwrap()
+}
+
+/// Same as [`to_array2`], but for tensors with 3 axes.
+pub fn to_array3<VT: ValType>(tensor: &COOTensor<u32, VT>) -> Array3<VT> {
+    tensor.to_ndarray().into_dimensionality().unwrap()
+}
@@ -0,0 +1,158 @@
+#![cfg(test)]
+
+mod common;
+
+use common::load_tensor;
+use ndarray::{arr0, arr1, array, Array2, Array3, ShapeBuilder};
+use pattie::algos::tensor::CoalesceCOOTensor;
+use pattie::structs::axis::AxisBuilder;
+use pattie::structs::tensor::{COOTensor, COOTensorInner};
+use pattie::structs::vec::smallvec;
+use pattie::traits::RawParts;
+use std::collections::BTreeMap;
+use streaming_iterator::StreamingIterator;
+
+fn to_map(tensor: &COOTensor<u32, f64>) -> BTreeMap<Vec<u32>, f64> {
+    let mut result = BTreeMap::new();
+    let mut tensor_iter = tensor.iter();
+    while let Some(&(index, &value)) = tensor_iter.next() {
+        *result.entry(index.to_vec()).or_insert(0.0) += value;
+    }
+    result
+}
+
+#[test]
+fn test_coalesce_sum() {
+    let mut tensor = load_tensor("3D_12031.tns");
+    assert!(!tensor.is_coalesced());
+    let expected_blocks = to_map(&tensor).len();
+
+    // Duplicate every other element.
+    let duplicates = tensor
+        .raw_parts()
+        .indices
+        .rows()
+        .into_iter()
+        .zip(tensor.raw_parts().values.iter())
+        .step_by(2)
+        .map(|(index, &value)| (index.to_owned(), value))
+        .collect::<Vec<_>>();
+    for (index, value) in duplicates.iter() {
+        tensor.push_block(index.view(), arr0(*value * 0.5).into_dyn().view());
+    }
+    let expected = to_map(&tensor);
+
+    CoalesceCOOTensor::new(&mut tensor).execute();
+    assert!(tensor.is_coalesced());
+    assert_eq!(tensor.sparse_sort_order(), Some(tensor.sparse_axes()));
+    assert_eq!(tensor.num_blocks(), expected_blocks);
+
+    let indices = &tensor.raw_parts().indices;
+    for i in 1..indices.nrows() {
+        assert!(indices.row(i - 1).iter().lt(indices.row(i).iter()));
+    }
+    let output = to_map(&tensor);
+    assert_eq!(output.len(), expected.len());
+    for (index, value) in output.iter() {
+        assert!((value - expected[index]).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_coalesce_reducers() {
+    let axis = AxisBuilder::new().range(0..4).build();
+    let build = || {
+        let mut tensor = COOTensor::<u32, f64>::zeros(std::slice::from_ref(&axis), &[false]);
+        for (index, value) in [(2, 1.0), (0, 5.0), (2, 3.0), (1, 0.0), (2, 2.0), (0, -5.0)] {
+            tensor.push_block(arr1(&[index]).view(), arr0(value).into_dyn().view());
+        }
+        tensor
+    };
+    let values = |tensor: &COOTensor<u32, f64>| {
+        tensor
+            .raw_parts()
+            .indices
+            .iter()
+            .cloned()
+            .zip(tensor.raw_parts().values.iter().cloned())
+            .collect::<Vec<_>>()
+    };
+
+    let mut tensor = build();
+    CoalesceCOOTensor::new(&mut tensor).execute();
+    assert_eq!(values(&tensor), [(0, 0.0), (1, 0.0), (2, 6.0)]);
+
+    let mut tensor = build();
+    CoalesceCOOTensor::new(&mut tensor).drop_zeros().execute();
+    assert_eq!(values(&tensor), [(2, 6.0)]);
+
+    let mut tensor = build();
+    CoalesceCOOTensor::new(&mut tensor).last().execute();
+    assert_eq!(values(&tensor), [(0, -5.0), (1, 0.0), (2, 2.0)]);
+
+    let mut tensor = build();
+    CoalesceCOOTensor::new(&mut tensor).max().execute();
+    assert_eq!(values(&tensor), [(0, 5.0), (1, 0.0), (2, 3.0)]);
+
+    let mut tensor = build();
+    CoalesceCOOTensor::new(&mut tensor)
+        .reducer(|acc, value| acc * 10.0 + value)
+        .execute();
+    assert_eq!(values(&tensor), [(0, 45.0), (1, 0.0), (2, 132.0)]);
+}
+
+#[test]
+fn test_coalesce_semi_sparse() {
+    let rows = AxisBuilder::new().range(0..3).build();
+    let cols = AxisBuilder::new().range(0..2).build();
+    let mut tensor = COOTensor::<u32, f64>::zeros(&[rows, cols], &[false, true]);
+    tensor.push_block(arr1(&[1]).view(), arr1(&[1.0, 2.0]).into_dyn().view());
+    tensor.push_block(arr1(&[0]).view(), arr1(&[0.0, 0.0]).into_dyn().view());
+    tensor.push_block(arr1(&[1]).view(), arr1(&[-1.0, 3.0]).into_dyn().view());
+
+    CoalesceCOOTensor::new(&mut tensor).drop_zeros().execute();
+    assert!(tensor.is_coalesced());
+    assert_eq!(tensor.raw_parts().indices, Array2::from_elem((1, 1), 1));
+    assert_eq!(
+        tensor.raw_parts().values,
+        Array2::from_shape_vec((1, 2), vec![0.0, 5.0])
+            .unwrap()
+            .into_dyn()
+    );
+}
+
+#[test]
+fn test_coalesce_fortran_layout() {
+    // Dense blocks stored in Fortran order are merged in logical order.
+    let rows = AxisBuilder::new().range(0..3).build();
+    let x = AxisBuilder::new().range(0..2).build();
+    let y = AxisBuilder::new().range(0..3).build();
+    let values = Array3::from_shape_fn((3, 2, 3).f(), |(i, j, k)| (i * 6 + j * 3 + k) as f64);
+    let mut tensor = unsafe {
+        COOTensor::from_raw_parts(COOTensorInner {
+            name: None,
+            shape: smallvec![rows.clone(), x.clone(), y.clone()],
+            sparse_axes: smallvec![rows.clone()],
+            dense_axes: smallvec![x, y],
+            indices: array![[0], [2], [2]],
+            values: values.clone().into_dyn(),
+            sparse_is_sorted: true,
+            sparse_sort_order: smallvec![rows],
+            sparse_is_coalesced: false,
+        })
+    };
+    let expected = tensor.to_ndarray();
+
+    CoalesceCOOTensor::new(&mut tensor).execute();
+    assert_eq!(tensor.num_blocks(), 2);
+    assert_eq!(
+        tensor.raw_parts().values,
+        ndarray::stack![
+            ndarray::Axis(0),
+            values.index_axis(ndarray::Axis(0), 0),
+            &values.index_axis(ndarray::Axis(0), 1) + &values.index_axis(ndarray::Axis(0), 2)
+        ]
+        .into_dyn()
+    );
+    assert_eq!(tensor.to_ndarray(), expected);
+}
@@ -0,0 +1,205 @@
+#![cfg(test)]
+
+mod common;
+
+use common::load_tensor;
+use ndarray::{arr2, Array2, Array3, ArrayD, IxDyn};
+use pattie::algos::matrix::CreateRandomDenseMatrix;
+use pattie::algos::tensor::DensifyCOOTensorAxis;
+use pattie::algos::tensor_tensor::ContractCOOTensors;
+use pattie::structs::axis::AxisBuilder;
+use pattie::structs::tensor::COOTensor;
+use pattie::traits::{RawParts, Tensor};
+
+#[test]
+fn test_contract_sparse_dense() {
+    let tensor = load_tensor("3D_12031.tns");
+    let shape = tensor.shape();
+    let free_axis = AxisBuilder::new().range(0..4).build();
+    let matrix =
+        CreateRandomDenseMatrix::<u32, f64>::new((shape[1].clone(), free_axis.clone()), 0.0, 1.0)
+            .execute()
+            .unwrap();
+    let tensor_dense = tensor
+        .to_ndarray()
+        .into_dimensionality::<ndarray::Ix3>()
+        .unwrap();
+    let matrix_dense = matrix
+        .to_ndarray()
+        .into_dimensionality::<ndarray::Ix2>()
+        .unwrap();
+
+    for multi_thread in [false, true] {
+        let mut task = ContractCOOTensors::new(&tensor, &matrix);
+        task.multi_thread = multi_thread;
+        let output = task.execute().unwrap();
+        assert_eq!(
+            output.shape(),
+            &[shape[0].clone(), shape[2].clone(), free_axis.clone()]
+        );
+        assert_eq!(output.sparse_axes(), &[shape[0].clone(), shape[2].clone()]);
+        assert_eq!(output.dense_axes()[0], free_axis);
+
+        let output_dense = output
+            .to_ndarray()
+            .into_dimensionality::<ndarray::Ix3>()
+            .unwrap();
+        for ((i, k, c), &value) in output_dense.indexed_iter() {
+            let expected = (0..shape[1].len())
+                .map(|r| tensor_dense[(i, r, k)] * matrix_dense[(r, c)])
+                .sum::<f64>();
+            assert!((value - expected).abs() < 1e-9);
+        }
+    }
+}
+
+#[test]
+fn test_contract_all_axes() {
+    let tensor = load_tensor("3D_12031.tns");
+    let expected = tensor.to_ndarray().iter().map(|x| x * x).sum::<f64>();
+
+    for multi_thread in [false, true] {
+        let mut task = ContractCOOTensors::new(&tensor, &tensor);
+        task.multi_thread = multi_thread;
+        let output = task.execute().unwrap();
+        assert_eq!(output.ndim(), 0);
+        assert_eq!(output.num_blocks(), 1);
+        let value = output.raw_parts().values.iter().next().cloned().unwrap();
+        assert!((value - expected).abs() < 1e-6 * expected);
+    }
+}
+
+#[test]
+fn test_contract_spec() {
+    let a = arr2(&[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    let b = arr2(&[[1.0, 0.0], [0.0, 1.0], [2.0, 3.0]]);
+    let a_tensor = COOTensor::<u32, f64>::from_ndarray(a.clone());
+    let b_tensor = COOTensor::<u32, f64>::from_ndarray(b.clone());
+
+    let matmul = Array2::from_shape_fn((2, 2), |(i, k)| {
+        (0..3).map(|j| a[(i, j)] * b[(j, k)]).sum::<f64>()
+    });
+    for spec in ["ij,jk->ik", "ij,jk", "i j, j k -> i k"] {
+        let output = ContractCOOTensors::new(&a_tensor, &b_tensor)
+            .spec(spec)
+            .execute()
+            .unwrap();
+        assert_eq!(output.shape()[0], a_tensor.shape()[0]);
+        assert_eq!(output.shape()[1], b_tensor.shape()[1]);
+        assert_eq!(output.to_ndarray(), matmul.clone().into_dyn());
+    }
+
+    let output = ContractCOOTensors::new(&a_tensor, &b_tensor)
+        .spec("ij,jk->ki")
+        .execute()
+        .unwrap();
+    assert_eq!(output.to_ndarray(), matmul.t().to_owned().into_dyn());
+
+    // Row-wise dot products keep a shared axis.
+    let output = ContractCOOTensors::new(&a_tensor, &a_tensor)
+        .spec("ij,ij->i")
+        .execute()
+        .unwrap();
+    assert_eq!(output.to_ndarray(), ndarray::arr1(&[14.0, 77.0]).into_dyn());
+
+    // Outer product.
+    let output = ContractCOOTensors::new(&a_tensor, &b_tensor)
+        .spec("ij,kl->ijkl")
+        .execute()
+        .unwrap();
+    let outer = output.to_ndarray();
+    assert_eq!(outer.shape(), &[2, 3, 3, 2]);
+    assert_eq!(outer[[1, 2, 2, 1]], a[(1, 2)] * b[(2, 1)]);
+
+    let cube = COOTensor::<u32, f64>::from_ndarray(Array3::<f64>::zeros((2, 3, 4)));
+    for spec in [
+        "ij,jk->ii",
+        "ij,jk->iz",
+        "ij,ik->jk",
+        "ijk,jk->i",
+        "ii,jk->ik",
+        "ij;jk",
+    ] {
+        assert!(ContractCOOTensors::new(&a_tensor, &cube)
+            .spec(spec)
+            .execute()
+            .is_err());
+    }
+}
+
+/// Contract two dense arrays by trying every combination of the letters in `spec`.
+fn einsum_reference(spec: &str, a: &ArrayD<f64>, b: &ArrayD<f64>) -> ArrayD<f64> {
+    let (inputs, output) = spec.split_once("->").unwrap();
+    let (a_letters, b_letters) = inputs.split_once(',').unwrap();
+    let mut letters = Vec::new();
+    let mut lens = Vec::new();
+    for (letter, &len) in a_letters
+        .chars()
+        .zip(a.shape())
+        .chain(b_letters.chars().zip(b.shape()))
+    {
+        if !letters.contains(&letter) {
+            letters.push(letter);
+            lens.push(len);
+        }
+    }
+    let select = |term: &str, index: &IxDyn| {
+        term.chars()
+            .map(|letter| index[letters.iter().position(|&l| l == letter).unwrap()])
+            .collect::<Vec<_>>()
+    };
+    let output_shape = select(output, &IxDyn(&lens));
+    let mut result = ArrayD::zeros(IxDyn(&output_shape));
+    for index in ndarray::indices(IxDyn(&lens)) {
+        result[IxDyn(&select(output, &index))] +=
+            a[IxDyn(&select(a_letters, &index))] * b[IxDyn(&select(b_letters, &index))];
+    }
+    result
+}
+
+#[test]
+fn test_contract_mixed_sparse_dense() {
+    // Every combination of sparse and dense for the last two axes.
+    let splits = || {
+        let tensor = load_tensor("3d-24.tns");
+        let mut splits = vec![tensor.clone()];
+        for axis in &tensor.shape()[1..] {
+            for split in splits.clone() {
+                splits.push(DensifyCOOTensorAxis::new(&split, axis).execute().unwrap());
+            }
+        }
+        splits
+    };
+    // Load the tensor twice, so the result never contains the same axis twice.
+    let (a_splits, b_splits) = (splits(), splits());
+    let dense = a_splits[0].to_ndarray();
+
+    for spec in [
+        // Axes that are dense in one input and sparse in the other.
+        "ijk,ijk->i",
+        // A free axis of `a` and a contracted axis.
+        "ijk,ijl->ikl",
+        // Axes only in `a` that are summed over.
+        "ijk,ljm->lm",
+        // A shared axis kept in the result.
+        "ijk,ijm->jm",
+        // An outer product.
+        "ijk,lmn->jm",
+    ] {
+        let expected = einsum_reference(spec, &dense, &dense);
+        for a in &a_splits {
+            for b in &b_splits {
+                for multi_thread in [false, true] {
+                    let mut task = ContractCOOTensors::new(a, b).spec(spec);
+                    task.multi_thread = multi_thread;
+                    let output = task.execute().unwrap();
+                    let output_dense = output.to_ndarray();
+                    assert_eq!(output_dense.shape(), expected.shape());
+                    for (value, expected) in output_dense.iter().zip(expected.iter()) {
+                        assert!((value - expected).abs() < 1e-9, "spec {}", spec);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,104 @@
+#![cfg(test)]
+
+use ndarray::{array, ArrayD};
+use pattie::algos::tensor::{DensifyCOOTensorAxis, SparsifyCOOTensorAxis};
+use pattie::structs::tensor::COOTensor;
+use pattie::traits::Tensor;
+use std::fs::File;
+
+#[test]
+fn test_densify_sparsify() {
+    let mut input_file = File::open("data/tensors/4d_3_16.tns").unwrap();
+    let tensor = COOTensor::<u32, f32>::read_from_text(&mut input_file).unwrap();
+    let expected = tensor.to_ndarray();
+    assert_eq!(
+        expected.shape(),
+        tensor
+            .shape()
+            .iter()
+            .map(|axis| axis.len())
+            .collect::<Vec<_>>()
+    );
+
+    let axis = &tensor.shape()[1];
+    let densified = DensifyCOOTensorAxis::new(&tensor, axis).execute().unwrap();
+    assert_eq!(densified.shape(), tensor.shape());
+    assert_eq!(densified.sparse_axes().len(), 3);
+    assert_eq!(densified.dense_axes(), std::slice::from_ref(axis));
+    assert!(densified.is_coalesced());
+    assert_eq!(densified.sparse_sort_order(), Some(densified.sparse_axes()));
+    assert_eq!(densified.to_ndarray(), expected);
+
+    // Densify another axis of a semi-sparse tensor.
+    let axis_3 = &tensor.shape()[3];
+    let densified_twice = DensifyCOOTensorAxis::new(&densified, axis_3)
+        .execute()
+        .unwrap();
+    assert_eq!(
+        densified_twice.dense_axes(),
+        &[axis.clone(), axis_3.clone()]
+    );
+    assert_eq!(densified_twice.to_ndarray(), expected);
+
+    let sparsified = SparsifyCOOTensorAxis::new(&densified_twice, axis)
+        .execute()
+        .unwrap();
+    assert_eq!(sparsified.dense_axes(), std::slice::from_ref(axis_3));
+    assert_eq!(sparsified.sparse_axes().last(), Some(axis));
+    assert!(sparsified.sparse_sort_order().is_some());
+    assert_eq!(sparsified.to_ndarray(), expected);
+
+    let sparsified = SparsifyCOOTensorAxis::new(&densified, axis)
+        .drop_zeros()
+        .execute()
+        .unwrap();
+    assert!(sparsified.dense_axes().is_empty());
+    assert!(sparsified.num_non_zeros() <= tensor.num_non_zeros());
+    assert_eq!(sparsified.to_ndarray(), expected);
+
+    // The axis must be on the right side.
+    assert!(DensifyCOOTensorAxis::new(&densified, axis)
+        .execute()
+        .is_err());
+    assert!(SparsifyCOOTensorAxis::new(&tensor, axis).execute().is_err());
+}
+
+#[test]
+fn test_sparsify_dense() {
+    let tensor = COOTensor::<u32, f32>::from_ndarray(array![[1.0, 0.0], [0.0, 0.0], [3.0, 4.0]]);
+    let rows = &tensor.shape()[0];
+    let cols = &tensor.shape()[1];
+
+    let sparsified = SparsifyCOOTensorAxis::new(&tensor, rows)
+        .drop_zeros()
+        .execute()
+        .unwrap();
+    assert_eq!(sparsified.num_blocks(), 2);
+    assert_eq!(sparsified.dense_axes(), std::slice::from_ref(cols));
+    let sparsified = SparsifyCOOTensorAxis::new(&sparsified, cols)
+        .drop_zeros()
+        .execute()
+        .unwrap();
+    assert_eq!(sparsified.num_non_zeros(), 3);
+    assert_eq!(sparsified.sparse_sort_order(), Some(tensor.shape()));
+    assert_eq!(sparsified.to_ndarray(), tensor.to_ndarray());
+
+    let densified = DensifyCOOTensorAxis::new(&sparsified, rows)
+        .execute()
+        .unwrap();
+    assert_eq!(densified.num_blocks(), 2);
+    assert_eq!(densified.to_ndarray(), tensor.to_ndarray());
+}
+
+#[test]
+fn test_densify_empty() {
+    // Densifying the last sparse axis of an empty tensor gives one block of zeros.
+    let tensor = COOTensor::<u32, f32>::from_ndarray(array![[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]);
+    let empty = COOTensor::<u32, f32>::zeros(tensor.shape(), &[false, true]);
+    let densified = DensifyCOOTensorAxis::new(&empty, &tensor.shape()[0])
+        .execute()
+        .unwrap();
+    assert!(densified.sparse_axes().is_empty());
+    assert_eq!(densified.num_blocks(), 1);
+    assert_eq!(densified.to_ndarray(), ArrayD::<f32>::zeros(vec![3, 2]));
+}
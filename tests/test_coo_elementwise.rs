@@ -0,0 +1,297 @@
+#![cfg(test)]
+
+mod common;
+
+use common::load_tensor;
+use ndarray::{arr0, arr1, array, Array1, Array2, Array3, ArrayD, IxDyn, ShapeBuilder};
+use pattie::algos::matrix::CreateRandomDenseMatrix;
+use pattie::algos::tensor::SortCOOTensor;
+use pattie::algos::tensor_tensor::{ElementwiseCOOTensors, ElementwiseOp};
+use pattie::structs::axis::{Axis, AxisBuilder};
+use pattie::structs::tensor::{COOTensor, COOTensorInner};
+use pattie::structs::vec::smallvec;
+use pattie::traits::{RawParts, Tensor};
+use streaming_iterator::StreamingIterator;
+
+/// Convert to a dense array in the order of `shape`, with a mask of stored elements.
+fn to_dense(tensor: &COOTensor<u32, f64>, shape: &[Axis<u32>]) -> (ArrayD<f64>, ArrayD<bool>) {
+    let dims = shape.iter().map(|axis| axis.len()).collect::<Vec<_>>();
+    let mut values = ArrayD::zeros(dims.clone());
+    let mut mask = ArrayD::from_elem(dims, false);
+    let positions = shape
+        .iter()
+        .map(|axis| {
+            tensor
+                .shape()
+                .iter()
+                .position(|other| other == axis)
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    let mut tensor_iter = tensor.iter();
+    while let Some(&(index, &value)) = tensor_iter.next() {
+        let offset = positions
+            .iter()
+            .zip(shape.iter())
+            .map(|(&pos, axis)| (index[pos] - axis.lower()) as usize)
+            .collect::<Vec<_>>();
+        values[offset.as_slice()] += value;
+        mask[offset.as_slice()] = true;
+    }
+    (values, mask)
+}
+
+fn sorted(tensor: &COOTensor<u32, f64>, sort_order: &[Axis<u32>]) -> COOTensor<u32, f64> {
+    let mut tensor = tensor.clone();
+    SortCOOTensor::new(&mut tensor, sort_order).execute();
+    tensor
+}
+
+/// Build a second tensor with permuted axes.
+/// It shares every third element with `tensor`, and has extra elements of its own.
+fn other_tensor(tensor: &COOTensor<u32, f64>) -> COOTensor<u32, f64> {
+    let shape = tensor.shape();
+    let permuted = [shape[2].clone(), shape[0].clone(), shape[1].clone()];
+    let mut result = COOTensor::zeros(&permuted, &[false, false, false]);
+    let mut tensor_iter = tensor.iter();
+    let mut count = 0;
+    while let Some(&(index, &value)) = tensor_iter.next() {
+        if count % 3 == 0 {
+            result.push_block(
+                arr1(&[index[2], index[0], index[1]]).view(),
+                arr0(value * 2.0 + 1.0).into_dyn().view(),
+            );
+        } else if count % 3 == 1 {
+            let shifted = [
+                shape[2].lower() + (index[2] - shape[2].lower() + 1) % shape[2].len() as u32,
+                index[0],
+                index[1],
+            ];
+            result.push_block(arr1(&shifted).view(), arr0(value - 3.0).into_dyn().view());
+        }
+        count += 1;
+    }
+    result
+}
+
+fn check(
+    op: ElementwiseOp,
+    a: &COOTensor<u32, f64>,
+    b: &COOTensor<u32, f64>,
+    output: &COOTensor<u32, f64>,
+) {
+    let shape = a.shape();
+    assert_eq!(output.shape(), shape);
+    let (a_dense, a_mask) = to_dense(a, shape);
+    let (b_dense, b_mask) = to_dense(b, shape);
+    let (output_dense, output_mask) = to_dense(output, shape);
+    for (((((&value, &in_output), &a_value), &in_a), &b_value), &in_b) in output_dense
+        .iter()
+        .zip(output_mask.iter())
+        .zip(a_dense.iter())
+        .zip(a_mask.iter())
+        .zip(b_dense.iter())
+        .zip(b_mask.iter())
+    {
+        let (expected, present) = match op {
+            ElementwiseOp::Add => (a_value + b_value, in_a || in_b),
+            ElementwiseOp::Sub => (a_value - b_value, in_a || in_b),
+            ElementwiseOp::Mul => (a_value * b_value, in_a && in_b),
+            ElementwiseOp::Div if b_value == 0.0 => (0.0, in_a && in_b),
+            ElementwiseOp::Div => (a_value / b_value, in_a && in_b),
+        };
+        assert_eq!(in_output, present);
+        if present {
+            assert!(value == expected || (value - expected).abs() < 1e-9 * expected.abs().max(1.0));
+        }
+    }
+}
+
+#[test]
+fn test_elementwise_sparse() {
+    let a = load_tensor("3D_12031.tns");
+    let b = other_tensor(&a);
+    let (a_sorted, b_sorted) = (sorted(&a, a.sparse_axes()), sorted(&b, a.sparse_axes()));
+
+    for op in [
+        ElementwiseOp::Add,
+        ElementwiseOp::Sub,
+        ElementwiseOp::Mul,
+        ElementwiseOp::Div,
+    ] {
+        for multi_thread in [false, true] {
+            // Hash join.
+            let mut task = ElementwiseCOOTensors::new(&a, &b, op);
+            task.multi_thread = multi_thread;
+            let output = task.execute().unwrap();
+            assert_eq!(output.sparse_axes(), a.sparse_axes());
+            check(op, &a, &b, &output);
+
+            // Sorted merge.
+            let mut task = ElementwiseCOOTensors::new(&a_sorted, &b_sorted, op);
+            task.multi_thread = multi_thread;
+            let output = task.execute().unwrap();
+            assert_eq!(output.sparse_sort_order(), a_sorted.sparse_sort_order());
+            check(op, &a_sorted, &b_sorted, &output);
+        }
+    }
+}
+
+#[test]
+fn test_elementwise_dense() {
+    let a = load_tensor("3D_12031.tns");
+    let shape = a.shape();
+    let axes = (shape[0].clone(), shape[1].clone());
+    let x = CreateRandomDenseMatrix::<u32, f64>::new(axes.clone(), 0.0, 1.0)
+        .execute()
+        .unwrap();
+    let y = CreateRandomDenseMatrix::<u32, f64>::new(axes, 1.0, 1.0)
+        .execute()
+        .unwrap();
+    let x_values = x.raw_parts().values.clone();
+    let y_values = y.raw_parts().values.clone();
+
+    let output = &x + &y;
+    assert_eq!(output.num_blocks(), 1);
+    assert_eq!(output.raw_parts().values, &x_values + &y_values);
+    let output = &x - &y;
+    assert_eq!(output.raw_parts().values, &x_values - &y_values);
+    let output = &x * &y;
+    assert_eq!(output.raw_parts().values, &x_values * &y_values);
+    let output = ElementwiseCOOTensors::div(&x, &y).execute().unwrap();
+    assert_eq!(output.raw_parts().values, &x_values / &y_values);
+}
+
+#[test]
+fn test_elementwise_semi_sparse() {
+    // Sparse rows with dense columns, where `b` stores some rows twice.
+    let a = COOTensor::<u32, f64>::from_ndarray(Array2::from_shape_fn((4, 3), |(i, j)| {
+        (i * 3 + j) as f64
+    }));
+    let shape = a.shape();
+    let mut x = COOTensor::zeros(shape, &[false, true]);
+    let mut y = COOTensor::zeros(shape, &[false, true]);
+    x.push_block(arr1(&[2]).view(), arr1(&[1.0, 2.0, 3.0]).into_dyn().view());
+    x.push_block(arr1(&[0]).view(), arr1(&[4.0, 5.0, 6.0]).into_dyn().view());
+    y.push_block(arr1(&[0]).view(), arr1(&[1.0, 1.0, 1.0]).into_dyn().view());
+    y.push_block(arr1(&[3]).view(), arr1(&[2.0, 2.0, 2.0]).into_dyn().view());
+    y.push_block(arr1(&[0]).view(), arr1(&[1.0, 1.0, 1.0]).into_dyn().view());
+
+    let output = &x + &y;
+    let dense = to_dense(&output, shape).0;
+    let expected = Array2::from_shape_vec(
+        (4, 3),
+        vec![6.0, 7.0, 8.0, 0.0, 0.0, 0.0, 1.0, 2.0, 3.0, 2.0, 2.0, 2.0],
+    )
+    .unwrap();
+    assert_eq!(output.num_blocks(), 3);
+    assert_eq!(dense, expected.into_dyn());
+
+    let output = &x * &y;
+    assert_eq!(output.num_blocks(), 1);
+    assert_eq!(
+        output.raw_parts().values,
+        Array1::from(vec![8.0, 10.0, 12.0])
+            .into_shape(IxDyn(&[1, 3]))
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_elementwise_mismatch() {
+    let a = load_tensor("3D_12031.tns");
+    let shape = a.shape();
+    let matrix =
+        CreateRandomDenseMatrix::<u32, f64>::new((shape[0].clone(), shape[1].clone()), 0.0, 1.0)
+            .execute()
+            .unwrap();
+    assert!(ElementwiseCOOTensors::add(&a, &matrix).execute().is_err());
+}
+
+#[test]
+fn test_elementwise_different_split() {
+    // The residual of a sparse tensor against a dense tensor with the same axes.
+    let a = load_tensor("3d-24.tns");
+    let shape = a.shape();
+    let dims = shape.iter().map(Axis::len).collect::<Vec<_>>();
+    let mut b = COOTensor::zeros(shape, &[true, true, true]);
+    let b_values = ArrayD::from_shape_fn(dims, |index| {
+        (index[0] + 2 * index[1] + 3 * index[2]) as f64
+    });
+    b.push_block(Array1::zeros(0).view(), b_values.view());
+
+    for multi_thread in [false, true] {
+        let mut task = ElementwiseCOOTensors::sub(&a, &b);
+        task.multi_thread = multi_thread;
+        let output = task.execute().unwrap();
+        assert_eq!(output.sparse_axes(), a.sparse_axes());
+        assert_eq!(output.to_ndarray(), &a.to_ndarray() - &b_values);
+    }
+
+    // The other way around, with a dense axis that is sparse in `b`.
+    let semi = {
+        let mut semi = COOTensor::zeros(shape, &[false, false, true]);
+        let mut row = ArrayD::zeros(vec![shape[2].len()]);
+        row[[1]] = 5.0;
+        semi.push_block(
+            arr1(&[shape[0].lower(), shape[1].lower()]).view(),
+            row.view(),
+        );
+        semi
+    };
+    let output = ElementwiseCOOTensors::mul(&semi, &a).execute().unwrap();
+    assert_eq!(output.dense_axes(), semi.dense_axes());
+    assert_eq!(output.to_ndarray(), &semi.to_ndarray() * &a.to_ndarray());
+}
+
+#[test]
+fn test_elementwise_div_by_zero() {
+    // Integer division must not panic on the zeros inside dense blocks.
+    let shape = [
+        AxisBuilder::new().range(0..3).build(),
+        AxisBuilder::new().range(0..2).build(),
+    ];
+    let mut x = COOTensor::<u32, i64>::zeros(&shape, &[false, true]);
+    let mut y = COOTensor::<u32, i64>::zeros(&shape, &[false, true]);
+    x.push_block(arr1(&[1]).view(), arr1(&[6, 7]).into_dyn().view());
+    y.push_block(arr1(&[1]).view(), arr1(&[3, 0]).into_dyn().view());
+
+    let output = ElementwiseCOOTensors::div(&x, &y).execute().unwrap();
+    assert_eq!(output.num_blocks(), 1);
+    assert_eq!(
+        output.raw_parts().values,
+        arr1(&[2, 0]).into_dyn().insert_axis(ndarray::Axis(0))
+    );
+}
+
+#[test]
+fn test_elementwise_fortran_layout() {
+    // The values of a dense tensor in Fortran order must not be linearized in that order.
+    let x = AxisBuilder::new().range(0..2).build();
+    let y = AxisBuilder::new().range(0..3).build();
+    let values = Array3::from_shape_vec((1, 2, 3).f(), (1..=6).map(f64::from).collect()).unwrap();
+    let a = unsafe {
+        COOTensor::<u32, f64>::from_raw_parts(COOTensorInner {
+            name: None,
+            shape: smallvec![x.clone(), y.clone()],
+            sparse_axes: smallvec![],
+            dense_axes: smallvec![x, y],
+            indices: Array2::zeros((1, 0)),
+            values: values.into_dyn(),
+            sparse_is_sorted: true,
+            sparse_sort_order: smallvec![],
+            sparse_is_coalesced: true,
+        })
+    };
+    let expected = array![[1.0, 3.0, 5.0], [2.0, 4.0, 6.0]].into_dyn();
+    assert_eq!(a.to_ndarray(), expected);
+
+    let mut b = COOTensor::zeros(a.shape(), &[true, true]);
+    b.push_block(
+        Array1::zeros(0).view(),
+        array![[1.0, 1.0, 1.0], [2.0, 2.0, 2.0]].into_dyn().view(),
+    );
+    assert_eq!((&a + &b).to_ndarray(), &expected + &b.to_ndarray());
+    assert_eq!((&b - &a).to_ndarray(), &b.to_ndarray() - &expected);
+    assert_eq!((&a * &a).to_ndarray(), &expected * &expected);
+}
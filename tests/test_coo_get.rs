@@ -0,0 +1,80 @@
+#![cfg(test)]
+
+use ndarray::array;
+use pattie::algos::tensor::SortCOOTensor;
+use pattie::structs::axis::AxisBuilder;
+use pattie::structs::tensor::COOTensor;
+use pattie::traits::Tensor;
+use std::fs::File;
+use streaming_iterator::StreamingIterator;
+
+#[test]
+fn test_coo_get() {
+    let mut input_file = File::open("data/tensors/4d_3_16.tns").unwrap();
+    let mut tensor = COOTensor::<u32, f32>::read_from_text(&mut input_file).unwrap();
+
+    let mut elements = Vec::new();
+    let mut tensor_iter = tensor.iter();
+    while let Some(&(index, &value)) = tensor_iter.next() {
+        elements.push((index.to_vec(), value));
+    }
+
+    // Unsorted, looked up with a hash map.
+    assert!(tensor.sparse_sort_order().is_none());
+    for (index, value) in elements.iter() {
+        assert_eq!(tensor.get(index), Some(value));
+    }
+
+    // Sorted in a different order from the shape, looked up with binary search.
+    let sort_order = tensor.shape().iter().rev().cloned().collect::<Vec<_>>();
+    SortCOOTensor::new(&mut tensor, &sort_order).execute();
+    assert!(tensor.sparse_sort_order().is_some());
+    for (index, value) in elements.iter() {
+        assert_eq!(tensor.get(index), Some(value));
+    }
+
+    let lower = tensor
+        .shape()
+        .iter()
+        .map(|axis| axis.lower())
+        .collect::<Vec<_>>();
+    let upper = tensor
+        .shape()
+        .iter()
+        .map(|axis| axis.upper())
+        .collect::<Vec<_>>();
+    if !elements.iter().any(|(index, _)| *index == lower) {
+        assert_eq!(tensor.get(&lower), None);
+    }
+    assert_eq!(tensor.get(&upper), None);
+
+    *tensor.get_mut(&elements[0].0).unwrap() = 100.0;
+    assert_eq!(tensor.get(&elements[0].0), Some(&100.0));
+}
+
+#[test]
+fn test_coo_get_after_push() {
+    let x = AxisBuilder::new().range(0..3).build();
+    let y = AxisBuilder::new().range(0..2).build();
+    let mut tensor = COOTensor::<u32, f32>::zeros(&[x, y], &[false, true]);
+    tensor.push_block(array![2].view(), array![1.0, 2.0].into_dyn().view());
+    assert_eq!(tensor.get(&[2, 1]), Some(&2.0));
+    assert_eq!(tensor.get(&[1, 1]), None);
+
+    // The hash map is rebuilt after the indices change.
+    tensor.push_block(array![1].view(), array![3.0, 4.0].into_dyn().view());
+    assert_eq!(tensor.get(&[1, 1]), Some(&4.0));
+    *tensor.get_mut(&[1, 0]).unwrap() += 10.0;
+    assert_eq!(tensor.get(&[1, 0]), Some(&13.0));
+
+    // A duplicate index returns the first block.
+    tensor.push_block(array![2].view(), array![5.0, 6.0].into_dyn().view());
+    assert_eq!(tensor.get(&[2, 0]), Some(&1.0));
+}
+
+#[test]
+fn test_coo_get_dense() {
+    let tensor = COOTensor::<u32, f32>::from_ndarray(array![[1.0, 2.0], [3.0, 4.0]]);
+    assert_eq!(tensor.get(&[1, 0]), Some(&3.0));
+    assert_eq!(tensor.get(&[2, 0]), None);
+}
@@ -0,0 +1,65 @@
+#![cfg(test)]
+
+mod common;
+
+use common::{to_array2, to_array3};
+use pattie::algos::matrix::CreateRandomDenseMatrix;
+use pattie::algos::tensor_matrix::COOTensorMTTKRP;
+use pattie::structs::axis::AxisBuilder;
+use pattie::structs::tensor::COOTensor;
+use pattie::traits::Tensor;
+use std::fs::File;
+
+#[test]
+fn test_coo_mttkrp() {
+    let mut input_file = File::open("data/tensors/3D_12031.tns").unwrap();
+    let tensor = COOTensor::<u32, f64>::read_from_text(&mut input_file).unwrap();
+    let rank_axis = AxisBuilder::new().range(0..4).build();
+    let matrices = tensor
+        .shape()
+        .iter()
+        .map(|axis| {
+            CreateRandomDenseMatrix::<u32, f64>::new((axis.clone(), rank_axis.clone()), 0.0, 1.0)
+                .execute()
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    let factors = matrices.iter().map(to_array2).collect::<Vec<_>>();
+
+    let expected = to_array3(&tensor);
+    for multi_thread in [false, true] {
+        let mut task = COOTensorMTTKRP::new(&tensor, &matrices, &tensor.shape()[0]);
+        task.multi_thread = multi_thread;
+        let output = task.execute().unwrap();
+        assert_eq!(output.shape()[0], tensor.shape()[0]);
+        assert_eq!(output.shape()[1], rank_axis);
+
+        let output_values = to_array2(&output);
+        for ((i, r), &value) in output_values.indexed_iter() {
+            let mut expected_value = 0.0;
+            for (j, &b) in factors[1].column(r).indexed_iter() {
+                for (k, &c) in factors[2].column(r).indexed_iter() {
+                    expected_value += expected[(i, j, k)] * b * c;
+                }
+            }
+            assert!((value - expected_value).abs() < 1e-9);
+        }
+    }
+}
+
+#[test]
+fn test_coo_mttkrp_missing_matrix() {
+    let mut input_file = File::open("data/tensors/3D_12031.tns").unwrap();
+    let tensor = COOTensor::<u32, f64>::read_from_text(&mut input_file).unwrap();
+    let rank_axis = AxisBuilder::new().range(0..4).build();
+    let matrices = [CreateRandomDenseMatrix::<u32, f64>::new(
+        (tensor.shape()[1].clone(), rank_axis),
+        0.0,
+        1.0,
+    )
+    .execute()
+    .unwrap()];
+
+    let task = COOTensorMTTKRP::new(&tensor, &matrices, &tensor.shape()[0]);
+    assert!(task.execute().is_err());
+}
@@ -1,5 +1,14 @@
 #![cfg(test)]
 
+use ndarray::{array, Array2};
+use pattie::algos::tensor::SortCOOTensor;
+use pattie::algos::tensor_matrix::COOTensorMulDenseMatrix;
+use pattie::structs::axis::AxisBuilder;
+use pattie::structs::tensor::{COOTensor, COOTensorInner};
+use pattie::structs::vec::{smallvec, SmallVec};
+use pattie::traits::{RawParts, Tensor};
+use streaming_iterator::StreamingIterator;
+
 #[test]
 fn test_coo_mul_dense() {
     let input_tensor = "4
@@ -22,8 +31,60 @@ fn test_coo_mul_dense() {
 1 1 2 3 15.000000
 2 1 1 2 16.000000
 ";
-    let input_matrix = "2
-1 1
-4 2
-";
+    let mut tensor = COOTensor::<u32, f32>::read_from_text(&mut input_tensor.as_bytes()).unwrap();
+
+    // A 3x2 dense matrix, whose rows share the last axis of the tensor.
+    let common_axis = tensor.shape()[3].clone();
+    let free_axis = AxisBuilder::new().range(1..3).build();
+    let matrix_values = array![[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]];
+    let matrix = unsafe {
+        COOTensor::from_raw_parts(COOTensorInner {
+            name: None,
+            shape: smallvec![common_axis.clone(), free_axis.clone()],
+            sparse_axes: SmallVec::new(),
+            dense_axes: smallvec![common_axis.clone(), free_axis.clone()],
+            indices: Array2::zeros((1, 0)),
+            values: matrix_values
+                .clone()
+                .insert_axis(ndarray::Axis(0))
+                .into_dyn(),
+            sparse_is_sorted: true,
+            sparse_sort_order: SmallVec::new(),
+            sparse_is_coalesced: true,
+        })
+    };
+
+    let sort_order = tensor.shape().to_vec();
+    SortCOOTensor::new(&mut tensor, &sort_order).execute();
+    let output = COOTensorMulDenseMatrix::new(&tensor, &matrix)
+        .execute()
+        .unwrap();
+    assert_eq!(output.shape()[3], free_axis);
+
+    // Compute the expected result with a dense 3x3x3x2 array.
+    let mut expected = ndarray::Array4::<f32>::zeros((3, 3, 3, 2));
+    let mut tensor_iter = tensor.iter();
+    while let Some(&(index, &value)) = tensor_iter.next() {
+        let (i, j, k, r) = (
+            index[0] as usize - 1,
+            index[1] as usize - 1,
+            index[2] as usize - 1,
+            index[3] as usize - 1,
+        );
+        for c in 0..2 {
+            expected[[i, j, k, c]] += value * matrix_values[[r, c]];
+        }
+    }
+
+    let mut actual = ndarray::Array4::<f32>::zeros((3, 3, 3, 2));
+    let mut output_iter = output.iter();
+    while let Some(&(index, &value)) = output_iter.next() {
+        actual[[
+            index[0] as usize - 1,
+            index[1] as usize - 1,
+            index[2] as usize - 1,
+            index[3] as usize - 1,
+        ]] += value;
+    }
+    assert_eq!(actual, expected);
 }
@@ -0,0 +1,168 @@
+#![cfg(test)]
+
+mod common;
+
+use common::load_tensor;
+use ndarray::{array, Array1, Array3, Array4, ArrayD, ShapeBuilder};
+use pattie::algos::matrix::CreateRandomDenseMatrix;
+use pattie::algos::tensor::SortCOOTensor;
+use pattie::algos::tensor_matrix::SemiCOOTensorMulDenseMatrix;
+use pattie::algos::tensor_vector::COOTensorMulDenseVector;
+use pattie::structs::axis::{Axis, AxisBuilder};
+use pattie::structs::tensor::{COOTensor, COOTensorInner};
+use pattie::structs::vec::{smallvec, SmallVec};
+use pattie::traits::{RawParts, Tensor};
+use streaming_iterator::StreamingIterator;
+
+fn dense_vector(axis: &Axis<u32>, values: Array1<f64>) -> COOTensor<u32, f64> {
+    unsafe {
+        COOTensor::from_raw_parts(COOTensorInner {
+            name: None,
+            shape: smallvec![axis.clone()],
+            sparse_axes: SmallVec::new(),
+            dense_axes: smallvec![axis.clone()],
+            indices: ndarray::Array2::zeros((1, 0)),
+            values: values.insert_axis(ndarray::Axis(0)).into_dyn(),
+            sparse_is_sorted: true,
+            sparse_sort_order: SmallVec::new(),
+            sparse_is_coalesced: true,
+        })
+    }
+}
+
+fn sort_by_trailing_axis(tensor: &mut COOTensor<u32, f64>, axis: &Axis<u32>) {
+    let sort_order = tensor
+        .sparse_axes()
+        .iter()
+        .filter(|&ax| ax != axis)
+        .chain(Some(axis))
+        .cloned()
+        .collect::<Vec<_>>();
+    SortCOOTensor::new(tensor, &sort_order).execute();
+}
+
+#[test]
+fn test_coo_mul_vector() {
+    let mut tensor = load_tensor("4d_3_16.tns");
+    let common_axis = tensor.shape()[1].clone();
+    let vector_values = array![1.0, -2.0, 0.5];
+    let vector = dense_vector(&common_axis, vector_values.clone());
+    sort_by_trailing_axis(&mut tensor, &common_axis);
+
+    let mut expected = Array3::<f64>::zeros((3, 3, 3));
+    let mut tensor_iter = tensor.iter();
+    while let Some(&(index, &value)) = tensor_iter.next() {
+        let index = index.iter().map(|&i| i as usize - 1).collect::<Vec<_>>();
+        expected[(index[0], index[2], index[3])] += value * vector_values[index[1]];
+    }
+
+    for multi_thread in [false, true] {
+        let mut task = COOTensorMulDenseVector::new(&tensor, &vector);
+        task.multi_thread = multi_thread;
+        let output = task.execute().unwrap();
+        assert_eq!(output.ndim(), 3);
+        assert!(!output.shape().contains(&common_axis));
+        assert!(output.dense_axes().is_empty());
+
+        let mut actual = Array3::<f64>::zeros((3, 3, 3));
+        let mut output_iter = output.iter();
+        while let Some(&(index, &value)) = output_iter.next() {
+            let index = index.iter().map(|&i| i as usize - 1).collect::<Vec<_>>();
+            actual[(index[0], index[1], index[2])] += value;
+        }
+        assert_eq!(actual, expected);
+    }
+}
+
+#[test]
+fn test_semi_coo_mul_vector() {
+    let mut tensor = load_tensor("4d_3_16.tns");
+    let matrix_axis = tensor.shape()[3].clone();
+    let free_axis = AxisBuilder::new().range(0..2).build();
+    let matrix =
+        CreateRandomDenseMatrix::<u32, f64>::new((matrix_axis.clone(), free_axis), 0.0, 1.0)
+            .execute()
+            .unwrap();
+    sort_by_trailing_axis(&mut tensor, &matrix_axis);
+    let mut semi_tensor = SemiCOOTensorMulDenseMatrix::new(&tensor, &matrix)
+        .execute()
+        .unwrap();
+
+    let common_axis = semi_tensor.shape()[0].clone();
+    let vector_values = array![2.0, 0.0, -1.0];
+    let vector = dense_vector(&common_axis, vector_values.clone());
+    sort_by_trailing_axis(&mut semi_tensor, &common_axis);
+
+    let mut expected = Array4::<f64>::zeros((1, 3, 3, 2));
+    let mut tensor_iter = semi_tensor.iter();
+    while let Some(&(index, &value)) = tensor_iter.next() {
+        let i = index[0] as usize - 1;
+        expected[(
+            0,
+            index[1] as usize - 1,
+            index[2] as usize - 1,
+            index[3] as usize,
+        )] += value * vector_values[i];
+    }
+
+    let output = COOTensorMulDenseVector::new(&semi_tensor, &vector)
+        .execute()
+        .unwrap();
+    assert_eq!(output.ndim(), 3);
+    assert_eq!(output.dense_axes(), semi_tensor.dense_axes());
+
+    let mut actual = Array4::<f64>::zeros((1, 3, 3, 2));
+    let mut output_iter = output.iter();
+    while let Some(&(index, &value)) = output_iter.next() {
+        actual[(
+            0,
+            index[0] as usize - 1,
+            index[1] as usize - 1,
+            index[2] as usize,
+        )] += value;
+    }
+    assert!(actual
+        .iter()
+        .zip(expected.iter())
+        .all(|(a, b)| (a - b).abs() < 1e-9));
+}
+
+#[test]
+fn test_coo_mul_vector_no_sparse_axes_left() {
+    // The vector axis is the only sparse axis, and the dense blocks are in Fortran order.
+    let x = AxisBuilder::new().range(0..3).build();
+    let y = AxisBuilder::new().range(0..2).build();
+    let z = AxisBuilder::new().range(0..4).build();
+    let values = Array3::from_shape_fn((2, 2, 4).f(), |(i, j, k)| (i * 8 + j * 4 + k) as f64);
+    let tensor = unsafe {
+        COOTensor::from_raw_parts(COOTensorInner {
+            name: None,
+            shape: smallvec![x.clone(), y.clone(), z.clone()],
+            sparse_axes: smallvec![x.clone()],
+            dense_axes: smallvec![y.clone(), z.clone()],
+            indices: array![[0], [2]],
+            values: values.clone().into_dyn(),
+            sparse_is_sorted: true,
+            sparse_sort_order: smallvec![x.clone()],
+            sparse_is_coalesced: true,
+        })
+    };
+    let vector = dense_vector(&x, array![1.0, -2.0, 0.5]);
+
+    let output = COOTensorMulDenseVector::new(&tensor, &vector)
+        .execute()
+        .unwrap();
+    assert!(output.sparse_axes().is_empty());
+    assert_eq!(output.num_blocks(), 1);
+    let expected =
+        &values.index_axis(ndarray::Axis(0), 0) + &(&values.index_axis(ndarray::Axis(0), 1) * 0.5);
+    assert_eq!(output.to_ndarray(), expected.into_dyn());
+
+    // An empty tensor still gives one block of zeros.
+    let empty = COOTensor::<u32, f64>::zeros(&[x, y, z], &[false, true, true]);
+    let output = COOTensorMulDenseVector::new(&empty, &vector)
+        .execute()
+        .unwrap();
+    assert_eq!(output.num_blocks(), 1);
+    assert_eq!(output.to_ndarray(), ArrayD::<f64>::zeros(vec![2, 4]));
+}
@@ -0,0 +1,83 @@
+#![cfg(test)]
+
+mod common;
+
+use common::load_tensor;
+use ndarray::{arr0, arr1};
+use pattie::algos::tensor::SortCOOTensor;
+use pattie::structs::axis::AxisBuilder;
+use pattie::structs::tensor::COOTensor;
+use pattie::traits::{RawParts, Tensor};
+use streaming_iterator::StreamingIterator;
+
+fn elements(tensor: &COOTensor<u32, f64>) -> Vec<(Vec<u32>, f64)> {
+    let mut result = Vec::new();
+    let mut tensor_iter = tensor.iter();
+    while let Some(&(index, &value)) = tensor_iter.next() {
+        result.push((index.to_vec(), value));
+    }
+    result
+}
+
+#[test]
+fn test_sort_lexicographic() {
+    let tensor = load_tensor("3D_12031.tns");
+    let shape = tensor.shape();
+    let mut expected = elements(&tensor);
+    expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    for order in [[0, 1, 2], [2, 0, 1], [1, 2, 0]] {
+        let order = order.map(|axis_idx| shape[axis_idx].clone());
+        for multi_thread in [false, true] {
+            let mut sorted = tensor.clone();
+            let mut task = SortCOOTensor::new(&mut sorted, &order);
+            task.multi_thread = multi_thread;
+            task.execute();
+            assert_eq!(sorted.sparse_sort_order(), Some(order.as_slice()));
+
+            let keys = elements(&sorted)
+                .into_iter()
+                .map(|(index, _)| {
+                    order
+                        .iter()
+                        .map(|axis| index[shape.iter().position(|ax| ax == axis).unwrap()])
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            assert!(keys.windows(2).all(|pair| pair[0] <= pair[1]));
+
+            let mut output = elements(&sorted);
+            output.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            assert_eq!(output, expected);
+        }
+    }
+}
+
+#[test]
+fn test_sort_stable() {
+    let rows = AxisBuilder::new().range(0..3).build();
+    let cols = AxisBuilder::new().range(0..3).build();
+    let order = [cols.clone(), rows.clone()];
+    for multi_thread in [false, true] {
+        let mut tensor =
+            COOTensor::<u32, f64>::zeros(&[rows.clone(), cols.clone()], &[false, false]);
+        for (i, (row, col)) in [(2, 0), (0, 1), (2, 0), (1, 0), (0, 1), (2, 0)]
+            .into_iter()
+            .enumerate()
+        {
+            tensor.push_block(arr1(&[row, col]).view(), arr0(i as f64).into_dyn().view());
+        }
+        let mut task = SortCOOTensor::new(&mut tensor, &order);
+        task.multi_thread = multi_thread;
+        task.execute();
+        assert_eq!(
+            tensor
+                .raw_parts()
+                .values
+                .iter()
+                .cloned()
+                .collect::<Vec<_>>(),
+            [3.0, 0.0, 2.0, 5.0, 1.0, 4.0]
+        );
+    }
+}
@@ -0,0 +1,82 @@
+#![cfg(test)]
+
+mod common;
+
+use common::to_array2;
+use ndarray::{Array2, Array3};
+use ndarray_rand::rand_distr::Uniform;
+use ndarray_rand::RandomExt;
+use pattie::algos::decomposition::COOTensorCPALS;
+use pattie::structs::axis::AxisBuilder;
+use pattie::structs::tensor::{COOTensor, KruskalTensor};
+use pattie::traits::Tensor;
+
+fn reconstruct(kruskal: &KruskalTensor<u32, f64>) -> Array3<f64> {
+    let shape = kruskal.shape();
+    let factors = shape
+        .iter()
+        .map(|axis| to_array2(kruskal.factor(axis).unwrap()))
+        .collect::<Vec<_>>();
+    let weights = kruskal.weights();
+    Array3::from_shape_fn(
+        (shape[0].len(), shape[1].len(), shape[2].len()),
+        |(i, j, k)| {
+            (0..kruskal.rank())
+                .map(|r| weights[r] * factors[0][(i, r)] * factors[1][(j, r)] * factors[2][(k, r)])
+                .sum()
+        },
+    )
+}
+
+#[test]
+fn test_cp_als_rank_one() {
+    // Build a fully sparse tensor holding an exact rank-1 tensor.
+    // ALS may get stuck in a local minimum for higher ranks, which would make the test flaky.
+    let shape = [6, 7, 8].map(|len| AxisBuilder::new().range(0..len).build());
+    let factors = shape
+        .iter()
+        .map(|axis| Array2::random((axis.len(), 1), Uniform::new(-1.0, 1.0)))
+        .collect::<Vec<_>>();
+    let expected = Array3::from_shape_fn((6, 7, 8), |(i, j, k)| {
+        factors[0][(i, 0)] * factors[1][(j, 0)] * factors[2][(k, 0)]
+    });
+    let mut tensor = COOTensor::<u32, f64>::zeros(&shape, &[false; 3]);
+    for ((i, j, k), &value) in expected.indexed_iter() {
+        let index = ndarray::arr1(&[i as u32, j as u32, k as u32]);
+        tensor.push_block(index.view(), ndarray::arr0(value).into_dyn().view());
+    }
+
+    for multi_thread in [false, true] {
+        let mut task = COOTensorCPALS::new(&tensor, 1, 100, 1e-12);
+        task.multi_thread = multi_thread;
+        let kruskal = task.execute().unwrap();
+        assert_eq!(kruskal.shape(), tensor.shape());
+        assert_eq!(kruskal.rank(), 1);
+
+        let residual = (&reconstruct(&kruskal) - &expected)
+            .iter()
+            .map(|x| x * x)
+            .sum::<f64>()
+            .sqrt();
+        let norm = expected.iter().map(|x| x * x).sum::<f64>().sqrt();
+        assert!(residual / norm < 1e-6);
+    }
+}
+
+#[test]
+fn test_cp_als_dense_tensor() {
+    let tensor = COOTensor::<u32, f64>::from_ndarray(Array3::<f64>::ones((2, 3, 4)));
+    assert!(COOTensorCPALS::new(&tensor, 2, 10, 1e-6).execute().is_err());
+}
+
+#[test]
+fn test_cp_als_nan() {
+    // A NaN in the tensor ends up in the Gram matrix, which must not panic.
+    let shape = [3, 4, 5].map(|len| AxisBuilder::new().range(0..len).build());
+    let mut tensor = COOTensor::<u32, f64>::zeros(&shape, &[false; 3]);
+    for (index, value) in [([0, 1, 2], 1.0), ([2, 3, 4], f64::NAN), ([1, 0, 3], 2.0)] {
+        let index = ndarray::arr1(&index);
+        tensor.push_block(index.view(), ndarray::arr0(value).into_dyn().view());
+    }
+    assert!(COOTensorCPALS::new(&tensor, 2, 10, 1e-6).execute().is_err());
+}
@@ -0,0 +1,65 @@
+#![cfg(test)]
+
+use ndarray::array;
+use pattie::structs::tensor::{COOTensor, CSFTensor};
+use pattie::traits::Tensor;
+use std::collections::BTreeSet;
+use std::fs::File;
+use streaming_iterator::StreamingIterator;
+
+fn collect_elements<'a>(
+    mut iter: impl StreamingIterator<Item = (&'a [u32], &'a f32)>,
+) -> Vec<(Vec<u32>, f32)> {
+    let mut result = Vec::new();
+    while let Some(&(index, &value)) = iter.next() {
+        result.push((index.to_vec(), value));
+    }
+    result.sort_by(|a, b| a.0.cmp(&b.0));
+    result
+}
+
+#[test]
+fn test_csf_from_coo() {
+    let mut input_file = File::open("data/tensors/4d_3_16.tns").unwrap();
+    let coo = COOTensor::<u32, f32>::read_from_text(&mut input_file).unwrap();
+    let order = coo.shape().iter().rev().cloned().collect::<Vec<_>>();
+
+    let csf = CSFTensor::from_coo(&coo, &order);
+    assert_eq!(csf.shape(), coo.shape());
+    assert_eq!(csf.sparse_sort_order(), order.as_slice());
+    assert_eq!(csf.num_levels(), 4);
+    assert_eq!(csf.num_blocks(), coo.num_blocks());
+    assert_eq!(csf.num_non_zeros(), coo.num_non_zeros());
+
+    // The root level holds each distinct index of the outermost axis exactly once.
+    let mut root_indices = BTreeSet::new();
+    let mut coo_iter = coo.iter();
+    while let Some(&(index, _)) = coo_iter.next() {
+        root_indices.insert(index[3]);
+    }
+    assert!(csf.num_fibers(0) >= root_indices.len());
+
+    let expected = collect_elements(coo.iter());
+    assert_eq!(collect_elements(csf.iter()), expected);
+
+    let coo_again = COOTensor::from(&csf);
+    assert_eq!(coo_again.sparse_sort_order(), Some(order.as_slice()));
+    assert_eq!(collect_elements(coo_again.iter()), expected);
+}
+
+#[test]
+fn test_csf_from_dense() {
+    let coo = COOTensor::<u32, f32>::from_ndarray(array![[1.0, 2.0], [3.0, 4.0]]);
+    let csf = CSFTensor::from_coo(&coo, &[]);
+    assert_eq!(csf.num_levels(), 0);
+    assert_eq!(csf.num_non_zeros(), 4);
+    assert_eq!(
+        collect_elements(csf.iter()),
+        vec![
+            (vec![0, 0], 1.0),
+            (vec![0, 1], 2.0),
+            (vec![1, 0], 3.0),
+            (vec![1, 1], 4.0)
+        ]
+    );
+}
@@ -0,0 +1,264 @@
+#![cfg(test)]
+
+mod common;
+
+use common::to_array2;
+use ndarray::{array, Array1, Array2};
+use pattie::algos::matrix::{CSRMatrixMulDenseMatrix, CSRMatrixMulDenseVector};
+use pattie::algos::tensor::SortCOOTensor;
+use pattie::algos::tensor_matrix::COOTensorMulCSRMatrix;
+use pattie::structs::axis::{Axis, AxisBuilder};
+use pattie::structs::matrix::{CSCMatrix, CSRMatrix};
+use pattie::structs::tensor::{COOTensor, COOTensorInner};
+use pattie::structs::vec::{smallvec, SmallVec};
+use pattie::traits::{RawParts, Tensor};
+use streaming_iterator::StreamingIterator;
+
+fn matmul(a: &Array2<f32>, b: &Array2<f32>) -> Array2<f32> {
+    Array2::from_shape_fn((a.nrows(), b.ncols()), |(i, j)| {
+        (0..a.ncols()).map(|k| a[[i, k]] * b[[k, j]]).sum()
+    })
+}
+
+fn dense_tensor(axes: &[Axis<u32>], values: ndarray::ArrayD<f32>) -> COOTensor<u32, f32> {
+    let values = values.insert_axis(ndarray::Axis(0));
+    unsafe {
+        COOTensor::from_raw_parts(COOTensorInner {
+            name: None,
+            shape: axes.iter().cloned().collect(),
+            sparse_axes: SmallVec::new(),
+            dense_axes: axes.iter().cloned().collect(),
+            indices: Array2::zeros((1, 0)),
+            values,
+            sparse_is_sorted: true,
+            sparse_sort_order: SmallVec::new(),
+            sparse_is_coalesced: true,
+        })
+    }
+}
+
+fn read_matrix() -> COOTensor<u32, f32> {
+    // Unsorted, with a duplicate element and an empty row.
+    let input = "#@ name: m
+2
+1 0
+4 5
+3 4 1.0
+1 0 2.0
+3 1 3.0
+1 3 4.0
+3 4 5.0
+";
+    COOTensor::read_from_text(&mut input.as_bytes()).unwrap()
+}
+
+#[test]
+fn test_csr_from_coo() {
+    let coo = read_matrix();
+    let expected = to_array2(&coo);
+
+    let csr = CSRMatrix::from_coo(&coo);
+    assert_eq!(csr.name(), Some("m"));
+    assert_eq!(csr.shape(), coo.shape());
+    assert_eq!(csr.num_non_zeros(), 5);
+    assert_eq!(csr.row(0), (&[0, 3][..], array![2.0, 4.0].view()));
+    assert_eq!(csr.row(1).0, &[] as &[u32]);
+    assert_eq!(csr.row(2), (&[1, 4, 4][..], array![3.0, 1.0, 5.0].view()));
+
+    let csc = CSCMatrix::from_coo(&coo);
+    assert_eq!(csc.shape(), coo.shape());
+    assert_eq!(csc.col(4), (&[3, 3][..], array![1.0, 5.0].view()));
+
+    let coo_from_csr = COOTensor::from(&csr);
+    assert_eq!(coo_from_csr.sparse_sort_order(), Some(coo.shape()));
+    assert_eq!(to_array2(&coo_from_csr), expected);
+    let coo_from_csc = COOTensor::from(&csc);
+    assert_eq!(
+        coo_from_csc.sparse_sort_order(),
+        Some(&[coo.shape()[1].clone(), coo.shape()[0].clone()][..])
+    );
+    assert_eq!(to_array2(&coo_from_csc), expected);
+
+    let csc_from_csr = CSCMatrix::from_csr(&csr);
+    assert_eq!(
+        csc_from_csr.raw_parts().col_offsets,
+        csc.raw_parts().col_offsets
+    );
+    assert_eq!(
+        csc_from_csr.raw_parts().row_indices,
+        csc.raw_parts().row_indices
+    );
+    let csr_from_csc = CSRMatrix::from_csc(&csc);
+    assert_eq!(
+        csr_from_csc.raw_parts().row_offsets,
+        csr.raw_parts().row_offsets
+    );
+    assert_eq!(
+        csr_from_csc.raw_parts().col_indices,
+        csr.raw_parts().col_indices
+    );
+    assert_eq!(csr_from_csc.raw_parts().values, csr.raw_parts().values);
+}
+
+#[test]
+fn test_csr_from_dense() {
+    let coo = COOTensor::<u32, f32>::from_ndarray(array![[1.0, 2.0], [3.0, 4.0]]);
+    let csr = CSRMatrix::from_coo(&coo);
+    assert_eq!(csr.raw_parts().row_offsets, vec![0, 2, 4]);
+    assert_eq!(
+        to_array2(&COOTensor::from(&csr)),
+        array![[1.0, 2.0], [3.0, 4.0]]
+    );
+}
+
+#[test]
+fn test_csr_mul_dense() {
+    let coo = read_matrix();
+    let csr = CSRMatrix::from_coo(&coo);
+    let matrix_dense = to_array2(&coo);
+
+    let vector_values = Array1::from_vec(vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+    let vector = dense_tensor(&coo.shape()[1..], vector_values.clone().into_dyn());
+    let free_axis = AxisBuilder::new().range(0..2).build();
+    let dense_values = array![[1.0, 2.0], [3.0, 4.0], [5.0, 6.0], [7.0, 8.0], [9.0, 10.0]];
+    let dense = dense_tensor(
+        &[coo.shape()[1].clone(), free_axis.clone()],
+        dense_values.clone().into_dyn(),
+    );
+    let dense_transposed = dense_tensor(
+        &[free_axis.clone(), coo.shape()[1].clone()],
+        dense_values.t().to_owned().into_dyn(),
+    );
+
+    for multi_thread in [false, true] {
+        let mut task = CSRMatrixMulDenseVector::new(&csr, &vector);
+        task.multi_thread = multi_thread;
+        let output = task.execute().unwrap();
+        assert_eq!(output.shape(), &coo.shape()[..1]);
+        assert!(output.sparse_axes().is_empty());
+        assert_eq!(
+            output
+                .raw_parts()
+                .values
+                .iter()
+                .copied()
+                .collect::<Vec<_>>(),
+            matmul(
+                &matrix_dense,
+                &vector_values.clone().insert_axis(ndarray::Axis(1))
+            )
+            .into_raw_vec()
+        );
+
+        for dense in [&dense, &dense_transposed] {
+            let mut task = CSRMatrixMulDenseMatrix::new(&csr, dense);
+            task.multi_thread = multi_thread;
+            let output = task.execute().unwrap();
+            assert_eq!(output.shape(), &[coo.shape()[0].clone(), free_axis.clone()]);
+            assert!(output.sparse_axes().is_empty());
+            assert_eq!(to_array2(&output), matmul(&matrix_dense, &dense_values));
+        }
+    }
+
+    // The vector must be along the column axis.
+    let vector = dense_tensor(&coo.shape()[..1], Array1::zeros(3).into_dyn());
+    assert!(CSRMatrixMulDenseVector::new(&csr, &vector)
+        .execute()
+        .is_err());
+}
+
+#[test]
+fn test_coo_mul_csr() {
+    let input_tensor = "3
+1 1 1
+4 4 4
+3 2 2 1.000000
+2 2 3 2.000000
+1 3 2 3.000000
+3 3 2 4.000000
+1 1 2 7.000000
+2 3 1 8.000000
+2 2 1 9.000000
+1 3 3 13.000000
+1 2 2 14.000000
+1 1 3 15.000000
+1 1 3 16.000000
+";
+    let mut tensor = COOTensor::<u32, f32>::read_from_text(&mut input_tensor.as_bytes()).unwrap();
+
+    // A sparse 3x4 matrix, whose rows share the middle axis of the tensor.
+    let common_axis = tensor.shape()[1].clone();
+    let free_axis = AxisBuilder::new().range(0..4).build();
+    let mut matrix =
+        COOTensor::<u32, f32>::zeros(&[common_axis.clone(), free_axis.clone()], &[false, false]);
+    for (row, col, value) in [
+        (1, 0, 1.0),
+        (1, 3, 2.0),
+        (3, 1, 3.0),
+        (3, 3, 4.0),
+        (3, 0, -1.0),
+    ] {
+        matrix.push_block(
+            array![row, col].view(),
+            ndarray::arr0(value).into_dyn().view(),
+        );
+    }
+    let csr = CSRMatrix::from_coo(&matrix);
+    let matrix_dense = to_array2(&matrix);
+
+    let sort_order: SmallVec<_> = smallvec![
+        tensor.shape()[0].clone(),
+        tensor.shape()[2].clone(),
+        common_axis.clone()
+    ];
+    SortCOOTensor::new(&mut tensor, &sort_order).execute();
+
+    // Compute the expected result with a dense 3x4x3 array.
+    let mut expected = ndarray::Array3::<f32>::zeros((3, 4, 3));
+    let mut tensor_iter = tensor.iter();
+    while let Some(&(index, &value)) = tensor_iter.next() {
+        let (i, r, k) = (
+            index[0] as usize - 1,
+            index[1] as usize - 1,
+            index[2] as usize - 1,
+        );
+        for c in 0..4 {
+            expected[[i, c, k]] += value * matrix_dense[[r, c]];
+        }
+    }
+
+    for multi_thread in [false, true] {
+        let mut task = COOTensorMulCSRMatrix::new(&tensor, &csr);
+        task.multi_thread = multi_thread;
+        let output = task.execute().unwrap();
+        assert_eq!(output.shape()[1], free_axis);
+        assert_eq!(output.sparse_axes(), output.shape());
+        assert_eq!(
+            output.sparse_sort_order(),
+            Some(
+                &[
+                    sort_order[0].clone(),
+                    sort_order[1].clone(),
+                    free_axis.clone()
+                ][..]
+            )
+        );
+        assert!(output.is_coalesced());
+
+        let mut actual = ndarray::Array3::<f32>::zeros((3, 4, 3));
+        let mut output_iter = output.iter();
+        while let Some(&(index, &value)) = output_iter.next() {
+            actual[[
+                index[0] as usize - 1,
+                index[1] as usize,
+                index[2] as usize - 1,
+            ]] += value;
+        }
+        assert_eq!(actual, expected);
+    }
+
+    // The tensor must be sorted along the common axis.
+    let sort_order = tensor.shape().to_vec();
+    SortCOOTensor::new(&mut tensor, &sort_order).execute();
+    assert!(COOTensorMulCSRMatrix::new(&tensor, &csr).execute().is_err());
+}
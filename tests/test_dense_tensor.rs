@@ -0,0 +1,132 @@
+#![cfg(test)]
+
+use ndarray::{array, ArrayD};
+use pattie::structs::axis::AxisBuilder;
+use pattie::structs::tensor::{COOTensor, COOTensorInner, DenseTensor};
+use pattie::structs::vec::smallvec;
+use pattie::traits::{RawParts, Tensor};
+use streaming_iterator::{StreamingIterator, StreamingIteratorMut};
+
+fn collect_elements<'a>(
+    mut iter: impl StreamingIterator<Item = (&'a [u32], &'a f32)>,
+) -> Vec<(Vec<u32>, f32)> {
+    let mut result = Vec::new();
+    while let Some(&(index, &value)) = iter.next() {
+        result.push((index.to_vec(), value));
+    }
+    result
+}
+
+#[test]
+fn test_dense_iter() {
+    let mut tensor =
+        DenseTensor::<u32, f32>::from_ndarray(array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    assert_eq!(tensor.ndim(), 2);
+    assert_eq!(tensor.num_non_zeros(), 6);
+    assert_eq!(
+        collect_elements(tensor.iter()),
+        vec![
+            (vec![0, 0], 1.0),
+            (vec![0, 1], 2.0),
+            (vec![0, 2], 3.0),
+            (vec![1, 0], 4.0),
+            (vec![1, 1], 5.0),
+            (vec![1, 2], 6.0),
+        ]
+    );
+
+    let mut tensor_iter = tensor.iter_mut();
+    while let Some((index, value)) = tensor_iter.next_mut() {
+        **value += index[0] as f32 * 10.0;
+    }
+    assert_eq!(
+        tensor.values(),
+        &array![[1.0, 2.0, 3.0], [14.0, 15.0, 16.0]].into_dyn()
+    );
+
+    let axis = AxisBuilder::new().range(3..5).build();
+    let tensor = DenseTensor::<u32, f32>::zeros(&[axis.clone(), axis.clone()]);
+    assert_eq!(collect_elements(tensor.iter())[3], (vec![4, 4], 0.0));
+    let empty = DenseTensor::<u32, f32>::zeros(&[axis, AxisBuilder::new().range(0..0).build()]);
+    assert_eq!(empty.iter().count(), 0);
+    let scalar = DenseTensor::<u32, f32>::from_ndarray(ndarray::arr0(7.0));
+    assert_eq!(collect_elements(scalar.iter()), vec![(vec![], 7.0)]);
+}
+
+#[test]
+fn test_dense_coo_conversion() {
+    let dense = DenseTensor::<u32, f32>::from_ndarray(array![[1.0, 2.0], [3.0, 4.0]]);
+    let expected = collect_elements(dense.iter());
+    let data_ptr = dense.values().as_ptr();
+
+    let coo = COOTensor::from(dense);
+    assert!(coo.sparse_axes().is_empty());
+    assert_eq!(coo.dense_axes(), coo.shape());
+    assert_eq!(coo.raw_parts().values.as_ptr(), data_ptr);
+
+    let dense = DenseTensor::from(coo);
+    assert_eq!(dense.values().as_ptr(), data_ptr);
+    assert_eq!(collect_elements(dense.iter()), expected);
+
+    let view = dense.coo_view();
+    assert_eq!(view.num_blocks(), 1);
+    assert_eq!(view.dense_axes(), dense.shape());
+    let coo = view.to_owned();
+    assert_eq!(collect_elements(coo.iter()), expected);
+    assert_eq!(
+        collect_elements(COOTensor::from_dense(&dense).iter()),
+        expected
+    );
+}
+
+#[test]
+fn test_dense_from_coo() {
+    // Dense axes in a different order from the shape.
+    let x = AxisBuilder::new().range(0..2).build();
+    let y = AxisBuilder::new().range(1..4).build();
+    let coo = unsafe {
+        COOTensor::from_raw_parts(COOTensorInner {
+            name: Some("t".to_owned()),
+            shape: smallvec![x.clone(), y.clone()],
+            sparse_axes: smallvec![],
+            dense_axes: smallvec![y.clone(), x.clone()],
+            indices: ndarray::Array2::zeros((1, 0)),
+            values: array![[[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]].into_dyn(),
+            sparse_is_sorted: true,
+            sparse_sort_order: smallvec![],
+            sparse_is_coalesced: true,
+        })
+    };
+    let dense = DenseTensor::from(coo.clone());
+    assert_eq!(dense.name(), Some("t"));
+    assert_eq!(dense.shape(), coo.shape());
+    assert_eq!(
+        dense.values(),
+        &array![[1.0, 3.0, 5.0], [2.0, 4.0, 6.0]].into_dyn()
+    );
+    assert!(dense.values().is_standard_layout());
+    assert_eq!(DenseTensor::from_coo(&coo).values(), dense.values());
+    let coo_again = COOTensor::from(dense.clone());
+    assert!(coo_again.raw_parts().values.is_standard_layout());
+    assert_eq!(coo_again.to_ndarray(), *dense.values());
+
+    // A Fortran-order array is copied into standard layout.
+    let dense =
+        DenseTensor::<u32, f32>::from_ndarray(array![[1.0, 2.0], [3.0, 4.0]].reversed_axes());
+    assert!(dense.values().is_standard_layout());
+    assert_eq!(dense.values(), &array![[1.0, 3.0], [2.0, 4.0]].into_dyn());
+
+    // A sparse tensor with a duplicate element.
+    let input = "2\n1 0\n3 2\n1 1 1.0\n2 0 2.0\n1 1 3.0\n";
+    let coo = COOTensor::<u32, f32>::read_from_text(&mut input.as_bytes()).unwrap();
+    let dense = DenseTensor::from_coo(&coo);
+    assert_eq!(dense.shape(), coo.shape());
+    assert_eq!(dense.values(), &array![[0.0, 4.0], [2.0, 0.0]].into_dyn());
+
+    // A dense tensor without blocks is all zeros.
+    let coo = COOTensor::<u32, f32>::zeros(&[x, y], &[true, true]);
+    assert_eq!(
+        DenseTensor::from(coo).values(),
+        &ArrayD::<f32>::zeros(vec![2, 3])
+    );
+}
@@ -0,0 +1,75 @@
+#![cfg(test)]
+
+use pattie::structs::axis::AxisBuilder;
+use pattie::structs::tensor::{COOTensor, DOKTensor};
+use pattie::traits::{RawParts, Tensor};
+use std::fs::File;
+use streaming_iterator::StreamingIterator;
+
+#[test]
+fn test_dok_tensor() {
+    let x = AxisBuilder::new().range(1..4).build();
+    let y = AxisBuilder::new().range(0..2).build();
+    let mut tensor = DOKTensor::<u32, f32>::new(&[x.clone(), y.clone()]);
+    assert_eq!(tensor.num_non_zeros(), 0);
+
+    assert_eq!(tensor.insert(&[3, 1], 1.0), None);
+    assert_eq!(tensor.insert(&[1, 0], 2.0), None);
+    assert_eq!(tensor.insert(&[3, 1], 3.0), Some(1.0));
+    tensor.update(&[2, 1], |value| *value += 4.0);
+    tensor.update(&[1, 0], |value| *value *= 5.0);
+    assert_eq!(tensor.get(&[3, 1]), Some(&3.0));
+    assert_eq!(tensor.get(&[2, 1]), Some(&4.0));
+    assert_eq!(tensor.get(&[1, 0]), Some(&10.0));
+    assert_eq!(tensor.get(&[1, 1]), None);
+    tensor.insert(&[3, 0], 6.0);
+    assert_eq!(tensor.remove(&[3, 0]), Some(6.0));
+    assert_eq!(tensor.remove(&[3, 0]), None);
+    assert_eq!(tensor.num_non_zeros(), 3);
+
+    let coo = tensor.freeze();
+    assert_eq!(coo.shape(), &[x, y]);
+    assert_eq!(coo.sparse_sort_order(), Some(coo.shape()));
+    assert!(coo.is_coalesced());
+    assert_eq!(
+        coo.raw_parts().indices,
+        ndarray::array![[1, 0], [2, 1], [3, 1]]
+    );
+    assert_eq!(
+        coo.raw_parts().values,
+        ndarray::array![10.0, 4.0, 3.0].into_dyn()
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_dok_out_of_bound() {
+    let x = AxisBuilder::new().range(1..4).build();
+    let mut tensor = DOKTensor::<u32, f32>::new(&[x]);
+    tensor.insert(&[4], 1.0);
+}
+
+#[test]
+fn test_dok_from_coo() {
+    let mut input_file = File::open("data/tensors/3D_12031.tns").unwrap();
+    let coo = COOTensor::<u32, f32>::read_from_text(&mut input_file).unwrap();
+
+    let dok = DOKTensor::from_coo(&coo);
+    let mut coo_iter = coo.iter();
+    while let Some(&(index, _)) = coo_iter.next() {
+        assert!(dok.get(index).is_some());
+    }
+
+    let frozen = COOTensor::from(dok.clone());
+    assert_eq!(frozen.num_non_zeros(), dok.num_non_zeros());
+    let indices = &frozen.raw_parts().indices;
+    for i in 1..indices.nrows() {
+        assert!(indices.row(i - 1).as_slice() < indices.row(i).as_slice());
+    }
+    let mut frozen_iter = frozen.iter();
+    while let Some(&(index, value)) = frozen_iter.next() {
+        assert_eq!(dok.get(index), Some(value));
+    }
+    let sum = |tensor: &COOTensor<u32, f32>| tensor.raw_parts().values.iter().sum::<f32>();
+    assert!((sum(&frozen) - sum(&coo)).abs() <= sum(&coo).abs() * 1e-5);
+}
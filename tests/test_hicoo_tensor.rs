@@ -0,0 +1,92 @@
+#![cfg(test)]
+
+mod common;
+
+use common::{load_tensor, to_array2, to_array3};
+use pattie::algos::matrix::CreateRandomDenseMatrix;
+use pattie::algos::tensor_matrix::{HiCOOTensorMTTKRP, HiCOOTensorMulDenseMatrix};
+use pattie::structs::axis::AxisBuilder;
+use pattie::structs::tensor::{COOTensor, HiCOOTensor};
+use pattie::traits::{RawParts, Tensor};
+
+#[test]
+fn test_hicoo_from_coo() {
+    let coo = load_tensor("3D_12031.tns");
+    let hicoo = HiCOOTensor::from_coo(&coo, 2, 4);
+    assert_eq!(hicoo.shape(), coo.shape());
+    assert_eq!(hicoo.num_non_zeros(), coo.num_non_zeros());
+    assert!(hicoo.num_blocks() <= hicoo.num_non_zeros());
+    assert!(hicoo.num_superblocks() <= hicoo.num_blocks());
+
+    let coo_again = COOTensor::from(&hicoo);
+    assert_eq!(to_array3(&coo_again), to_array3(&coo));
+}
+
+#[test]
+fn test_hicoo_mul_dense() {
+    let coo = load_tensor("3D_12031.tns");
+    let hicoo = HiCOOTensor::from_coo(&coo, 3, 5);
+    let common_axis = coo.shape()[1].clone();
+    let free_axis = AxisBuilder::new().range(0..4).build();
+    let matrix = CreateRandomDenseMatrix::<u32, f64>::new((common_axis, free_axis), 0.0, 1.0)
+        .execute()
+        .unwrap();
+    let matrix_values = to_array2(&matrix);
+
+    let expected = to_array3(&coo);
+    for multi_thread in [false, true] {
+        let mut task = HiCOOTensorMulDenseMatrix::new(&hicoo, &matrix);
+        task.multi_thread = multi_thread;
+        let output = task.execute().unwrap();
+        assert_eq!(output.num_non_zeros(), output.num_blocks() * 4);
+        let indices = &output.raw_parts().indices;
+        for i in 1..indices.nrows() {
+            assert!(indices.row(i - 1).as_slice() < indices.row(i).as_slice());
+        }
+
+        let output_dense = to_array3(&output);
+        for ((i, c, k), &value) in output_dense.indexed_iter() {
+            let expected_value = (0..expected.shape()[1])
+                .map(|r| expected[(i, r, k)] * matrix_values[(r, c)])
+                .sum::<f64>();
+            assert!((value - expected_value).abs() < 1e-9);
+        }
+    }
+}
+
+#[test]
+fn test_hicoo_mttkrp() {
+    let coo = load_tensor("3D_12031.tns");
+    let hicoo = HiCOOTensor::from_coo(&coo, 2, 4);
+    let rank_axis = AxisBuilder::new().range(0..3).build();
+    let matrices = coo
+        .shape()
+        .iter()
+        .map(|axis| {
+            CreateRandomDenseMatrix::<u32, f64>::new((axis.clone(), rank_axis.clone()), 0.0, 1.0)
+                .execute()
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    let factors = matrices.iter().map(to_array2).collect::<Vec<_>>();
+
+    let expected = to_array3(&coo);
+    for multi_thread in [false, true] {
+        let mut task = HiCOOTensorMTTKRP::new(&hicoo, &matrices, &coo.shape()[2]);
+        task.multi_thread = multi_thread;
+        let output = task.execute().unwrap();
+        assert_eq!(output.shape()[0], coo.shape()[2]);
+        assert_eq!(output.shape()[1], rank_axis);
+
+        let output_values = to_array2(&output);
+        for ((k, r), &value) in output_values.indexed_iter() {
+            let mut expected_value = 0.0;
+            for (i, &a) in factors[0].column(r).indexed_iter() {
+                for (j, &b) in factors[1].column(r).indexed_iter() {
+                    expected_value += expected[(i, j, k)] * a * b;
+                }
+            }
+            assert!((value - expected_value).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,151 @@
+#![cfg(test)]
+
+use ndarray::{arr1, Array2};
+use pattie::algos::tensor::{CoalesceCOOTensor, SortCOOTensor};
+use pattie::io::BinaryReadError;
+use pattie::structs::axis::AxisBuilder;
+use pattie::structs::tensor::COOTensor;
+use pattie::traits::{RawParts, Tensor};
+use std::fs::File;
+
+fn round_trip<IT, VT>(tensor: &COOTensor<IT, VT>) -> COOTensor<IT, VT>
+where
+    IT: pattie::traits::IdxType + pattie::traits::BinaryScalar,
+    VT: pattie::traits::ValType + pattie::traits::BinaryScalar,
+{
+    let mut buffer = Vec::new();
+    tensor.write_to_binary(&mut buffer).unwrap();
+    COOTensor::read_from_binary(&mut buffer.as_slice()).unwrap()
+}
+
+fn assert_same_axes<IT, VT>(a: &COOTensor<IT, VT>, b: &COOTensor<IT, VT>)
+where
+    IT: pattie::traits::IdxType,
+    VT: pattie::traits::ValType,
+{
+    let describe = |tensor: &COOTensor<IT, VT>| {
+        let position = |axis| tensor.shape().iter().position(|ax| ax == axis).unwrap();
+        (
+            tensor
+                .shape()
+                .iter()
+                .map(|axis| (axis.range(), axis.label().map(str::to_owned)))
+                .collect::<Vec<_>>(),
+            tensor
+                .sparse_axes()
+                .iter()
+                .map(position)
+                .collect::<Vec<_>>(),
+            tensor.dense_axes().iter().map(position).collect::<Vec<_>>(),
+            tensor
+                .sparse_sort_order()
+                .map(|order| order.iter().map(position).collect::<Vec<_>>()),
+        )
+    };
+    assert_eq!(describe(a), describe(b));
+}
+
+#[test]
+fn test_binary_round_trip_sparse() {
+    let mut input_file = File::open("data/tensors/3D_12031.tns").unwrap();
+    let tensor = COOTensor::<u32, f32>::read_from_text(&mut input_file).unwrap();
+    let output = round_trip(&tensor);
+    assert_same_axes(&tensor, &output);
+    assert_eq!(output.name(), None);
+    assert!(!output.is_coalesced());
+    assert_eq!(output.raw_parts().indices, tensor.raw_parts().indices);
+    assert_eq!(output.raw_parts().values, tensor.raw_parts().values);
+}
+
+#[test]
+fn test_binary_round_trip_semi_sparse() {
+    let rows = AxisBuilder::new().label("row").range(2..6).build();
+    let cols = AxisBuilder::new().range(0..3).build();
+    let depth = AxisBuilder::new().label("depth").range(10..12).build();
+    let mut tensor = COOTensor::<u64, f64>::zeros(
+        &[cols.clone(), rows.clone(), depth.clone()],
+        &[true, false, false],
+    );
+    for (row, depth, base) in [(5, 10, 1.0), (2, 11, 4.0), (3, 10, 7.0)] {
+        tensor.push_block(
+            arr1(&[row, depth]).view(),
+            arr1(&[base, base + 1.0, base + 2.0]).into_dyn().view(),
+        );
+    }
+    *tensor.name_mut() = Some("semi sparse".to_owned());
+    let order = [depth.clone(), rows.clone()];
+    SortCOOTensor::new(&mut tensor, &order).execute();
+    CoalesceCOOTensor::new(&mut tensor).execute();
+
+    let output = round_trip(&tensor);
+    assert_same_axes(&tensor, &output);
+    assert_eq!(output.name(), Some("semi sparse"));
+    assert!(output.is_coalesced());
+    assert_eq!(output.raw_parts().indices, tensor.raw_parts().indices);
+    assert_eq!(output.raw_parts().values, tensor.raw_parts().values);
+    assert_eq!(output.raw_parts().values.shape(), &[3, 3]);
+}
+
+#[test]
+fn test_binary_round_trip_dense() {
+    let tensor = COOTensor::<i32, i64>::from_ndarray(Array2::from_shape_fn((3, 4), |(i, j)| {
+        (i * 4 + j) as i64 - 5
+    }));
+    let output = round_trip(&tensor);
+    assert_same_axes(&tensor, &output);
+    assert_eq!(output.num_blocks(), 1);
+    assert_eq!(output.raw_parts().values, tensor.raw_parts().values);
+}
+
+#[test]
+fn test_binary_errors() {
+    let tensor = COOTensor::<u32, f32>::from_ndarray(Array2::<f32>::ones((2, 2)));
+    let mut buffer = Vec::new();
+    tensor.write_to_binary(&mut buffer).unwrap();
+
+    assert!(matches!(
+        COOTensor::<u32, f64>::read_from_binary(&mut buffer.as_slice()),
+        Err(BinaryReadError::TypeError { what: "value", .. })
+    ));
+    assert!(matches!(
+        COOTensor::<u64, f32>::read_from_binary(&mut buffer.as_slice()),
+        Err(BinaryReadError::TypeError { what: "index", .. })
+    ));
+    assert!(matches!(
+        COOTensor::<u32, f32>::read_from_binary(&mut &buffer[..buffer.len() - 1]),
+        Err(BinaryReadError::IOError(_))
+    ));
+    assert!(matches!(
+        COOTensor::<u32, f32>::read_from_binary(&mut &b"3\n0 0 0\n1 1 1\n"[..]),
+        Err(BinaryReadError::MagicError)
+    ));
+
+    // A sort order of [axis 0, axis 0] must not pass as sorted.
+    let input = "2\n0 0\n3 3\n0 1 1.0\n1 2 2.0\n";
+    let mut sorted = COOTensor::<u32, f32>::read_from_text(&mut input.as_bytes()).unwrap();
+    let order = sorted.shape().to_vec();
+    SortCOOTensor::new(&mut sorted, &order).execute();
+    let mut duplicated = Vec::new();
+    sorted.write_to_binary(&mut duplicated).unwrap();
+    let indices_offset = u64::from_le_bytes(duplicated[32..40].try_into().unwrap()) as usize;
+    let sort_order = [2u32, 0, 1]
+        .iter()
+        .flat_map(|position| position.to_le_bytes())
+        .collect::<Vec<_>>();
+    let position = duplicated[..indices_offset]
+        .windows(sort_order.len())
+        .rposition(|window| window == sort_order)
+        .unwrap();
+    duplicated[position + 8] = 0;
+    assert!(matches!(
+        COOTensor::<u32, f32>::read_from_binary(&mut duplicated.as_slice()),
+        Err(BinaryReadError::HeaderError(_))
+    ));
+
+    let mut future = buffer.clone();
+    future[8] = 2;
+    assert!(matches!(
+        COOTensor::<u32, f32>::read_from_binary(&mut future.as_slice()),
+        Err(BinaryReadError::VersionError(2))
+    ));
+}
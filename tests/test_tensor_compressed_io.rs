@@ -0,0 +1,121 @@
+#![cfg(test)]
+
+use pattie::io::Compression;
+use pattie::structs::tensor::COOTensor;
+use pattie::traits::{RawParts, Tensor};
+use std::fs::File;
+
+fn read_tensor() -> COOTensor<u32, f32> {
+    let mut input_file = File::open("data/tensors/3d_7.tns").unwrap();
+    COOTensor::read_from_text(&mut input_file).unwrap()
+}
+
+fn text_round_trip(compression: Compression) {
+    let tensor = read_tensor();
+    let mut w = compression.writer(Vec::new()).unwrap();
+    tensor.write_to_text(&mut w).unwrap();
+    let buffer = w.finish().unwrap();
+    assert_eq!(Compression::detect(&buffer), compression);
+
+    let output = COOTensor::<u32, f32>::read_from_text(&mut buffer.as_slice()).unwrap();
+    assert_eq!(output.raw_parts().indices, tensor.raw_parts().indices);
+    assert_eq!(output.raw_parts().values, tensor.raw_parts().values);
+}
+
+fn binary_round_trip(compression: Compression) {
+    let tensor = read_tensor();
+    let mut w = compression.writer(Vec::new()).unwrap();
+    tensor.write_to_binary(&mut w).unwrap();
+    let buffer = w.finish().unwrap();
+
+    let output = COOTensor::<u32, f32>::read_from_binary(&mut buffer.as_slice()).unwrap();
+    assert_eq!(output.raw_parts().indices, tensor.raw_parts().indices);
+    assert_eq!(output.raw_parts().values, tensor.raw_parts().values);
+}
+
+fn path_round_trip(compression: Compression, file_name: &str) {
+    let tensor = read_tensor();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(file_name);
+    assert_eq!(Compression::from_path(&path), compression);
+    let mut w = compression.writer(File::create(&path).unwrap()).unwrap();
+    tensor.write_to_text(&mut w).unwrap();
+    w.finish().unwrap();
+
+    let mut input = Compression::open(&path).unwrap();
+    let output = COOTensor::<u32, f32>::read_from_text(&mut input).unwrap();
+    assert_eq!(output.raw_parts().indices, tensor.raw_parts().indices);
+    assert_eq!(output.raw_parts().values, tensor.raw_parts().values);
+}
+
+#[test]
+fn test_uncompressed_round_trip() {
+    text_round_trip(Compression::None);
+    binary_round_trip(Compression::None);
+    path_round_trip(Compression::None, "tensor.tns");
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+fn test_gzip_round_trip() {
+    text_round_trip(Compression::Gzip);
+    binary_round_trip(Compression::Gzip);
+    path_round_trip(Compression::Gzip, "tensor.tns.gz");
+}
+
+#[test]
+#[cfg(feature = "zstd")]
+fn test_zstd_round_trip() {
+    text_round_trip(Compression::Zstd);
+    binary_round_trip(Compression::Zstd);
+    path_round_trip(Compression::Zstd, "tensor.tns.zst");
+}
+
+#[test]
+#[cfg(feature = "xz")]
+fn test_xz_round_trip() {
+    text_round_trip(Compression::Xz);
+    binary_round_trip(Compression::Xz);
+    path_round_trip(Compression::Xz, "tensor.tns.xz");
+}
+
+#[test]
+fn test_detect_compression() {
+    assert_eq!(Compression::detect(b"\x1f\x8b\x08\x00"), Compression::Gzip);
+    assert_eq!(Compression::detect(b"\x28\xb5\x2f\xfd"), Compression::Zstd);
+    assert_eq!(Compression::detect(b"\xfd7zXZ\x00"), Compression::Xz);
+    assert_eq!(Compression::detect(b"3\n0 0 0\n"), Compression::None);
+    assert_eq!(Compression::detect(b""), Compression::None);
+    assert_eq!(Compression::from_path("a.tns.zst"), Compression::Zstd);
+    assert_eq!(Compression::from_path("a.bin.xz"), Compression::Xz);
+}
+
+#[test]
+fn test_short_input() {
+    // Shorter than any magic bytes, must not be mistaken for compressed data.
+    let tensor = COOTensor::<u32, f32>::read_from_text(&mut b"0\n".as_slice()).unwrap();
+    assert!(tensor.shape().is_empty());
+}
+
+#[test]
+#[cfg(not(feature = "zstd"))]
+fn test_unsupported_compression() {
+    use pattie::io::TensorReadError;
+    use std::io;
+
+    let err =
+        COOTensor::<u32, f32>::read_from_text(&mut b"\x28\xb5\x2f\xfd\x00".as_slice()).unwrap_err();
+    match err {
+        TensorReadError::IOError { source, .. } => {
+            assert_eq!(source.kind(), io::ErrorKind::Unsupported)
+        }
+        _ => panic!("unexpected error: {}", err),
+    }
+    assert!(Compression::Zstd.writer(Vec::new()).is_err());
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("tensor.tns.zst");
+    std::fs::write(&path, b"\x28\xb5\x2f\xfd\x00").unwrap();
+    let err = Compression::open(&path).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+}
@@ -0,0 +1,104 @@
+#![cfg(test)]
+
+use ndarray::{Array2, Axis};
+use pattie::io::TensorReadError;
+use pattie::structs::tensor::COOTensor;
+use pattie::traits::{RawParts, Tensor};
+use std::fs::File;
+
+#[test]
+fn test_chunks_match_whole_tensor() {
+    let mut input_file = File::open("data/tensors/3D_12031.tns").unwrap();
+    let tensor = COOTensor::<u32, f32>::read_from_text(&mut input_file).unwrap();
+
+    let input_file = File::open("data/tensors/3D_12031.tns").unwrap();
+    let chunks = COOTensor::<u32, f32>::read_chunks_from_text(input_file, 1000).unwrap();
+    let shape = chunks.shape().to_vec();
+    let chunks = chunks.collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(chunks.len(), 13);
+
+    let mut next_line = 4;
+    for chunk in chunks.iter() {
+        // Same axis ids across chunks.
+        assert_eq!(chunk.tensor.shape(), &shape[..]);
+        assert!(chunk.tensor.num_non_zeros() <= 1000);
+        assert_eq!(chunk.lines.start, next_line);
+        assert_eq!(
+            chunk.lines.end - chunk.lines.start,
+            chunk.tensor.num_non_zeros() as u64
+        );
+        next_line = chunk.lines.end;
+    }
+
+    let indices = chunks
+        .iter()
+        .map(|chunk| chunk.tensor.raw_parts().indices.view())
+        .collect::<Vec<_>>();
+    let indices: Array2<u32> = ndarray::concatenate(Axis(0), &indices).unwrap();
+    assert_eq!(indices, tensor.raw_parts().indices);
+    let values = chunks
+        .iter()
+        .flat_map(|chunk| chunk.tensor.raw_parts().values.iter().copied())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        values,
+        tensor
+            .raw_parts()
+            .values
+            .iter()
+            .copied()
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_chunks_header() {
+    let input = "#@ name: t\n2\n#@ axis 1: y\n0 0\n2 2\n\n0 1 1.0\n# comment\n1 0 2.0\n1 1 3.0\n";
+    let chunks = COOTensor::<u32, f32>::read_chunks_from_text(input.as_bytes(), 2).unwrap();
+    assert_eq!(chunks.name(), Some("t"));
+    let chunks = chunks.collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[0].lines, 7..10);
+    assert_eq!(chunks[1].lines, 10..11);
+    for chunk in chunks {
+        assert_eq!(chunk.tensor.name(), Some("t"));
+        assert_eq!(chunk.tensor.shape()[1].label(), Some("y"));
+    }
+
+    // Metadata after the line of upper bounds is an error, plain comments are not.
+    for (input, line) in [
+        ("2\n0 0\n2 2\n# comment\n#@ name: t\n0 1 1.0\n", 5),
+        ("2\n0 0\n2 2\n0 1 1.0\n#@ axis 1: y\n", 5),
+        ("2\n0 0\n2 2\n0 1 1.0\n1 1 2.0\n1 0 3.0\n#@ dense: 1\n", 7),
+    ] {
+        let chunks = COOTensor::<u32, f32>::read_chunks_from_text(input.as_bytes(), 2).unwrap();
+        let error = chunks.collect::<Result<Vec<_>, _>>().unwrap_err();
+        assert!(
+            matches!(error, TensorReadError::HeaderError { line: l, column: 1, .. } if l == line),
+            "{:?}",
+            error
+        );
+    }
+
+    // Exactly fills the chunks, then nothing is left.
+    let input = "1\n0\n2\n0 1.0\n1 2.0\n";
+    let chunks = COOTensor::<u32, f32>::read_chunks_from_text(input.as_bytes(), 2).unwrap();
+    assert_eq!(chunks.count(), 1);
+
+    let input = "1\n0\n2\n";
+    let chunks = COOTensor::<u32, f32>::read_chunks_from_text(input.as_bytes(), 2).unwrap();
+    assert_eq!(chunks.count(), 0);
+}
+
+#[test]
+fn test_chunks_error() {
+    let input = "1\n0\n2\n0 1.0\n1 2.0\n2 3.0\n1 4.0\n";
+    let mut chunks = COOTensor::<u32, f32>::read_chunks_from_text(input.as_bytes(), 2).unwrap();
+    assert!(chunks.next().unwrap().is_ok());
+    let error = chunks.next().unwrap().unwrap_err();
+    assert!(matches!(
+        error,
+        TensorReadError::IndexOutOfBoundError { line: 6, column: 1 }
+    ));
+    assert!(chunks.next().is_none());
+}
@@ -1,10 +1,14 @@
 #![cfg(test)]
 
 use anyhow::Result;
+use ndarray::{aview0, aview1};
+use pattie::io::TensorReadError;
 use pattie::structs::tensor;
+use pattie::traits::{RawParts, Tensor};
 use std::fs::File;
 use std::path::Path;
 use std::str;
+use streaming_iterator::StreamingIterator;
 
 fn load_then_store_tensor(filename: &Path) -> Result<()> {
     let mut input_file = File::open(filename)?;
@@ -32,3 +36,277 @@ test_tensor_io!(test_load_3d_12031, "data/tensors/3D_12031.tns");
 test_tensor_io!(test_load_3d_dense, "data/tensors/3d_dense.tns");
 test_tensor_io!(test_load_3d_24, "data/tensors/3d-24.tns");
 test_tensor_io!(test_load_4d_3_16, "data/tensors/4d_3_16.tns");
+
+#[test]
+fn test_header_round_trip() {
+    let mut input_file = File::open("data/tensors/3d_7.tns").unwrap();
+    let tensor = tensor::COOTensor::<u32, f32>::read_from_text(&mut input_file).unwrap();
+    assert_eq!(tensor.name(), None);
+    assert!(tensor.shape().iter().all(|axis| axis.label().is_none()));
+
+    // Label the first and last axes only.
+    let shape = tensor.shape();
+    let labeled_shape = [
+        shape[0].clone_with_label("user"),
+        shape[1].clone(),
+        shape[2].clone_with_label("tag\\with\nnewline"),
+    ];
+    let mut labeled = tensor::COOTensor::<u32, f32>::zeros(&labeled_shape, &[false; 3]);
+    let mut tensor_iter = tensor.iter();
+    while let Some(&(index, value)) = tensor_iter.next() {
+        labeled.push_block(aview1(index), aview0(value).into_dyn());
+    }
+    *labeled.name_mut() = Some("my tensor: v2".to_owned());
+
+    let mut output_buffer = Vec::new();
+    labeled.write_to_text(&mut output_buffer).unwrap();
+    let output =
+        tensor::COOTensor::<u32, f32>::read_from_text(&mut output_buffer.as_slice()).unwrap();
+    assert_eq!(output.name(), Some("my tensor: v2"));
+    let labels = output
+        .shape()
+        .iter()
+        .map(|axis| axis.label())
+        .collect::<Vec<_>>();
+    assert_eq!(labels, [Some("user"), None, Some("tag\\with\nnewline")]);
+    for (output_axis, axis) in output.shape().iter().zip(shape.iter()) {
+        assert_eq!(output_axis.range(), axis.range());
+    }
+    assert_eq!(output.sparse_axes(), output.shape());
+    assert_eq!(output.raw_parts().indices, tensor.raw_parts().indices);
+    assert_eq!(output.raw_parts().values, tensor.raw_parts().values);
+}
+
+#[test]
+fn test_legacy_header() {
+    // Written by older versions, the name is a plain comment.
+    let input = b"# my tensor\n2\n# Axis 0: user\r\n# Axis 1 has no label\n0 0\n2 2\n1 1 1.0\n";
+    let tensor = tensor::COOTensor::<u32, f32>::read_from_text(&mut input.as_slice()).unwrap();
+    assert_eq!(tensor.name(), None);
+    let labels = tensor
+        .shape()
+        .iter()
+        .map(|axis| axis.label())
+        .collect::<Vec<_>>();
+    assert_eq!(labels, [Some("user"), None]);
+}
+
+#[test]
+fn test_header_errors() {
+    let input = b"#@ axis 3: x\n3\n0 0 0\n2 2 2\n0 1 1 1.0\n";
+    let error = tensor::COOTensor::<u32, f32>::read_from_text(&mut input.as_slice()).unwrap_err();
+    assert!(matches!(
+        error,
+        TensorReadError::HeaderError {
+            line: 1,
+            column: 1,
+            ..
+        }
+    ));
+
+    let input = b"3\n0 0 0\n# plain comment: ignored\n2 2 2\n#@ axis one: x\n";
+    let error = tensor::COOTensor::<u32, f32>::read_from_text(&mut input.as_slice()).unwrap_err();
+    assert!(matches!(
+        error,
+        TensorReadError::HeaderError {
+            line: 5,
+            column: 1,
+            ..
+        }
+    ));
+
+    // Unknown keys are ignored.
+    let input = b"#@ version: 2\n1\n0\n2\n1 1.0 #@ axis 0: x\n";
+    let tensor = tensor::COOTensor::<u32, f32>::read_from_text(&mut input.as_slice()).unwrap();
+    assert_eq!(tensor.shape()[0].label(), Some("x"));
+    assert_eq!(tensor.num_non_zeros(), 1);
+}
+
+#[test]
+fn test_frostt_read() {
+    let input = b"# A FROSTT file\n1 1 1 1.5\n\n2 3 1 -2.0 # trailing comment\r\n1 2 4 3.0";
+    let tensor = tensor::COOTensor::<u32, f32>::read_from_frostt(&mut input.as_slice(), 1).unwrap();
+    let ranges = tensor
+        .shape()
+        .iter()
+        .map(|axis| axis.range())
+        .collect::<Vec<_>>();
+    assert_eq!(ranges, [0..2, 0..3, 0..4]);
+    assert_eq!(
+        tensor.raw_parts().indices,
+        ndarray::arr2(&[[0, 0, 0], [1, 2, 0], [0, 1, 3]])
+    );
+    assert_eq!(
+        tensor.raw_parts().values,
+        ndarray::arr1(&[1.5, -2.0, 3.0]).into_dyn()
+    );
+
+    let input = b"1 1 1.0\n0 1 2.0\n";
+    let error =
+        tensor::COOTensor::<u32, f32>::read_from_frostt(&mut input.as_slice(), 1).unwrap_err();
+    assert!(matches!(
+        error,
+        TensorReadError::IndexOutOfBoundError { line: 2, column: 1 }
+    ));
+
+    // The end of the axis would overflow.
+    for input in [&b"1 255 1.0\n"[..], &b"1 1 1.0\n255 1 2.0\n"[..]] {
+        let error = tensor::COOTensor::<u8, f32>::read_from_frostt(&mut &input[..], 1).unwrap_err();
+        assert!(matches!(
+            error,
+            TensorReadError::IndexOutOfBoundError { .. }
+        ));
+    }
+    let input = b"1 254 1.0\n";
+    let tensor = tensor::COOTensor::<u8, f32>::read_from_frostt(&mut input.as_slice(), 1).unwrap();
+    assert_eq!(tensor.shape()[1].range(), 0..254);
+
+    let input = b"# Nothing here\n";
+    assert!(tensor::COOTensor::<u32, f32>::read_from_frostt(&mut input.as_slice(), 1).is_err());
+}
+
+#[test]
+fn test_frostt_round_trip() {
+    let mut input_file = File::open("data/tensors/3D_12031.tns").unwrap();
+    let tensor = tensor::COOTensor::<u32, f32>::read_from_text(&mut input_file).unwrap();
+    let mut output_buffer = Vec::new();
+    tensor.write_to_frostt(&mut output_buffer, 1).unwrap();
+    assert!(!str::from_utf8(&output_buffer).unwrap().contains('#'));
+
+    let output =
+        tensor::COOTensor::<u32, f32>::read_from_frostt(&mut output_buffer.as_slice(), 1).unwrap();
+    assert_eq!(output.ndim(), tensor.ndim());
+    for (output_axis, axis) in output.shape().iter().zip(tensor.shape().iter()) {
+        assert_eq!(output_axis.lower(), 0);
+        assert!(output_axis.len() <= axis.len());
+    }
+    let expected_indices =
+        ndarray::Array2::from_shape_fn(tensor.raw_parts().indices.dim(), |(i, j)| {
+            tensor.raw_parts().indices[(i, j)] - tensor.shape()[j].lower()
+        });
+    assert_eq!(output.raw_parts().indices, expected_indices);
+    assert_eq!(output.raw_parts().values, tensor.raw_parts().values);
+
+    // Zero-based FROSTT files are read the same way.
+    let mut output_buffer = Vec::new();
+    tensor.write_to_frostt(&mut output_buffer, 0).unwrap();
+    let zero_based =
+        tensor::COOTensor::<u32, f32>::read_from_frostt(&mut output_buffer.as_slice(), 0).unwrap();
+    assert_eq!(zero_based.raw_parts().indices, expected_indices);
+}
+
+#[test]
+fn test_semi_sparse_round_trip() {
+    use pattie::algos::matrix::CreateRandomDenseMatrix;
+    use pattie::algos::tensor::{CreateRandomCOOTensor, SortCOOTensor};
+    use pattie::algos::tensor_matrix::SemiCOOTensorMulDenseMatrix;
+    use pattie::structs::axis::AxisBuilder;
+
+    let tensor_shape = vec![
+        AxisBuilder::new().range(1..6).build(),
+        AxisBuilder::new().range(0..4).build(),
+        AxisBuilder::new().range(2..7).build(),
+    ];
+    let mut tensor = CreateRandomCOOTensor::<u32, f32>::new(&tensor_shape, 0.3, 0.0, 1.0)
+        .execute()
+        .unwrap();
+    let sort_order = vec![
+        tensor_shape[0].clone(),
+        tensor_shape[2].clone(),
+        tensor_shape[1].clone(),
+    ];
+    SortCOOTensor::new(&mut tensor, &sort_order).execute();
+    let matrix1 = CreateRandomDenseMatrix::<u32, f32>::new(
+        (
+            tensor_shape[1].clone(),
+            AxisBuilder::new().range(0..3).build(),
+        ),
+        0.0,
+        1.0,
+    )
+    .execute()
+    .unwrap();
+    let semi_sparse = SemiCOOTensorMulDenseMatrix::new(&tensor, &matrix1)
+        .execute()
+        .unwrap();
+    assert_eq!(semi_sparse.dense_axes().len(), 1);
+
+    let mut output_buffer = Vec::new();
+    semi_sparse
+        .write_to_text_with_formatter(&mut output_buffer, |value| format!("{:?}", value))
+        .unwrap();
+    let output =
+        tensor::COOTensor::<u32, f32>::read_from_text(&mut output_buffer.as_slice()).unwrap();
+
+    let positions = |tensor: &tensor::COOTensor<u32, f32>,
+                     axes: &[pattie::structs::axis::Axis<u32>]| {
+        axes.iter()
+            .map(|axis| tensor.shape().iter().position(|ax| ax == axis).unwrap())
+            .collect::<Vec<_>>()
+    };
+    let ranges = |tensor: &tensor::COOTensor<u32, f32>| {
+        tensor
+            .shape()
+            .iter()
+            .map(|axis| axis.range())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ranges(&output), ranges(&semi_sparse));
+    assert_eq!(
+        positions(&output, output.sparse_axes()),
+        positions(&semi_sparse, semi_sparse.sparse_axes())
+    );
+    assert_eq!(
+        positions(&output, output.dense_axes()),
+        positions(&semi_sparse, semi_sparse.dense_axes())
+    );
+    assert_eq!(output.raw_parts().indices, semi_sparse.raw_parts().indices);
+    assert_eq!(output.raw_parts().values, semi_sparse.raw_parts().values);
+}
+
+#[test]
+fn test_dense_axes_read() {
+    // Axis 1 and 0 are dense, in this order.
+    let input = b"3\n#@ dense: 1 0\n0 0 0\n2 3 2\n1\t1 2 3 4 5 6\n0\t6 5 4 3 2 1\n";
+    let tensor = tensor::COOTensor::<u32, f32>::read_from_text(&mut input.as_slice()).unwrap();
+    let shape = tensor.shape();
+    assert_eq!(tensor.sparse_axes(), &shape[2..]);
+    assert_eq!(tensor.dense_axes(), &[shape[1].clone(), shape[0].clone()]);
+    assert_eq!(tensor.raw_parts().indices, ndarray::arr2(&[[1], [0]]));
+    assert_eq!(
+        tensor.raw_parts().values,
+        ndarray::arr3(&[
+            [[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]],
+            [[6.0, 5.0], [4.0, 3.0], [2.0, 1.0]]
+        ])
+        .into_dyn()
+    );
+
+    let mut output_buffer = Vec::new();
+    tensor
+        .write_to_text_with_formatter(&mut output_buffer, |value| value.to_string())
+        .unwrap();
+    assert_eq!(
+        str::from_utf8(&output_buffer).unwrap(),
+        "3\n#@ dense: 1 0\n0 0 0\n2 3 2\n1\t1\t2\t3\t4\t5\t6\n0\t6\t5\t4\t3\t2\t1\n"
+    );
+
+    let input = b"2\n#@ dense: 0 2\n0 0\n2 2\n";
+    let error = tensor::COOTensor::<u32, f32>::read_from_text(&mut input.as_slice()).unwrap_err();
+    assert!(matches!(
+        error,
+        TensorReadError::HeaderError { line: 2, .. }
+    ));
+
+    let input = b"2\n0 0\n2 2\n1 1 2.0\n#@ dense: 1\n";
+    let error = tensor::COOTensor::<u32, f32>::read_from_text(&mut input.as_slice()).unwrap_err();
+    assert!(matches!(
+        error,
+        TensorReadError::HeaderError { line: 5, .. }
+    ));
+
+    // Each line must contain the whole block.
+    let input = b"2\n#@ dense: 1\n0 0\n2 2\n1 1.0\n2.0\n";
+    let error = tensor::COOTensor::<u32, f32>::read_from_text(&mut input.as_slice()).unwrap_err();
+    assert!(matches!(error, TensorReadError::TokenizeError { .. }));
+}
@@ -0,0 +1,89 @@
+#![cfg(test)]
+
+use pattie::io::TensorReadError;
+use pattie::structs::tensor::COOTensor;
+use pattie::traits::{RawParts, Tensor};
+use std::fs;
+
+fn read_parallel(input: &[u8], chunk_size: usize) -> Result<COOTensor<u32, f32>, TensorReadError> {
+    COOTensor::read_from_text_parallel_with_parser(&mut &input[..], chunk_size, |value| {
+        value.parse().ok()
+    })
+}
+
+fn assert_same_tensor(a: &COOTensor<u32, f32>, b: &COOTensor<u32, f32>) {
+    assert_eq!(a.name(), b.name());
+    let describe = |tensor: &COOTensor<u32, f32>| {
+        tensor
+            .shape()
+            .iter()
+            .map(|axis| (axis.range(), axis.label().map(str::to_owned)))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(describe(a), describe(b));
+    assert_eq!(
+        a.sparse_sort_order().is_some(),
+        b.sparse_sort_order().is_some()
+    );
+    assert_eq!(a.raw_parts().indices, b.raw_parts().indices);
+    assert_eq!(a.raw_parts().values, b.raw_parts().values);
+}
+
+#[test]
+fn test_parallel_read_matches_sequential() {
+    for filename in [
+        "data/tensors/3d_7.tns",
+        "data/tensors/3d_8.tns",
+        "data/tensors/3D_12031.tns",
+        "data/tensors/3d_dense.tns",
+        "data/tensors/3d-24.tns",
+        "data/tensors/4d_3_16.tns",
+    ] {
+        let input = fs::read(filename).unwrap();
+        let expected = COOTensor::<u32, f32>::read_from_text(&mut input.as_slice()).unwrap();
+        for chunk_size in [1, 7, 64, 4096, usize::MAX / 2] {
+            let output = read_parallel(&input, chunk_size).unwrap();
+            assert_same_tensor(&output, &expected);
+        }
+        let output = COOTensor::<u32, f32>::read_from_text_parallel(&mut input.as_slice()).unwrap();
+        assert_same_tensor(&output, &expected);
+    }
+}
+
+#[test]
+fn test_parallel_read_header() {
+    let input =
+        b"#@ name: first\n2\n0 0\n2 2\n0 1 1.0\r\n#@ axis 1: y\n1 0 2.0\n#@ name: last\n1 1 3.0";
+    for chunk_size in [1, 8, 1024] {
+        let tensor = read_parallel(input, chunk_size).unwrap();
+        assert_eq!(tensor.name(), Some("last"));
+        assert_eq!(tensor.shape()[0].label(), None);
+        assert_eq!(tensor.shape()[1].label(), Some("y"));
+        assert_eq!(tensor.num_non_zeros(), 3);
+    }
+
+    let input = b"2\n0 0\n2 2\n";
+    let tensor = read_parallel(input, 1).unwrap();
+    assert_eq!(tensor.num_non_zeros(), 0);
+    assert!(tensor.is_coalesced());
+}
+
+#[test]
+fn test_parallel_read_errors() {
+    let inputs: [&[u8]; 7] = [
+        b"2\n0 0\n2 2\n0 1 1.0\n1 1 2.0\n1 x 3.0\n0 0 4.0\n",
+        b"2\n0 0\n2 2\n0 1 1.0\n1 1 2.0\n0 0 4.0\n1 2 3.0\n",
+        b"2\n0 0\n2 2\n0 1 1.0\n1 1 2.0\n0 0 4.0\n1 1\n",
+        b"2\n0 0\n2 2\n0 1 1.0\n1 1 2.0\n#@ axis 2: z\n0 0 1.0\n",
+        b"2\n0 0\n2 2\n0 1 1.0\n1 1 2.0\n0 0 abc\n0 0 x\n",
+        b"2\n0 0\n2 2\n0 1 1.0\n1 1 2.0\n0 0 4.0\n#@ dense: 1\n",
+        b"2\n0 0\n2 2\n0 1 1.0\n#@ dense: 1\n1 1 2.0\n0 0 4.0\n#@ dense: 0\n",
+    ];
+    for input in inputs {
+        let expected = COOTensor::<u32, f32>::read_from_text(&mut &input[..]).unwrap_err();
+        for chunk_size in [1, 5, 1024] {
+            let error = read_parallel(input, chunk_size).unwrap_err();
+            assert_eq!(error.to_string(), expected.to_string());
+        }
+    }
+}
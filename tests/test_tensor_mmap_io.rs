@@ -0,0 +1,134 @@
+#![cfg(test)]
+
+use ndarray::{Array2, Array3};
+use pattie::algos::tensor::SortCOOTensor;
+use pattie::algos::tensor_matrix::COOTensorMulDenseMatrix;
+use pattie::io::{BinaryReadError, MappedCOOTensor};
+use pattie::structs::axis::{Axis, AxisBuilder};
+use pattie::structs::tensor::{COOTensor, COOTensorInner};
+use pattie::structs::vec::{smallvec, SmallVec};
+use pattie::traits::{RawParts, Tensor};
+use std::fs::File;
+use std::io::{Seek, SeekFrom, Write};
+use std::ops::Range;
+
+fn read_tensor() -> COOTensor<u32, f32> {
+    let mut input_file = File::open("data/tensors/3D_12031.tns").unwrap();
+    COOTensor::read_from_text(&mut input_file).unwrap()
+}
+
+fn write_temp_file(tensor: &COOTensor<u32, f32>) -> File {
+    let mut file = tempfile::tempfile().unwrap();
+    tensor.write_to_binary(&mut file).unwrap();
+    file.seek(SeekFrom::Start(0)).unwrap();
+    file
+}
+
+fn ranges(shape: &[Axis<u32>]) -> Vec<Range<u32>> {
+    shape.iter().map(Axis::range).collect()
+}
+
+/// A dense matrix, whose rows are on `common_axis`.
+fn dense_matrix(common_axis: &Axis<u32>) -> COOTensor<u32, f32> {
+    let free_axis = AxisBuilder::new().range(0..4).build();
+    unsafe {
+        COOTensor::from_raw_parts(COOTensorInner {
+            name: None,
+            shape: smallvec![common_axis.clone(), free_axis.clone()],
+            sparse_axes: SmallVec::new(),
+            dense_axes: smallvec![common_axis.clone(), free_axis],
+            indices: Array2::zeros((1, 0)),
+            values: Array3::from_shape_fn((1, common_axis.len(), 4), |(_, i, j)| {
+                (i * 4 + j) as f32
+            })
+            .into_dyn(),
+            sparse_is_sorted: true,
+            sparse_sort_order: SmallVec::new(),
+            sparse_is_coalesced: true,
+        })
+    }
+}
+
+#[test]
+fn test_mmap_view() {
+    let mut tensor = read_tensor();
+    *tensor.name_mut() = Some("mapped".to_owned());
+    let file = write_temp_file(&tensor);
+
+    // # Safety
+    // The temporary file is not modified while mapped.
+    let mapped = unsafe { MappedCOOTensor::<u32, f32>::map(&file) }.unwrap();
+    let view = mapped.view();
+    assert_eq!(view.name(), Some("mapped"));
+    assert_eq!(ranges(view.shape()), ranges(tensor.shape()));
+    assert_eq!(view.sparse_axes(), view.shape());
+    assert_eq!(view.sparse_sort_order(), None);
+    assert_eq!(view.num_blocks(), tensor.num_blocks());
+    assert_eq!(view.indices(), tensor.raw_parts().indices);
+    assert_eq!(view.values(), tensor.raw_parts().values);
+
+    let owned = view.to_owned();
+    assert_eq!(owned.name(), Some("mapped"));
+    assert_eq!(owned.raw_parts().indices, tensor.raw_parts().indices);
+    assert_eq!(owned.raw_parts().values, tensor.raw_parts().values);
+}
+
+#[test]
+fn test_mmap_mul_dense() {
+    let mut tensor = read_tensor();
+    let sort_order = tensor.shape().to_vec();
+    SortCOOTensor::new(&mut tensor, &sort_order).execute();
+    let file = write_temp_file(&tensor);
+    // # Safety
+    // The temporary file is not modified while mapped.
+    let mapped = unsafe { MappedCOOTensor::<u32, f32>::map(&file) }.unwrap();
+    let view = mapped.view();
+    assert_eq!(view.sparse_sort_order(), Some(view.shape()));
+
+    let matrix = dense_matrix(&tensor.shape()[2]);
+    let expected = COOTensorMulDenseMatrix::new(&tensor, &matrix)
+        .execute()
+        .unwrap();
+    let matrix = dense_matrix(&view.shape()[2]);
+    let actual = COOTensorMulDenseMatrix::new(view, &matrix)
+        .execute()
+        .unwrap();
+    assert_eq!(ranges(actual.shape()), ranges(expected.shape()));
+    assert_eq!(actual.raw_parts().indices, expected.raw_parts().indices);
+    assert_eq!(actual.raw_parts().values, expected.raw_parts().values);
+}
+
+#[test]
+fn test_mmap_errors() {
+    let tensor = read_tensor();
+
+    let file = write_temp_file(&tensor);
+    // # Safety
+    // The temporary file is not modified while mapped.
+    let result = unsafe { MappedCOOTensor::<u32, f64>::map(&file) };
+    assert!(matches!(
+        result,
+        Err(BinaryReadError::TypeError { what: "value", .. })
+    ));
+
+    // Truncate the value array.
+    let mut file = write_temp_file(&tensor);
+    let len = file.metadata().unwrap().len();
+    file.set_len(len - 4).unwrap();
+    let result = unsafe { MappedCOOTensor::<u32, f32>::map(&file) };
+    assert!(matches!(result, Err(BinaryReadError::IOError(_))));
+
+    // Corrupt the first index.
+    let mut buffer = Vec::new();
+    tensor.write_to_binary(&mut buffer).unwrap();
+    let indices_offset = u64::from_le_bytes(buffer[32..40].try_into().unwrap()) as usize;
+    buffer[indices_offset..indices_offset + 4].copy_from_slice(&1000u32.to_le_bytes());
+    file.set_len(0).unwrap();
+    file.seek(SeekFrom::Start(0)).unwrap();
+    file.write_all(&buffer).unwrap();
+    let result = unsafe { MappedCOOTensor::<u32, f32>::map(&file) };
+    assert!(matches!(
+        result,
+        Err(BinaryReadError::IndexOutOfBoundError { block: 0 })
+    ));
+}
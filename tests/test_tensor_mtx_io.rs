@@ -0,0 +1,230 @@
+#![cfg(test)]
+
+mod common;
+
+use common::to_array2;
+use ndarray::array;
+use num::Complex;
+use pattie::io::{MatrixMarketSymmetry, TensorReadError};
+use pattie::structs::tensor::COOTensor;
+use pattie::traits::{MatrixMarketScalar, RawParts, Tensor};
+
+fn read<VT: MatrixMarketScalar>(input: &str) -> Result<COOTensor<u32, VT>, TensorReadError> {
+    COOTensor::read_from_matrix_market(&mut input.as_bytes())
+}
+
+fn write<VT: MatrixMarketScalar>(
+    tensor: &COOTensor<u32, VT>,
+    symmetry: MatrixMarketSymmetry,
+) -> std::io::Result<String> {
+    let mut buffer = Vec::new();
+    tensor.write_to_matrix_market(&mut buffer, symmetry)?;
+    Ok(String::from_utf8(buffer).unwrap())
+}
+
+#[test]
+fn test_mtx_read_coordinate() {
+    let input = "%%MatrixMarket matrix coordinate real general
+% A comment
+
+2 3 3
+1 1 1.5
+2 3 -2
+1 3 3e1
+";
+    let tensor = read::<f64>(input).unwrap();
+    assert_eq!(tensor.shape()[0].range(), 0..2);
+    assert_eq!(tensor.shape()[1].range(), 0..3);
+    assert!(tensor.dense_axes().is_empty());
+    assert_eq!(tensor.num_blocks(), 3);
+    assert_eq!(tensor.raw_parts().indices, array![[0, 0], [1, 2], [0, 2]]);
+    assert_eq!(
+        to_array2(&tensor),
+        array![[1.5, 0.0, 30.0], [0.0, 0.0, -2.0]]
+    );
+
+    let input = "%%MatrixMarket matrix coordinate pattern general
+2 2 2
+1 2
+2 1
+";
+    let tensor = read::<u32>(input).unwrap();
+    assert_eq!(to_array2(&tensor), array![[0, 1], [1, 0]]);
+}
+
+#[test]
+fn test_mtx_read_symmetry() {
+    let input = "%%MatrixMarket matrix coordinate real symmetric
+3 3 4
+1 1 1.0
+2 1 2.0
+3 2 3.0
+3 3 4.0
+";
+    let tensor = read::<f32>(input).unwrap();
+    assert_eq!(tensor.num_blocks(), 6);
+    assert_eq!(
+        to_array2(&tensor),
+        array![[1.0, 2.0, 0.0], [2.0, 0.0, 3.0], [0.0, 3.0, 4.0]]
+    );
+
+    let input = "%%MatrixMarket matrix coordinate integer skew-symmetric
+3 3 2
+2 1 5
+3 1 -7
+";
+    let tensor = read::<i32>(input).unwrap();
+    assert_eq!(
+        to_array2(&tensor),
+        array![[0, -5, 7], [5, 0, 0], [-7, 0, 0]]
+    );
+
+    let input = "%%MatrixMarket matrix coordinate complex hermitian
+2 2 2
+1 1 1.0 0.0
+2 1 2.0 3.0
+";
+    let tensor = read::<Complex<f64>>(input).unwrap();
+    assert_eq!(
+        to_array2(&tensor),
+        array![
+            [Complex::new(1.0, 0.0), Complex::new(2.0, -3.0)],
+            [Complex::new(2.0, 3.0), Complex::new(0.0, 0.0)]
+        ]
+    );
+}
+
+#[test]
+fn test_mtx_read_array() {
+    let input = "%%MatrixMarket matrix array real general
+2 3
+1
+2
+3
+4
+5
+6
+";
+    let tensor = read::<f64>(input).unwrap();
+    assert!(tensor.sparse_axes().is_empty());
+    assert_eq!(tensor.num_blocks(), 1);
+    assert_eq!(to_array2(&tensor), array![[1.0, 3.0, 5.0], [2.0, 4.0, 6.0]]);
+
+    let input = "%%MatrixMarket matrix array real skew-symmetric
+3 3
+1
+2
+3
+";
+    let tensor = read::<f64>(input).unwrap();
+    assert_eq!(
+        to_array2(&tensor),
+        array![[0.0, -1.0, -2.0], [1.0, 0.0, -3.0], [2.0, 3.0, 0.0]]
+    );
+}
+
+#[test]
+fn test_mtx_read_errors() {
+    let result = read::<f64>("%%MatrixMarket matrix coordinate real diagonal\n");
+    assert!(matches!(
+        result,
+        Err(TensorReadError::HeaderError {
+            line: 1,
+            column: 39,
+            ..
+        })
+    ));
+
+    let result = read::<f64>("%%MatrixMarket matrix coordinate real symmetric\n2 3 0\n");
+    assert!(matches!(
+        result,
+        Err(TensorReadError::HeaderError { line: 2, .. })
+    ));
+
+    let result = read::<u32>("%%MatrixMarket matrix coordinate real general\n2 2 1\n1 1 0.5\n");
+    assert!(matches!(
+        result,
+        Err(TensorReadError::ValueError {
+            line: 3,
+            column: 5,
+            ..
+        })
+    ));
+
+    let result = read::<f64>("%%MatrixMarket matrix coordinate real general\n2 2 1\n1 3 1\n");
+    assert!(matches!(
+        result,
+        Err(TensorReadError::IndexOutOfBoundError { line: 3, column: 3 })
+    ));
+
+    let result = read::<f64>("%%MatrixMarket matrix coordinate real general\n2 2 2\n1 1 1\n");
+    assert!(matches!(
+        result,
+        Err(TensorReadError::TokenizeError { line: 4, .. })
+    ));
+
+    let result =
+        read::<f64>("%%MatrixMarket matrix coordinate real general\n2 2 1\n1 1 1\n2 2 2\n");
+    assert!(matches!(
+        result,
+        Err(TensorReadError::TokenizeError {
+            line: 4,
+            column: 1,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_mtx_write() {
+    let input = "%%MatrixMarket matrix coordinate real symmetric
+3 3 4
+1 1 1e0
+2 1 2e0
+3 2 3e0
+3 3 4e0
+";
+    let tensor = read::<f64>(input).unwrap();
+    assert_eq!(
+        write(&tensor, MatrixMarketSymmetry::Symmetric).unwrap(),
+        input
+    );
+
+    let output = write(&tensor, MatrixMarketSymmetry::General).unwrap();
+    assert!(output.starts_with("%%MatrixMarket matrix coordinate real general\n3 3 6\n"));
+    let round_trip = read::<f64>(&output).unwrap();
+    assert_eq!(to_array2(&round_trip), to_array2(&tensor));
+
+    assert_eq!(
+        write(&tensor, MatrixMarketSymmetry::SkewSymmetric)
+            .unwrap_err()
+            .kind(),
+        std::io::ErrorKind::InvalidInput
+    );
+}
+
+#[test]
+fn test_mtx_write_array() {
+    let tensor = COOTensor::<u32, i64>::from_ndarray(array![[1, -2], [2, 0]]);
+    assert_eq!(
+        write(&tensor, MatrixMarketSymmetry::General).unwrap(),
+        "%%MatrixMarket matrix array integer general\n2 2\n1\n2\n-2\n0\n"
+    );
+    assert_eq!(
+        write(&tensor, MatrixMarketSymmetry::Symmetric)
+            .unwrap_err()
+            .kind(),
+        std::io::ErrorKind::InvalidInput
+    );
+
+    let tensor = COOTensor::<u32, i64>::from_ndarray(array![[0, -2], [2, 0]]);
+    let output = write(&tensor, MatrixMarketSymmetry::SkewSymmetric).unwrap();
+    assert_eq!(
+        output,
+        "%%MatrixMarket matrix array integer skew-symmetric\n2 2\n2\n"
+    );
+    assert_eq!(
+        to_array2(&read::<i64>(&output).unwrap()),
+        array![[0, -2], [2, 0]]
+    );
+}
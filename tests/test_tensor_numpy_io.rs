@@ -0,0 +1,196 @@
+#![cfg(test)]
+
+use ndarray::{array, Array2, ArrayD, ArrayView1};
+use npyz::{Order, WriteOptions, WriterBuilder};
+use pattie::io::NumpyReadError;
+use pattie::structs::axis::{Axis, AxisBuilder};
+use pattie::structs::tensor::COOTensor;
+use pattie::traits::{RawParts, Tensor};
+use std::fs::File;
+use std::io::Cursor;
+use std::ops::Range;
+
+fn ranges(shape: &[Axis<u32>]) -> Vec<Range<u32>> {
+    shape.iter().map(Axis::range).collect()
+}
+
+#[test]
+fn test_npz_round_trip() {
+    let mut input_file = File::open("data/tensors/3D_12031.tns").unwrap();
+    let tensor = COOTensor::<u32, f32>::read_from_text(&mut input_file).unwrap();
+    let input = "#@ axis 1: time\n".to_owned()
+        + &std::fs::read_to_string("data/tensors/3D_12031.tns").unwrap();
+    let labeled = COOTensor::<u32, f32>::read_from_text(&mut input.as_bytes()).unwrap();
+
+    let mut buffer = Cursor::new(Vec::new());
+    labeled.write_to_npz(&mut buffer).unwrap();
+    buffer.set_position(0);
+    let output = COOTensor::<u32, f32>::read_from_npz(&mut buffer).unwrap();
+
+    assert_eq!(ranges(output.shape()), ranges(tensor.shape()));
+    let labels = output.shape().iter().map(Axis::label).collect::<Vec<_>>();
+    assert_eq!(labels, [None, Some("time"), None]);
+    assert_eq!(output.sparse_axes(), output.shape());
+    assert_eq!(output.sparse_sort_order(), None);
+    assert_eq!(output.raw_parts().indices, tensor.raw_parts().indices);
+    assert_eq!(output.raw_parts().values, tensor.raw_parts().values);
+}
+
+#[test]
+fn test_npz_dense_blocks() {
+    let tensor = COOTensor::<u32, f64>::from_ndarray(array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    let mut buffer = Cursor::new(Vec::new());
+    tensor.write_to_npz(&mut buffer).unwrap();
+    buffer.set_position(0);
+    let output = COOTensor::<u32, f64>::read_from_npz(&mut buffer).unwrap();
+
+    assert!(output.dense_axes().is_empty());
+    assert_eq!(output.num_blocks(), 6);
+    assert_eq!(
+        output.raw_parts().indices,
+        array![[0, 0], [0, 1], [0, 2], [1, 0], [1, 1], [1, 2]]
+    );
+    assert_eq!(
+        output.raw_parts().values,
+        array![1.0, 2.0, 3.0, 4.0, 5.0, 6.0].into_dyn()
+    );
+}
+
+#[test]
+fn test_npz_errors() {
+    let tensor = COOTensor::<u32, f64>::from_ndarray(array![[1.0, 2.0], [3.0, 4.0]]);
+    let mut buffer = Cursor::new(Vec::new());
+    tensor.write_to_npz(&mut buffer).unwrap();
+
+    buffer.set_position(0);
+    let result = COOTensor::<u32, f32>::read_from_npz(&mut buffer);
+    assert!(matches!(
+        result,
+        Err(NumpyReadError::TypeError {
+            array: "values",
+            ..
+        })
+    ));
+
+    let mut buffer = Cursor::new(Vec::new());
+    let mut npz = npyz::npz::NpzWriter::new(&mut buffer);
+    let mut writer = npz
+        .array::<u32>("shape_upper", Default::default())
+        .unwrap()
+        .default_dtype()
+        .shape(&[2])
+        .begin_nd()
+        .unwrap();
+    writer.extend([2, 2]).unwrap();
+    writer.finish().unwrap();
+    npz.zip_writer().finish().unwrap();
+    drop(npz);
+    buffer.set_position(0);
+    let result = COOTensor::<u32, f64>::read_from_npz(&mut buffer);
+    assert!(matches!(
+        result,
+        Err(NumpyReadError::MissingArrayError("indices"))
+    ));
+}
+
+#[test]
+fn test_npz_negative_index() {
+    let mut buffer = Cursor::new(Vec::new());
+    let mut npz = npyz::npz::NpzWriter::new(&mut buffer);
+    for (name, shape, data) in [
+        ("shape_upper", &[1][..], vec![3]),
+        ("indices", &[1, 1][..], vec![-5]),
+    ] {
+        let mut writer = npz
+            .array::<i32>(name, Default::default())
+            .unwrap()
+            .default_dtype()
+            .shape(shape)
+            .begin_nd()
+            .unwrap();
+        writer.extend(data).unwrap();
+        writer.finish().unwrap();
+    }
+    let mut writer = npz
+        .array::<f64>("values", Default::default())
+        .unwrap()
+        .default_dtype()
+        .shape(&[1])
+        .begin_nd()
+        .unwrap();
+    writer.push(&1.0).unwrap();
+    writer.finish().unwrap();
+    npz.zip_writer().finish().unwrap();
+    drop(npz);
+
+    buffer.set_position(0);
+    let result = COOTensor::<i32, f64>::read_from_npz(&mut buffer);
+    assert!(matches!(
+        result,
+        Err(NumpyReadError::IndexOutOfBoundError { element: 0 })
+    ));
+}
+
+#[test]
+fn test_npy_round_trip() {
+    let array = ArrayD::from_shape_fn(vec![2, 3, 4], |index| {
+        (index[0] * 100 + index[1] * 10 + index[2]) as i64
+    });
+    let tensor = COOTensor::<u32, i64>::from_ndarray(array.clone());
+    let mut buffer = Vec::new();
+    tensor.write_to_npy(&mut buffer).unwrap();
+    assert!(buffer.starts_with(b"\x93NUMPY"));
+
+    let output = COOTensor::<u32, i64>::read_from_npy(&mut buffer.as_slice()).unwrap();
+    assert_eq!(ranges(output.shape()), [0..2, 0..3, 0..4]);
+    assert!(output.sparse_axes().is_empty());
+    assert_eq!(
+        output.raw_parts().values,
+        array.insert_axis(ndarray::Axis(0))
+    );
+
+    let mut input_file = File::open("data/tensors/3D_12031.tns").unwrap();
+    let sparse = COOTensor::<u32, f32>::read_from_text(&mut input_file).unwrap();
+    let error = sparse.write_to_npy(&mut Vec::new()).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_npy_fortran_order() {
+    let mut buffer = Vec::new();
+    let mut writer = WriteOptions::new()
+        .default_dtype()
+        .shape(&[2, 3])
+        .order(Order::Fortran)
+        .writer(&mut buffer)
+        .begin_nd()
+        .unwrap();
+    writer.extend([1.0, 4.0, 2.0, 5.0, 3.0, 6.0]).unwrap();
+    writer.finish().unwrap();
+
+    let output = COOTensor::<u32, f64>::read_from_npy(&mut buffer.as_slice()).unwrap();
+    let expected: Array2<f64> = array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+    assert_eq!(
+        output.raw_parts().values,
+        expected.insert_axis(ndarray::Axis(0)).into_dyn()
+    );
+}
+
+#[test]
+fn test_npy_blocks() {
+    let x = AxisBuilder::new().range(0..2).build();
+    let y = AxisBuilder::new().range(0..3).build();
+    let mut tensor = COOTensor::<u32, i64>::zeros(&[x, y], &[true, true]);
+    let mut buffer = Vec::new();
+    tensor.write_to_npy(&mut buffer).unwrap();
+    let output = COOTensor::<u32, i64>::read_from_npy(&mut buffer.as_slice()).unwrap();
+    assert_eq!(output.to_ndarray(), Array2::<i64>::zeros((2, 3)).into_dyn());
+
+    let block = array![[1, 2, 3], [4, 5, 6]].into_dyn();
+    tensor.push_block(ArrayView1::from(&[]), block.view());
+    tensor.push_block(ArrayView1::from(&[]), block.view());
+    let mut buffer = Vec::new();
+    tensor.write_to_npy(&mut buffer).unwrap();
+    let output = COOTensor::<u32, i64>::read_from_npy(&mut buffer.as_slice()).unwrap();
+    assert_eq!(output.to_ndarray(), block * 2);
+}
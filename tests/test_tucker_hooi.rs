@@ -0,0 +1,110 @@
+#![cfg(test)]
+
+mod common;
+
+use common::{load_tensor, to_array2};
+use ndarray::{Array2, Array3};
+use ndarray_rand::rand_distr::Uniform;
+use ndarray_rand::RandomExt;
+use pattie::algos::decomposition::COOTensorTuckerHOOI;
+use pattie::structs::axis::AxisBuilder;
+use pattie::structs::tensor::{COOTensor, TuckerTensor};
+use pattie::traits::{RawParts, Tensor};
+
+fn reconstruct(
+    core: &Array3<f64>,
+    factors: &[Array2<f64>],
+    shape: (usize, usize, usize),
+) -> Array3<f64> {
+    Array3::from_shape_fn(shape, |(i, j, k)| {
+        core.indexed_iter()
+            .map(|((a, b, c), &value)| {
+                value * factors[0][(i, a)] * factors[1][(j, b)] * factors[2][(k, c)]
+            })
+            .sum()
+    })
+}
+
+fn tucker_parts(tucker: &TuckerTensor<u32, f64>) -> (Array3<f64>, Vec<Array2<f64>>) {
+    let core = tucker.core();
+    let core_shape = core.shape();
+    let core_values = Array3::from_shape_vec(
+        (
+            core_shape[0].len(),
+            core_shape[1].len(),
+            core_shape[2].len(),
+        ),
+        core.raw_parts().values.iter().cloned().collect(),
+    )
+    .unwrap();
+    let factors = tucker
+        .shape()
+        .iter()
+        .map(|axis| to_array2(tucker.factor(axis).unwrap()))
+        .collect();
+    (core_values, factors)
+}
+
+#[test]
+fn test_tucker_hooi_exact() {
+    // Each unfolding is taller than the product of the other ranks.
+    check_tucker_hooi_exact([6, 7, 8], [2, 3, 2]);
+    // Each unfolding is wider than its number of rows.
+    check_tucker_hooi_exact([4, 5, 6], [3, 3, 2]);
+}
+
+/// Build a fully sparse tensor with multilinear rank `ranks`, then check that it is recovered exactly.
+fn check_tucker_hooi_exact(lens: [u32; 3], ranks: [usize; 3]) {
+    let shape = lens.map(|len| AxisBuilder::new().range(0..len).build());
+    let dense_shape = (lens[0] as usize, lens[1] as usize, lens[2] as usize);
+    let core = Array3::random((ranks[0], ranks[1], ranks[2]), Uniform::new(-1.0, 1.0));
+    let factors = shape
+        .iter()
+        .zip(ranks)
+        .map(|(axis, rank)| Array2::random((axis.len(), rank), Uniform::new(-1.0, 1.0)))
+        .collect::<Vec<_>>();
+    let expected = reconstruct(&core, &factors, dense_shape);
+    let mut tensor = COOTensor::<u32, f64>::zeros(&shape, &[false; 3]);
+    for ((i, j, k), &value) in expected.indexed_iter() {
+        let index = ndarray::arr1(&[i as u32, j as u32, k as u32]);
+        tensor.push_block(index.view(), ndarray::arr0(value).into_dyn().view());
+    }
+
+    for multi_thread in [false, true] {
+        let mut task = COOTensorTuckerHOOI::new(&tensor, &ranks, 20, 1e-12);
+        task.multi_thread = multi_thread;
+        let tucker = task.execute().unwrap();
+        assert_eq!(tucker.shape(), tensor.shape());
+
+        let (core, factors) = tucker_parts(&tucker);
+        assert_eq!(core.shape(), &ranks);
+        for (factor, rank) in factors.iter().zip(ranks) {
+            for a in 0..rank {
+                for b in 0..rank {
+                    let dot = (factor.column(a).to_owned() * factor.column(b)).sum();
+                    let expected_dot = if a == b { 1.0 } else { 0.0 };
+                    assert!((dot - expected_dot).abs() < 1e-9);
+                }
+            }
+        }
+
+        let residual = (&reconstruct(&core, &factors, dense_shape) - &expected)
+            .iter()
+            .map(|x| x * x)
+            .sum::<f64>()
+            .sqrt();
+        let norm = expected.iter().map(|x| x * x).sum::<f64>().sqrt();
+        assert!(residual / norm < 1e-9);
+    }
+}
+
+#[test]
+fn test_tucker_hooi_invalid_ranks() {
+    let tensor = load_tensor("3D_12031.tns");
+    assert!(COOTensorTuckerHOOI::new(&tensor, &[2, 2], 10, 1e-6)
+        .execute()
+        .is_err());
+    assert!(COOTensorTuckerHOOI::new(&tensor, &[2, 0, 2], 10, 1e-6)
+        .execute()
+        .is_err());
+}